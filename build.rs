@@ -0,0 +1,7 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["proto/rex/v1/rex.proto"], &["proto"])?;
+
+    Ok(())
+}
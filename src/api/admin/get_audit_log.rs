@@ -0,0 +1,60 @@
+use super::{APIError, AuthToken, record_audit};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "admin",
+    summary = "Read the administrator audit log",
+    responses(
+        (status = 200, description = "The audit log, newest entries first.", body = [AuditEntryV3])
+    ),
+    security(("oidc" = []))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/admin/audit")]
+async fn get_admin_audit_log_v3(
+    (state, token): (web::Data<GlobalState>, AuthToken),
+) -> Result<web::Json<Vec<AuditEntryV3>>, APIError> {
+    require_role!(token, "Administrator");
+
+    let mut entries = state.store.send(GetAuditEntries {}.trace()).await??;
+    entries.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+
+    record_audit(&state, &token, "AuditLog.Read", "audit".into()).await?;
+
+    Ok(web::Json(entries.into_iter().map(|e| e.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_admin_audit_log_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreAuditEntry {
+                id: 1,
+                principal_id: 2,
+                timestamp: chrono::Utc::now() - chrono::Duration::minutes(5),
+                action: "User.Remove".into(),
+                target: "user/00000000000000000000000000000003".into(),
+            }]
+        );
+
+        let content: Vec<AuditEntryV3> =
+            test_request!(GET "/api/v3/admin/audit" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0].id, "00000000000000000000000000000001");
+        assert_eq!(content[0].user_id, "00000000000000000000000000000002");
+        assert_eq!(content[0].action, "User.Remove");
+
+        let content: Vec<AuditEntryV3> =
+            test_request!(GET "/api/v3/admin/audit" => OK with content | state = state);
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[0].action, "AuditLog.Read");
+    }
+}
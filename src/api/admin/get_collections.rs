@@ -0,0 +1,116 @@
+use super::{APIError, AuthToken, record_audit};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "admin",
+    summary = "List all collections",
+    responses(
+        (status = 200, description = "Every collection known to the service.", body = [CollectionSummaryV3])
+    ),
+    security(("oidc" = []))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/admin/collections")]
+async fn get_admin_collections_v3(
+    (state, token): (web::Data<GlobalState>, AuthToken),
+) -> Result<web::Json<Vec<CollectionSummaryV3>>, APIError> {
+    require_role!(token, "Administrator");
+
+    // Every member of a collection holds their own entry for it, so we only
+    // keep the first entry we see for each collection ID.
+    let mut collections = BTreeMap::new();
+    for collection in state.store.send(GetAllCollections {}.trace()).await?? {
+        collections
+            .entry(collection.collection_id)
+            .or_insert(collection);
+    }
+
+    let mut summaries = Vec::with_capacity(collections.len());
+    for (cid, collection) in collections {
+        let owners = match state
+            .store
+            .send(GetRoleAssignments { collection_id: cid }.trace())
+            .await?
+        {
+            Ok(roles) => roles
+                .into_iter()
+                .filter(|r| r.role == Role::Owner)
+                .map(|r| format!("{:0>32x}", r.user_id))
+                .collect(),
+            Err(err) if err.code == 404 => vec![],
+            Err(err) => return Err(err),
+        };
+
+        let ideas = state
+            .store
+            .send(CountIdeas { collection: cid }.trace())
+            .await??;
+
+        summaries.push(CollectionSummaryV3 {
+            id: format!("{cid:0>32x}"),
+            name: collection.name,
+            owners,
+            ideas,
+        });
+    }
+
+    record_audit(&state, &token, "Collections.List", "collections".into()).await?;
+
+    Ok(web::Json(summaries))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_admin_collections_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 2,
+                    name: "Shared Collection".into(),
+                },
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 3,
+                    name: "Shared Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 2,
+                    role: Role::Owner,
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 3,
+                    role: Role::Viewer,
+                },
+                StoreIdea {
+                    id: 4,
+                    collection: 1,
+                    name: "Test Idea".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let content: Vec<CollectionSummaryV3> =
+            test_request!(GET "/api/v3/admin/collections" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0].id, "00000000000000000000000000000001");
+        assert_eq!(content[0].name, "Shared Collection");
+        assert_eq!(
+            content[0].owners,
+            vec!["00000000000000000000000000000002".to_string()]
+        );
+        assert_eq!(content[0].ideas, 1);
+    }
+}
@@ -0,0 +1,72 @@
+use super::CollectionFilter;
+use super::{APIError, AuthToken, record_audit};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "admin",
+    summary = "List the users with access to any collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The role assignments for the collection.", body = [RoleAssignmentV3])
+    ),
+    security(("oidc" = []))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/admin/collection/{collection}/users")]
+async fn get_admin_role_assignments_v3(
+    (info, state, token): (
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<web::Json<Vec<RoleAssignmentV3>>, APIError> {
+    require_role!(token, "Administrator");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+
+    let roles = state
+        .store
+        .send(GetRoleAssignments { collection_id: cid }.trace())
+        .await??;
+
+    record_audit(
+        &state,
+        &token,
+        "RoleAssignments.List",
+        format!("collection/{cid:0>32x}"),
+    )
+    .await?;
+
+    Ok(web::Json(roles.into_iter().map(|r| r.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_admin_role_assignments_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 2,
+                role: Role::Owner,
+            }]
+        );
+
+        let content: Vec<RoleAssignmentV3> = test_request!(GET "/api/v3/admin/collection/00000000000000000000000000000001/users" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(
+            content[0].user_id,
+            Some("00000000000000000000000000000002".into())
+        );
+        assert_eq!(content[0].role, "Owner".to_string());
+    }
+}
@@ -0,0 +1,67 @@
+use super::{APIError, AuthToken, record_audit};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "admin",
+    summary = "List all users",
+    responses(
+        (status = 200, description = "Every user known to the service.", body = [UserV3])
+    ),
+    security(("oidc" = []))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/admin/users")]
+async fn get_admin_users_v3(
+    (state, token): (web::Data<GlobalState>, AuthToken),
+) -> Result<web::Json<Vec<UserV3>>, APIError> {
+    require_role!(token, "Administrator");
+
+    let users = state.store.send(GetUsers {}.trace()).await??;
+
+    record_audit(&state, &token, "Users.List", "users".into()).await?;
+
+    Ok(web::Json(users.into_iter().map(|u| u.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_admin_users_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreUser {
+                    email_hash: 1,
+                    principal_id: 2,
+                    first_name: "Alice".into(),
+                },
+                StoreUser {
+                    email_hash: 3,
+                    principal_id: 4,
+                    first_name: "Bob".into(),
+                }
+            ]
+        );
+
+        let content: Vec<UserV3> =
+            test_request!(GET "/api/v3/admin/users" => OK with content | state = state);
+        assert_eq!(content.len(), 2);
+        assert!(content.iter().any(|u| u.first_name == "Alice"));
+        assert!(content.iter().any(|u| u.first_name == "Bob"));
+
+        let audit = state
+            .store
+            .send(GetAuditEntries {})
+            .await
+            .expect("the actor should have run")
+            .expect("the audit log should be available");
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].action, "Users.List");
+    }
+}
@@ -0,0 +1,83 @@
+mod get_audit_log;
+mod get_collections;
+mod get_role_assignments;
+mod get_users;
+mod remove_role_assignment;
+mod remove_user;
+mod store_role_assignment;
+
+use super::{APIError, AuthToken};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::web;
+use utoipa::OpenApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_users::get_admin_users_v3)
+        .service(remove_user::remove_admin_user_v3)
+        .service(get_collections::get_admin_collections_v3)
+        .service(get_role_assignments::get_admin_role_assignments_v3)
+        .service(store_role_assignment::store_admin_role_assignment_v3)
+        .service(remove_role_assignment::remove_admin_role_assignment_v3)
+        .service(get_audit_log::get_admin_audit_log_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_users::get_admin_users_v3,
+    remove_user::remove_admin_user_v3,
+    get_collections::get_admin_collections_v3,
+    get_role_assignments::get_admin_role_assignments_v3,
+    store_role_assignment::store_admin_role_assignment_v3,
+    remove_role_assignment::remove_admin_role_assignment_v3,
+    get_audit_log::get_admin_audit_log_v3,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct UserFilter {
+    user: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionFilter {
+    collection: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionUserFilter {
+    collection: String,
+    user: String,
+}
+
+/// Records an administrative action in the audit log once it has completed, or once it
+/// has failed for actions which may leave partial changes behind.
+#[tracing::instrument(err, skip(state, token))]
+async fn record_audit(
+    state: &GlobalState,
+    token: &AuthToken,
+    action: &str,
+    target: String,
+) -> Result<(), APIError> {
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    info!(
+        "Administrator {:0>32x} performed {} on {}",
+        uid, action, target
+    );
+
+    state
+        .store
+        .send(
+            StoreAuditEntry {
+                id: new_id(),
+                principal_id: uid,
+                timestamp: chrono::Utc::now(),
+                action: action.to_string(),
+                target,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use super::CollectionUserFilter;
+use super::{APIError, AuthToken, record_audit};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, delete, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "admin",
+    summary = "Remove a user's access to any collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("user" = String, Path, description = "The ID of the user.")
+    ),
+    responses(
+        (status = 204, description = "The role assignment was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = []))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[delete("/api/v3/admin/collection/{collection}/user/{user}")]
+async fn remove_admin_role_assignment_v3(
+    (info, state, token): (
+        web::Path<CollectionUserFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let tuid = parse_uuid!(info.user, "user ID");
+
+    state
+        .store
+        .send(
+            RemoveRoleAssignment {
+                collection_id: cid,
+                principal_id: tuid,
+            }
+            .trace(),
+        )
+        .await??;
+
+    record_audit(
+        &state,
+        &token,
+        "RoleAssignments.Remove",
+        format!("collection/{cid:0>32x}/user/{tuid:0>32x}"),
+    )
+    .await?;
+
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn remove_admin_role_assignment_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 2,
+                role: Role::Owner,
+            }]
+        );
+
+        test_request!(DELETE "/api/v3/admin/collection/00000000000000000000000000000001/user/00000000000000000000000000000002" => NO_CONTENT | state = state);
+
+        state
+            .store
+            .send(GetRoleAssignment {
+                collection_id: 1,
+                principal_id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("The role assignment should not exist anymore");
+    }
+}
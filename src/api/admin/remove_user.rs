@@ -0,0 +1,306 @@
+use super::UserFilter;
+use super::{APIError, AuthToken, record_audit};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, delete, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "admin",
+    summary = "Remove a user and their data",
+    params(
+        ("user" = String, Path, description = "The ID of the user.")
+    ),
+    responses(
+        (status = 204, description = "The user was removed.")
+    ),
+    security(("oidc" = []))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[delete("/api/v3/admin/user/{user}")]
+async fn remove_admin_user_v3(
+    (info, state, token): (web::Path<UserFilter>, web::Data<GlobalState>, AuthToken),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator");
+
+    let tuid = parse_uuid!(info.user, "user ID");
+
+    // The removal is audited even when it fails partway through, so that an administrator
+    // can see which users may still have data left behind and retry them.
+    let result = remove_user_data(&state, tuid).await;
+    let action = if result.is_ok() {
+        "User.Remove"
+    } else {
+        "User.Remove.Failed"
+    };
+    record_audit(&state, &token, action, format!("user/{tuid:0>32x}")).await?;
+    result?;
+
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
+}
+
+#[instrument(err, skip(state))]
+async fn remove_user_data(state: &GlobalState, tuid: u128) -> Result<(), APIError> {
+    let collections = match state
+        .store
+        .send(GetCollections { principal_id: tuid }.trace())
+        .await?
+    {
+        Ok(collections) => collections,
+        Err(err) if err.code == 404 => vec![],
+        Err(err) => return Err(err),
+    };
+
+    for collection in collections {
+        let cid = collection.collection_id;
+
+        remove_role_assignment(state, cid, tuid).await?;
+
+        state
+            .store
+            .send(
+                RemoveCollection {
+                    id: cid,
+                    principal_id: tuid,
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    // A user may hold roles on collections which they never listed, for example when their
+    // listing was removed separately, so those are removed as well.
+    for role in state
+        .store
+        .send(GetPrincipalRoleAssignments { principal_id: tuid }.trace())
+        .await??
+    {
+        remove_role_assignment(state, role.collection_id, tuid).await?;
+    }
+
+    for user in state
+        .store
+        .send(GetUsers {}.trace())
+        .await??
+        .into_iter()
+        .filter(|u| u.principal_id == tuid)
+    {
+        state
+            .store
+            .send(
+                RemoveUser {
+                    email_hash: user.email_hash,
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    Ok(())
+}
+
+async fn remove_role_assignment(
+    state: &GlobalState,
+    cid: u128,
+    tuid: u128,
+) -> Result<(), APIError> {
+    match state
+        .store
+        .send(
+            RemoveRoleAssignment {
+                collection_id: cid,
+                principal_id: tuid,
+            }
+            .trace(),
+        )
+        .await?
+    {
+        Ok(_) => {}
+        Err(err) if err.code == 404 => {}
+        Err(err) => return Err(err),
+    }
+
+    let remaining_members = match state
+        .store
+        .send(GetRoleAssignments { collection_id: cid }.trace())
+        .await?
+    {
+        Ok(roles) => roles.len(),
+        Err(err) if err.code == 404 => 0,
+        Err(err) => return Err(err),
+    };
+
+    // Ideas belong to the collection rather than the user, so we only remove them
+    // once nobody else has access to the collection.
+    if remaining_members == 0 {
+        let ideas = match state
+            .store
+            .send(
+                GetIdeas {
+                    collection: cid,
+                    is_completed: None,
+                    tag: None,
+                }
+                .trace(),
+            )
+            .await?
+        {
+            Ok(ideas) => ideas,
+            Err(err) if err.code == 404 => vec![],
+            Err(err) => return Err(err),
+        };
+
+        for idea in ideas {
+            state
+                .store
+                .send(
+                    RemoveIdea {
+                        collection: cid,
+                        id: idea.id,
+                    }
+                    .trace(),
+                )
+                .await??;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn remove_admin_user_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreUser {
+                    email_hash: 9,
+                    principal_id: 2,
+                    first_name: "Alice".into(),
+                },
+                StoreCollection {
+                    collection_id: 2,
+                    principal_id: 2,
+                    name: "My Ideas".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 2,
+                    principal_id: 2,
+                    role: Role::Owner,
+                },
+                StoreIdea {
+                    id: 3,
+                    collection: 2,
+                    name: "Private Idea".into(),
+                    ..Default::default()
+                },
+                StoreCollection {
+                    collection_id: 4,
+                    principal_id: 2,
+                    name: "Shared Ideas".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 4,
+                    principal_id: 2,
+                    role: Role::Contributor,
+                },
+                StoreRoleAssignment {
+                    collection_id: 4,
+                    principal_id: 5,
+                    role: Role::Owner,
+                },
+                StoreIdea {
+                    id: 6,
+                    collection: 4,
+                    name: "Shared Idea".into(),
+                    ..Default::default()
+                },
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 2,
+                    role: Role::Owner,
+                },
+                StoreIdea {
+                    id: 8,
+                    collection: 7,
+                    name: "Unlisted Idea".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        test_request!(DELETE "/api/v3/admin/user/00000000000000000000000000000002" => NO_CONTENT | state = state);
+
+        state
+            .store
+            .send(GetUser { email_hash: 9 })
+            .await
+            .expect("the actor should have run")
+            .expect_err("the user should have been removed");
+
+        state
+            .store
+            .send(GetIdea {
+                collection: 2,
+                id: 3,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("ideas in the user's private collection should have been removed");
+
+        state
+            .store
+            .send(GetIdea {
+                collection: 4,
+                id: 6,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("ideas in shared collections should be retained");
+
+        state
+            .store
+            .send(GetRoleAssignment {
+                collection_id: 4,
+                principal_id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("the user's role assignments should have been removed");
+
+        state
+            .store
+            .send(GetRoleAssignment {
+                collection_id: 7,
+                principal_id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("role assignments on unlisted collections should have been removed");
+
+        state
+            .store
+            .send(GetIdea {
+                collection: 7,
+                id: 8,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("ideas in unlisted collections with no members should have been removed");
+
+        let audit = state
+            .store
+            .send(GetAuditEntries {})
+            .await
+            .expect("the actor should have run")
+            .expect("the audit log should be available");
+        assert!(
+            audit.iter().any(|e| e.action == "User.Remove"
+                && e.target == "user/00000000000000000000000000000002")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use super::CollectionUserFilter;
+use super::{APIError, AuthToken, record_audit};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{put, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "admin",
+    summary = "Set a user's role on any collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("user" = String, Path, description = "The ID of the user.")
+    ),
+    request_body = RoleAssignmentV3,
+    responses(
+        (status = 200, description = "The role assignment which was stored.", body = RoleAssignmentV3),
+        (status = 400, description = "The request was not valid.", body = APIError)
+    ),
+    security(("oidc" = []))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[put("/api/v3/admin/collection/{collection}/user/{user}")]
+async fn store_admin_role_assignment_v3(
+    (info, assignment, state, token): (
+        web::Path<CollectionUserFilter>,
+        web::Json<RoleAssignmentV3>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<RoleAssignmentV3, APIError> {
+    require_role!(token, "Administrator");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let tuid = parse_uuid!(info.user, "user ID");
+
+    let role: Role = assignment.role.as_str().into();
+    if role == Role::Invalid {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "The role you provided is not recognized. Please use one of Owner, Contributor or Viewer.",
+        ));
+    }
+
+    // Repairing a role assignment requires the user to also have an entry for the collection,
+    // which we copy from any other member of the collection.
+    let collections = state.store.send(GetAllCollections {}.trace()).await??;
+    if !collections
+        .iter()
+        .any(|c| c.collection_id == cid && c.user_id == tuid)
+    {
+        let original = collections
+            .iter()
+            .find(|c| c.collection_id == cid)
+            .ok_or_else(|| {
+                APIError::new(
+                    404,
+                    "Not Found",
+                    "The collection ID you provided could not be found. Please check it and try again.",
+                )
+            })?;
+
+        state
+            .store
+            .send(
+                StoreCollection {
+                    collection_id: cid,
+                    principal_id: tuid,
+                    name: original.name.clone(),
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    let assignment = state
+        .store
+        .send(
+            StoreRoleAssignment {
+                collection_id: cid,
+                principal_id: tuid,
+                role,
+            }
+            .trace(),
+        )
+        .await??;
+
+    record_audit(
+        &state,
+        &token,
+        "RoleAssignments.Store",
+        format!("collection/{cid:0>32x}/user/{tuid:0>32x}"),
+    )
+    .await?;
+
+    Ok(assignment.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn store_admin_role_assignment_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreCollection {
+                collection_id: 1,
+                principal_id: 2,
+                name: "Test Collection".into(),
+            }]
+        );
+
+        let content: RoleAssignmentV3 = test_request!(PUT "/api/v3/admin/collection/00000000000000000000000000000001/user/00000000000000000000000000000003", RoleAssignmentV3 {
+            collection_id: None,
+            user_id: None,
+            role: "Contributor".into(),
+        } => OK with content | state = state);
+        assert_eq!(
+            content.user_id,
+            Some("00000000000000000000000000000003".into())
+        );
+        assert_eq!(content.role, "Contributor".to_string());
+
+        let collection = state
+            .store
+            .send(GetCollection {
+                id: 1,
+                principal_id: 3,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the user should have an entry for the collection");
+        assert_eq!(collection.name, "Test Collection");
+    }
+
+    #[actix_rt::test]
+    async fn store_admin_role_assignment_v3_invalid_role() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreCollection {
+                collection_id: 1,
+                principal_id: 2,
+                name: "Test Collection".into(),
+            }]
+        );
+
+        test_request!(PUT "/api/v3/admin/collection/00000000000000000000000000000001/user/00000000000000000000000000000003", RoleAssignmentV3 {
+            collection_id: None,
+            user_id: None,
+            role: "Superuser".into(),
+        } => BAD_REQUEST | state = state);
+    }
+}
@@ -3,7 +3,7 @@ use actix::prelude::*;
 use actix_web::{FromRequest, HttpRequest, dev::Payload, web};
 use openidconnect::{
     ClientId, EndpointMaybeSet, EndpointNotSet, EndpointSet, IdToken, IdTokenClaims, Nonce,
-    NonceVerifier, RedirectUrl,
+    NonceVerifier,
     core::{
         CoreClient, CoreGenderClaim, CoreJweContentEncryptionAlgorithm, CoreJwsSigningAlgorithm,
         CoreProviderMetadata,
@@ -35,6 +35,8 @@ pub type AuthIdTokenClaims = IdTokenClaims<AuthAdditionalClaims, CoreGenderClaim
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthToken {
     claims: AuthIdTokenClaims,
+    #[serde(default)]
+    trust_issuer_emails: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -72,11 +74,45 @@ impl AuthToken {
         &self.claims.additional_claims().unique_name
     }
 
+    /// Whether the user is known to own their email address, which must be the case before
+    /// anything addressed to it is given to them. Tokens without an `email_verified` claim
+    /// are only treated as verified when the issuer's email addresses are trusted.
+    pub fn email_verified(&self) -> bool {
+        self.claims
+            .email_verified()
+            .unwrap_or(self.trust_issuer_emails)
+    }
+
+    /// Whether the issuer is trusted to only issue tokens for email addresses which belong
+    /// to the user they were issued to.
+    pub fn issuer_emails_trusted(&self) -> bool {
+        self.trust_issuer_emails
+    }
+
+    /// Verifies the bearer token provided in an `Authorization` header, or its gRPC
+    /// metadata equivalent, using the given [`OidcActor`].
+    pub async fn from_authorization(
+        oidc: &Addr<OidcActor>,
+        authorization: Option<&str>,
+    ) -> Result<AuthToken, APIError> {
+        let token = AuthToken::bearer_token(authorization)?;
+
+        oidc.send(VerifyToken(token)).await?
+    }
+
     fn bearer_token_from_request(req: &HttpRequest) -> Result<String, APIError> {
-        req.headers()
+        let header = req
+            .headers()
             .get("Authorization")
+            .map(|header| header.to_str().map_err(|_| APIError::unauthorized()))
+            .transpose()?;
+
+        AuthToken::bearer_token(header)
+    }
+
+    fn bearer_token(authorization: Option<&str>) -> Result<String, APIError> {
+        authorization
             .ok_or_else(APIError::unauthorized)
-            .and_then(|header| header.to_str().map_err(|_| APIError::unauthorized()))
             .and_then(|header| {
                 if header.starts_with("Bearer ") {
                     header
@@ -124,12 +160,16 @@ impl FromRequest for AuthToken {
 // ── Actor ─────────────────────────────────────────────────────────────────────
 
 pub struct OidcActor {
+    config: AuthConfig,
     client: Option<OidcClient>,
 }
 
 impl OidcActor {
-    pub fn new() -> Self {
-        Self { client: None }
+    pub fn new(config: AuthConfig) -> Self {
+        Self {
+            config,
+            client: None,
+        }
     }
 }
 
@@ -137,16 +177,66 @@ impl Actor for OidcActor {
     type Context = Context<Self>;
 
     fn started(&mut self, ctx: &mut Self::Context) {
-        ctx.wait(actix::fut::wrap_future(get_client()).map(
-            |client, actor: &mut OidcActor, _ctx| {
-                actor.client = Some(client);
-            },
-        ));
+        ctx.wait(
+            actix::fut::wrap_future(get_client(self.config.clone())).map(
+                |client, actor: &mut OidcActor, _ctx| {
+                    actor.client = Some(client);
+                },
+            ),
+        );
     }
 }
 
 // ── Message ───────────────────────────────────────────────────────────────────
 
+/// Reports whether the issuer's discovery document has been loaded, which is required
+/// before any tokens can be verified.
+pub struct CheckReady;
+
+impl Message for CheckReady {
+    type Result = bool;
+}
+
+impl Handler<CheckReady> for OidcActor {
+    type Result = bool;
+
+    fn handle(&mut self, _msg: CheckReady, _ctx: &mut Self::Context) -> Self::Result {
+        self.client.is_some()
+    }
+}
+
+/// Retrieves the details which a browser needs to sign a user in with the issuer.
+pub struct GetSignIn;
+
+/// The issuer's authorization endpoint and the client ID which Rex is registered under.
+pub struct SignIn {
+    pub authorization_endpoint: String,
+    pub client_id: String,
+}
+
+impl Message for GetSignIn {
+    type Result = Result<SignIn, APIError>;
+}
+
+impl Handler<GetSignIn> for OidcActor {
+    type Result = Result<SignIn, APIError>;
+
+    fn handle(&mut self, _msg: GetSignIn, _ctx: &mut Self::Context) -> Self::Result {
+        let client = self.client.as_ref().ok_or_else(|| {
+            APIError::new(
+                503,
+                "Service Unavailable",
+                "The authentication service is not yet ready. Please try again shortly.",
+            )
+        })?;
+
+        Ok(SignIn {
+            authorization_endpoint: client.auth_uri().to_string(),
+            client_id: self.config.client_id.clone(),
+        })
+    }
+}
+
 pub struct VerifyToken(pub String);
 
 impl Message for VerifyToken {
@@ -157,8 +247,11 @@ impl Handler<VerifyToken> for OidcActor {
     type Result = Result<AuthToken, APIError>;
 
     fn handle(&mut self, msg: VerifyToken, _ctx: &mut Self::Context) -> Self::Result {
+        let metrics = crate::telemetry::metrics();
+
         let client = self.client.as_ref().ok_or_else(|| {
             error!("OidcActor received VerifyToken before client was initialized");
+            metrics.record_oidc_verification("unavailable");
             APIError::new(
                 503,
                 "Service Unavailable",
@@ -169,6 +262,7 @@ impl Handler<VerifyToken> for OidcActor {
         let id_token: AuthIdToken = serde_json::from_value(serde_json::json!(msg.0.as_str()))
             .map_err(|e| {
                 warn!("Unable to deserialize credential token: {}", e);
+                metrics.record_oidc_verification("malformed");
                 APIError::unauthorized()
             })?;
 
@@ -189,39 +283,75 @@ impl Handler<VerifyToken> for OidcActor {
                 .claims(&token_verifier, nonce_verifier)
                 .map_err(|e| {
                     warn!("Unable to verify ID token for incoming request: {}", e);
+                    metrics.record_oidc_verification("rejected");
                     APIError::unauthorized()
                 })?;
 
+        metrics.record_oidc_verification("verified");
+
         Ok(AuthToken {
             claims: claims.clone(),
+            trust_issuer_emails: self.config.trust_issuer_emails,
         })
     }
 }
 
 // ── OIDC discovery ────────────────────────────────────────────────────────────
 
-async fn get_client() -> OidcClient {
-    let issuer_url = openidconnect::IssuerUrl::new(
-        "https://sts.windows.net/a26571f1-22b3-4756-ac7b-39ca684fab48/".to_string(),
-    )
-    .expect("The issuer URL should parse correctly.");
+/// The OpenID Connect issuer which is trusted to authenticate users of the API by default.
+pub const OIDC_ISSUER: &str = "https://sts.windows.net/a26571f1-22b3-4756-ac7b-39ca684fab48/";
+
+/// The client ID which Rex is registered under with the default OpenID Connect issuer.
+pub const OIDC_CLIENT_ID: &str = "https://rex.sierrasoftworks.com";
+
+/// The OpenID Connect provider which is trusted to authenticate users of the API.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// The issuer whose discovery document describes how tokens are verified.
+    pub issuer: String,
+    /// The client ID which Rex is registered under with the issuer.
+    pub client_id: String,
+    /// Whether the issuer only issues tokens for email addresses which belong to the user,
+    /// as Azure AD does for its organizational accounts. When it does, invitations are
+    /// accepted for tokens which carry no `email_verified` claim, and for people who
+    /// already use Rex as soon as they are invited. Otherwise only tokens which claim a
+    /// verified address accept invitations, which are checked for on each of their requests.
+    pub trust_issuer_emails: bool,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            issuer: OIDC_ISSUER.into(),
+            client_id: OIDC_CLIENT_ID.into(),
+            trust_issuer_emails: true,
+        }
+    }
+}
+
+impl AuthConfig {
+    /// The address of the issuer's OpenID Connect discovery document.
+    pub fn discovery_url(&self) -> String {
+        format!(
+            "{}/.well-known/openid-configuration",
+            self.issuer.trim_end_matches('/')
+        )
+    }
+}
+
+async fn get_client(config: AuthConfig) -> OidcClient {
+    let issuer_url = openidconnect::IssuerUrl::new(config.issuer)
+        .expect("The issuer URL should parse correctly.");
     let http_client = openidconnect::reqwest::ClientBuilder::new()
         .redirect(openidconnect::reqwest::redirect::Policy::none())
         .build()
         .expect("Failed to build HTTP client for OpenID Connect discovery");
     let provider_metadata = CoreProviderMetadata::discover_async(issuer_url, &http_client)
         .await
-        .expect("We should be able to resolve provider metadata for Azure AD.");
-
-    let redirect_url = RedirectUrl::new("https://rex.sierrasoftworks.com".to_string())
-        .expect("The redirect URL should parse correctly");
+        .expect("We should be able to resolve provider metadata for the OpenID Connect issuer.");
 
-    CoreClient::from_provider_metadata(
-        provider_metadata,
-        ClientId::new("https://rex.sierrasoftworks.com".to_string()),
-        None,
-    )
-    .set_redirect_uri(redirect_url)
+    CoreClient::from_provider_metadata(provider_metadata, ClientId::new(config.client_id), None)
 }
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
@@ -233,3 +363,22 @@ impl NonceVerifier for NoOpNonceVerifier {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test::auth_claims;
+
+    #[test]
+    fn email_verified() {
+        let token = |email_verified, trust_issuer_emails| AuthToken {
+            claims: auth_claims(email_verified),
+            trust_issuer_emails,
+        };
+
+        assert!(token(None, true).email_verified());
+        assert!(!token(None, false).email_verified());
+        assert!(token(Some(true), false).email_verified());
+        assert!(!token(Some(false), true).email_verified());
+    }
+}
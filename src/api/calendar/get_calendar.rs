@@ -0,0 +1,198 @@
+use super::{APIError, UserCalendarFilter, authorize, ical::Calendar};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, get, web};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "calendar",
+    summary = "Subscribe to a calendar feed",
+    description = "Retrieves the ideas which have been picked or scheduled in each of the collections the feed's owner can read, as an iCalendar document. The feed's ID acts as its credential, so no authentication is required.",
+    params(
+        ("user" = String, Path, description = "The ID of the user who created the calendar feed."),
+        ("calendar" = String, Path, description = "The ID of the calendar feed.")
+    ),
+    responses(
+        (status = 200, description = "The scheduled ideas, as all-day events.", content_type = "text/calendar", body = String),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    )
+)]
+#[instrument(err, skip(state), fields(otel.kind = "internal"))]
+#[get("/api/v3/calendar/{user}/{calendar}/ideas.ics")]
+async fn get_calendar_v3(
+    (info, state): (web::Path<UserCalendarFilter>, web::Data<GlobalState>),
+) -> Result<HttpResponse, APIError> {
+    let uid = parse_uuid!(info.user, "user ID");
+    let id = parse_uuid!(info.calendar, "calendar feed ID");
+
+    state
+        .store
+        .send(
+            GetCalendarFeed {
+                principal_id: uid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    let collections = state
+        .store
+        .send(GetCollections { principal_id: uid }.trace())
+        .await??;
+
+    let mut events = Vec::new();
+    for collection in collections {
+        match authorize(&state, collection.collection_id, uid, Permission::IdeaRead).await {
+            Ok(_) => {}
+            Err(err) if err.code < 500 => continue,
+            Err(err) => return Err(err),
+        }
+
+        let schedules = state
+            .store
+            .send(
+                GetIdeaSchedules {
+                    collection_id: collection.collection_id,
+                }
+                .trace(),
+            )
+            .await??;
+        if schedules.is_empty() {
+            continue;
+        }
+
+        let ideas: BTreeMap<u128, Idea> = state
+            .store
+            .send(
+                GetIdeas {
+                    collection: collection.collection_id,
+                    is_completed: None,
+                    tag: None,
+                }
+                .trace(),
+            )
+            .await??
+            .into_iter()
+            .map(|idea| (idea.id, idea))
+            .collect();
+
+        events.extend(
+            schedules
+                .into_iter()
+                .filter_map(|schedule| Some((ideas.get(&schedule.idea_id)?.clone(), schedule))),
+        );
+    }
+
+    events.sort_by_key(|(_, schedule)| schedule.date);
+
+    let mut calendar = Calendar::new("Rex");
+    for (idea, schedule) in events.iter() {
+        calendar.add_idea(idea, schedule);
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/calendar; charset=utf-8")
+        .body(calendar.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+    use chrono::NaiveDate;
+
+    #[actix_rt::test]
+    async fn get_calendar_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCalendarFeed {
+                    principal_id: 1,
+                    id: 2,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreCollection {
+                    collection_id: 3,
+                    principal_id: 1,
+                    name: "Shared Ideas".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 3,
+                    principal_id: 1,
+                    role: Role::Viewer,
+                },
+                StoreIdea {
+                    collection: 3,
+                    id: 4,
+                    name: "Go hiking".into(),
+                    ..Default::default()
+                },
+                StoreIdeaSchedule {
+                    collection_id: 3,
+                    idea_id: 4,
+                    date: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+                    principal_id: 5,
+                    scheduled_at: chrono::Utc::now(),
+                },
+                StoreIdeaSchedule {
+                    collection_id: 3,
+                    idea_id: 6,
+                    date: NaiveDate::from_ymd_opt(2024, 12, 30).unwrap(),
+                    principal_id: 5,
+                    scheduled_at: chrono::Utc::now(),
+                },
+                StoreCollection {
+                    collection_id: 7,
+                    principal_id: 1,
+                    name: "Former Ideas".into(),
+                },
+                StoreIdea {
+                    collection: 7,
+                    id: 8,
+                    name: "Read a book".into(),
+                    ..Default::default()
+                },
+                StoreIdeaSchedule {
+                    collection_id: 7,
+                    idea_id: 8,
+                    date: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+                    principal_id: 1,
+                    scheduled_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        let app = get_test_app(state.clone()).await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/v3/calendar/00000000000000000000000000000001/00000000000000000000000000000002/ideas.ics")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "text/calendar; charset=utf-8"
+        );
+
+        let body = actix_web::test::read_body(response).await;
+        let calendar = String::from_utf8_lossy(&body);
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("SUMMARY:Go hiking\r\n"));
+        assert!(!calendar.contains("Read a book"));
+    }
+
+    #[actix_rt::test]
+    async fn get_calendar_v3_revoked() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state.clone()).await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/v3/calendar/00000000000000000000000000000001/00000000000000000000000000000002/ideas.ics")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::NOT_FOUND).await;
+    }
+}
@@ -0,0 +1,63 @@
+use super::{APIError, AuthToken};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "calendar",
+    summary = "List your calendar feeds",
+    responses(
+        (status = 200, description = "The calendar feeds which you have created.", body = [CalendarFeedV3])
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/calendars")]
+async fn get_calendars_v3(
+    (state, token): (web::Data<GlobalState>, AuthToken),
+) -> Result<web::Json<Vec<CalendarFeedV3>>, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Read");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    state
+        .store
+        .send(GetCalendarFeeds { principal_id: uid }.trace())
+        .await?
+        .map(|feeds| web::Json(feeds.into_iter().map(|f| f.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_calendars_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCalendarFeed {
+                    principal_id: 0,
+                    id: 1,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreCalendarFeed {
+                    principal_id: 2,
+                    id: 3,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        let content: Vec<CalendarFeedV3> =
+            test_request!(GET "/api/v3/calendars" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(
+            content[0].id,
+            Some("00000000000000000000000000000001".into())
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use super::{APIError, AuthToken, CollectionFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "calendar",
+    summary = "List the scheduled ideas in a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The ideas in the collection which have been picked or scheduled, ordered by date.", body = [IdeaScheduleV3])
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/schedule")]
+async fn get_schedule_v3(
+    (info, state, token): (
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<web::Json<Vec<IdeaScheduleV3>>, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Read");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::IdeaRead).await?;
+
+    let mut schedules = state
+        .store
+        .send(GetIdeaSchedules { collection_id: cid }.trace())
+        .await??;
+    schedules.sort_by_key(|s| s.date);
+
+    Ok(web::Json(schedules.into_iter().map(|s| s.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+    use chrono::NaiveDate;
+
+    #[actix_rt::test]
+    async fn get_schedule_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Viewer,
+                },
+                StoreIdeaSchedule {
+                    collection_id: 1,
+                    idea_id: 2,
+                    date: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+                    principal_id: 0,
+                    scheduled_at: chrono::Utc::now(),
+                },
+                StoreIdeaSchedule {
+                    collection_id: 1,
+                    idea_id: 3,
+                    date: NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+                    principal_id: 0,
+                    scheduled_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        let content: Vec<IdeaScheduleV3> = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/schedule" => OK with content | state = state);
+        assert_eq!(content.len(), 2);
+        assert_eq!(
+            content[0].idea,
+            Some("00000000000000000000000000000003".into())
+        );
+        assert_eq!(content[1].date, NaiveDate::from_ymd_opt(2024, 12, 31));
+    }
+
+    #[actix_rt::test]
+    async fn get_schedule_v3_no_access() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        test_request!(GET "/api/v3/collection/00000000000000000000000000000001/schedule" => FORBIDDEN | state = state);
+    }
+}
@@ -0,0 +1,182 @@
+//! A minimal writer for the subset of the iCalendar format (RFC 5545) which is needed
+//! to publish scheduled ideas as all-day events.
+
+use crate::models::{Idea, IdeaSchedule};
+use chrono::{DateTime, Utc};
+
+/// The longest a content line may be, in octets, before it must be folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+pub struct Calendar {
+    name: String,
+    lines: Vec<String>,
+}
+
+impl Calendar {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            lines: Vec::new(),
+        }
+    }
+
+    /// Adds an all-day event for an idea on the date it has been scheduled for.
+    pub fn add_idea(&mut self, idea: &Idea, schedule: &IdeaSchedule) {
+        let mut tags: Vec<&str> = idea.tags.iter().map(|t| t.as_str()).collect();
+        tags.sort();
+
+        self.lines.push("BEGIN:VEVENT".into());
+        self.lines.push(format!(
+            "UID:{:0>32x}-{:0>32x}@rex.sierrasoftworks.com",
+            idea.collection_id, idea.id
+        ));
+        self.lines
+            .push(format!("DTSTAMP:{}", timestamp(schedule.scheduled_at)));
+        self.lines.push(format!(
+            "DTSTART;VALUE=DATE:{}",
+            schedule.date.format("%Y%m%d")
+        ));
+        if let Some(end) = schedule.date.succ_opt() {
+            self.lines
+                .push(format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")));
+        }
+        self.lines.push(format!("SUMMARY:{}", escape(&idea.name)));
+        if !idea.description.is_empty() {
+            self.lines
+                .push(format!("DESCRIPTION:{}", escape(&idea.description)));
+        }
+        if !tags.is_empty() {
+            self.lines.push(format!(
+                "CATEGORIES:{}",
+                tags.iter().map(|t| escape(t)).collect::<Vec<_>>().join(",")
+            ));
+        }
+        self.lines.push(format!(
+            "STATUS:{}",
+            if idea.completed {
+                "CONFIRMED"
+            } else {
+                "TENTATIVE"
+            }
+        ));
+        self.lines.push("TRANSP:TRANSPARENT".into());
+        self.lines.push("END:VEVENT".into());
+    }
+}
+
+impl std::fmt::Display for Calendar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = [
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//Sierra Softworks//Rex//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            "METHOD:PUBLISH".to_string(),
+            format!("X-WR-CALNAME:{}", escape(&self.name)),
+        ];
+
+        for line in header
+            .iter()
+            .chain(self.lines.iter())
+            .chain(std::iter::once(&"END:VCALENDAR".to_string()))
+        {
+            write!(f, "{}\r\n", fold(line))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes the characters which have special meaning in iCalendar text values.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+/// Splits a content line into lines of at most [`MAX_LINE_LENGTH`] octets, continuing
+/// each with a leading space, without splitting any multi-byte characters.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[test]
+    fn escaping() {
+        assert_eq!(
+            escape("Fish, chips; and \\ more\nstuff"),
+            "Fish\\, chips\\; and \\\\ more\\nstuff"
+        );
+    }
+
+    #[test]
+    fn folding() {
+        let line = format!("SUMMARY:{}", "é".repeat(40));
+        let folded = fold(&line);
+
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_LENGTH, "{part:?}");
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn calendar() {
+        let mut calendar = Calendar::new("Rex");
+        calendar.add_idea(
+            &Idea {
+                id: 2,
+                collection_id: 1,
+                name: "Go hiking".into(),
+                description: "Somewhere with a view".into(),
+                tags: ["outdoor".to_string()].into_iter().collect(),
+                completed: false,
+                created_at: None,
+                completed_at: None,
+            },
+            &IdeaSchedule {
+                collection_id: 1,
+                idea_id: 2,
+                date: NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+                scheduled_by: 3,
+                scheduled_at: Utc.with_ymd_and_hms(2024, 12, 1, 9, 30, 0).unwrap(),
+            },
+        );
+
+        let output = calendar.to_string().replace("\r\n ", "");
+        assert!(output.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(output.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(output.contains(
+            "UID:00000000000000000000000000000001-00000000000000000000000000000002@rex.sierrasoftworks.com\r\n"
+        ));
+        assert!(output.contains("DTSTAMP:20241201T093000Z\r\n"));
+        assert!(output.contains("DTSTART;VALUE=DATE:20241231\r\n"));
+        assert!(output.contains("DTEND;VALUE=DATE:20250101\r\n"));
+        assert!(output.contains("SUMMARY:Go hiking\r\n"));
+        assert!(output.contains("CATEGORIES:outdoor\r\n"));
+        assert!(output.contains("STATUS:TENTATIVE\r\n"));
+    }
+}
@@ -0,0 +1,56 @@
+mod get_calendar;
+mod get_calendars;
+mod get_schedule;
+mod ical;
+mod new_calendar;
+mod remove_calendar;
+mod schedule_idea;
+mod unschedule_idea;
+
+use super::{APIError, AuthToken, authorize};
+use actix_web::web;
+use utoipa::OpenApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_schedule::get_schedule_v3)
+        .service(schedule_idea::schedule_idea_v3)
+        .service(unschedule_idea::unschedule_idea_v3)
+        .service(get_calendars::get_calendars_v3)
+        .service(new_calendar::new_calendar_v3)
+        .service(remove_calendar::remove_calendar_v3)
+        .service(get_calendar::get_calendar_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_schedule::get_schedule_v3,
+    schedule_idea::schedule_idea_v3,
+    unschedule_idea::unschedule_idea_v3,
+    get_calendars::get_calendars_v3,
+    new_calendar::new_calendar_v3,
+    remove_calendar::remove_calendar_v3,
+    get_calendar::get_calendar_v3,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionFilter {
+    collection: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionIdeaFilter {
+    collection: String,
+    idea: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CalendarFilter {
+    calendar: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct UserCalendarFilter {
+    user: String,
+    calendar: String,
+}
@@ -0,0 +1,57 @@
+use super::{APIError, AuthToken};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{post, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "calendar",
+    summary = "Create a calendar feed",
+    description = "Creates a private feed of the ideas which have been scheduled in the collections you can read, which calendar clients can subscribe to using the address returned in the `Location` header. The feed can also be used to subscribe to the Atom feed of any of those collections.",
+    responses(
+        (status = 201, description = "The calendar feed which was created.", body = CalendarFeedV3)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[post("/api/v3/calendars")]
+async fn new_calendar_v3(
+    (state, token): (web::Data<GlobalState>, AuthToken),
+) -> Result<CalendarFeedV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    state
+        .store
+        .send(
+            StoreCalendarFeed {
+                principal_id: uid,
+                id: new_id(),
+                created_at: chrono::Utc::now(),
+            }
+            .trace(),
+        )
+        .await?
+        .map(|feed| feed.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn new_calendar_v3() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let content: CalendarFeedV3 = test_request!(POST "/api/v3/calendars" => CREATED with location =~ "/api/v3/calendar/00000000000000000000000000000000/", content | state = state);
+        assert_eq!(
+            content.user_id,
+            Some("00000000000000000000000000000000".into())
+        );
+        assert!(content.id.is_some());
+    }
+}
@@ -0,0 +1,70 @@
+use super::{APIError, AuthToken, CalendarFilter};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, delete, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "calendar",
+    summary = "Revoke a calendar feed",
+    params(
+        ("calendar" = String, Path, description = "The ID of the calendar feed.")
+    ),
+    responses(
+        (status = 204, description = "The calendar feed was revoked."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[delete("/api/v3/calendar/{calendar}")]
+async fn remove_calendar_v3(
+    (info, state, token): (web::Path<CalendarFilter>, web::Data<GlobalState>, AuthToken),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let id = parse_uuid!(info.calendar, "calendar feed ID");
+
+    state
+        .store
+        .send(
+            RemoveCalendarFeed {
+                principal_id: uid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn remove_calendar_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCalendarFeed {
+                    principal_id: 0,
+                    id: 1,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreCalendarFeed {
+                    principal_id: 2,
+                    id: 3,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        test_request!(DELETE "/api/v3/calendar/00000000000000000000000000000001" => NO_CONTENT | state = state);
+        test_request!(DELETE "/api/v3/calendar/00000000000000000000000000000003" => NOT_FOUND | state = state);
+    }
+}
@@ -0,0 +1,158 @@
+use super::{APIError, AuthToken, CollectionIdeaFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{put, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "calendar",
+    summary = "Schedule an idea",
+    description = "Schedules an idea for the given date. If no date is provided, the idea is marked as having been picked for today.",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("idea" = String, Path, description = "The ID of the idea.")
+    ),
+    request_body = IdeaScheduleV3,
+    responses(
+        (status = 200, description = "The schedule which was stored.", body = IdeaScheduleV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
+#[instrument(err, skip(state, token, schedule), fields(otel.kind = "internal"))]
+#[put("/api/v3/collection/{collection}/idea/{idea}/schedule")]
+async fn schedule_idea_v3(
+    (info, schedule, state, token): (
+        web::Path<CollectionIdeaFilter>,
+        web::Json<IdeaScheduleV3>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<IdeaScheduleV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let id = parse_uuid!(info.idea, "idea ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::IdeaUpdate).await?;
+
+    state
+        .store
+        .send(
+            GetIdea {
+                collection: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    let now = chrono::Utc::now();
+    state
+        .store
+        .send(
+            StoreIdeaSchedule {
+                collection_id: cid,
+                idea_id: id,
+                date: schedule.date.unwrap_or_else(|| now.date_naive()),
+                principal_id: uid,
+                scheduled_at: now,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|schedule| schedule.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+    use chrono::NaiveDate;
+
+    #[actix_rt::test]
+    async fn schedule_idea_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Contributor,
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 2,
+                    name: "Go hiking".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let content: IdeaScheduleV3 = test_request!(PUT "/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002/schedule", IdeaScheduleV3 {
+            collection: None,
+            idea: None,
+            date: NaiveDate::from_ymd_opt(2024, 12, 31),
+            scheduled_by: None,
+            scheduled_at: None,
+        } => OK with content | state = state);
+        assert_eq!(content.date, NaiveDate::from_ymd_opt(2024, 12, 31));
+        assert_eq!(
+            content.scheduled_by,
+            Some("00000000000000000000000000000000".into())
+        );
+
+        let content: IdeaScheduleV3 = test_request!(PUT "/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002/schedule", IdeaScheduleV3 {
+            collection: None,
+            idea: None,
+            date: None,
+            scheduled_by: None,
+            scheduled_at: None,
+        } => OK with content | state = state);
+        assert_eq!(content.date, Some(chrono::Utc::now().date_naive()));
+    }
+
+    #[actix_rt::test]
+    async fn schedule_idea_v3_missing_idea() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Contributor,
+            }]
+        );
+
+        test_request!(PUT "/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002/schedule", IdeaScheduleV3 {
+            collection: None,
+            idea: None,
+            date: None,
+            scheduled_by: None,
+            scheduled_at: None,
+        } => NOT_FOUND | state = state);
+    }
+
+    #[actix_rt::test]
+    async fn schedule_idea_v3_viewer() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Viewer,
+            }]
+        );
+
+        test_request!(PUT "/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002/schedule", IdeaScheduleV3 {
+            collection: None,
+            idea: None,
+            date: None,
+            scheduled_by: None,
+            scheduled_at: None,
+        } => FORBIDDEN | state = state);
+    }
+}
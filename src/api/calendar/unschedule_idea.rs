@@ -0,0 +1,80 @@
+use super::{APIError, AuthToken, CollectionIdeaFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, delete, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "calendar",
+    summary = "Remove an idea from the schedule",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("idea" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 204, description = "The idea was removed from the schedule."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[delete("/api/v3/collection/{collection}/idea/{idea}/schedule")]
+async fn unschedule_idea_v3(
+    (info, state, token): (
+        web::Path<CollectionIdeaFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let id = parse_uuid!(info.idea, "idea ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::IdeaUpdate).await?;
+
+    state
+        .store
+        .send(
+            RemoveIdeaSchedule {
+                collection_id: cid,
+                idea_id: id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn unschedule_idea_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Contributor,
+                },
+                StoreIdeaSchedule {
+                    collection_id: 1,
+                    idea_id: 2,
+                    date: chrono::Utc::now().date_naive(),
+                    principal_id: 0,
+                    scheduled_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        test_request!(DELETE "/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002/schedule" => NO_CONTENT | state = state);
+        test_request!(DELETE "/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002/schedule" => NOT_FOUND | state = state);
+    }
+}
@@ -0,0 +1,128 @@
+use super::APIError;
+use crate::api::{OidcActor, auth::GetSignIn};
+use actix::Addr;
+use actix_web::{HttpResponse, get, http::header::ContentType, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "chat",
+    summary = "Open the page which links a chat account to your Rex account",
+    description = "The page which the link sent to an unlinked chat user opens. It signs the user in with the OpenID Connect issuer and then links their chat account using the signed token from the link. The address of this page must be registered as a redirect URI with the issuer.",
+    params(
+        ("token" = Option<String>, Query, description = "The signed token from the link which was sent to the chat user.")
+    ),
+    responses(
+        (status = 200, description = "The page which links the chat account.", content_type = "text/html", body = String),
+        (status = 503, description = "The authentication service is not yet ready.", body = APIError)
+    )
+)]
+#[instrument(err, skip(oidc), fields(otel.kind = "internal"))]
+#[get("/api/v3/chat/link")]
+async fn link_chat_page_v3(oidc: web::Data<Addr<OidcActor>>) -> Result<HttpResponse, APIError> {
+    let sign_in = oidc.send(GetSignIn).await??;
+
+    // The configuration is embedded in a script, so it is serialized as JSON and any
+    // closing tags are escaped to keep it from ending the script early.
+    let config = serde_json::json!({
+        "authorization_endpoint": sign_in.authorization_endpoint,
+        "client_id": sign_in.client_id,
+    })
+    .to_string()
+    .replace("</", "<\\/");
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(PAGE.replace("{config}", &config)))
+}
+
+/// Signs the user in with the issuer's implicit flow, carrying the link token through the
+/// `state` parameter, and then redeems the token with the ID token which was issued.
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>Link your chat account to Rex</title>
+</head>
+<body>
+    <h1>Link your chat account to Rex</h1>
+    <p id="status">Signing you in&hellip;</p>
+    <script>
+        const config = {config};
+        const show = (message) => { document.getElementById("status").textContent = message; };
+
+        const query = new URLSearchParams(window.location.search);
+        const response = new URLSearchParams(window.location.hash.slice(1));
+        const redirectUri = window.location.origin + window.location.pathname;
+        window.history.replaceState(null, "", window.location.pathname);
+
+        if (response.has("id_token") && response.has("state")) {
+            show("Linking your account…");
+            fetch(`/api/v3/chat/links/${encodeURIComponent(response.get("state"))}`, {
+                method: "POST",
+                headers: { "Authorization": `Bearer ${response.get("id_token")}` },
+            })
+                .then(async (res) => {
+                    if (res.ok) {
+                        show("Your chat account has been linked to Rex, you can close this page and return to your chat.");
+                    } else {
+                        const error = await res.json().catch(() => ({}));
+                        show(`Your chat account could not be linked: ${error.message || res.statusText}`);
+                    }
+                })
+                .catch(() => show("Your chat account could not be linked, please try again."));
+        } else if (response.has("error")) {
+            show(`You could not be signed in: ${response.get("error_description") || response.get("error")}`);
+        } else if (query.has("token")) {
+            const url = new URL(config.authorization_endpoint);
+            url.search = new URLSearchParams({
+                client_id: config.client_id,
+                response_type: "id_token",
+                response_mode: "fragment",
+                scope: "openid profile email",
+                redirect_uri: redirectUri,
+                state: query.get("token"),
+                nonce: crypto.randomUUID(),
+            }).toString();
+            window.location.assign(url.toString());
+        } else {
+            show("This link is not valid, please ask Rex for a new one from your chat.");
+        }
+    </script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use actix_web::test::{TestRequest, call_service, read_body};
+
+    #[actix_rt::test]
+    async fn link_chat_page_v3() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state.clone()).await;
+        let response = call_service(
+            &app,
+            TestRequest::get()
+                .uri("/api/v3/chat/link?token=T123.U456.1.abc")
+                .to_request(),
+        )
+        .await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        assert_eq!(
+            response
+                .headers()
+                .get("Content-Type")
+                .and_then(|h| h.to_str().ok()),
+            Some("text/html; charset=utf-8")
+        );
+
+        let body = String::from_utf8(read_body(response).await.to_vec()).unwrap();
+        assert!(body.contains("\"client_id\":"), "{body}");
+        assert!(body.contains("/api/v3/chat/links/"), "{body}");
+    }
+}
@@ -0,0 +1,102 @@
+use super::{APIError, AuthToken, SlackConfig, TokenFilter, parse_link_token};
+use crate::{api::ensure_user_collection, models::*, telemetry::TraceMessageExt};
+use actix_web::{post, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "chat",
+    summary = "Link a chat account to your Rex account",
+    description = "Redeems the link sent to a chat user who hasn't linked their account yet, allowing them to use chat commands on your behalf.",
+    params(
+        ("token" = String, Path, description = "The signed token from the link which was sent to the chat user.")
+    ),
+    responses(
+        (status = 200, description = "The chat account which was linked.", body = ChatLinkV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError),
+        (status = 410, description = "The link has expired.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
+#[instrument(err, skip(info, state, token, config), fields(otel.kind = "internal"))]
+#[post("/api/v3/chat/links/{token}")]
+async fn link_chat_user_v3(
+    (info, state, token, config): (
+        web::Path<TokenFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+        web::Data<SlackConfig>,
+    ),
+) -> Result<ChatLinkV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let (team_id, user_id) = parse_link_token(config.secret()?, &info.token)?;
+
+    ensure_user_collection(&state, &token).await?;
+
+    state
+        .store
+        .send(
+            StoreChatLink {
+                team_id,
+                user_id,
+                principal_id: uid,
+                created_at: chrono::Utc::now(),
+            }
+            .trace(),
+        )
+        .await?
+        .map(|link| link.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{link_token_for, test::SIGNING_SECRET};
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn link_chat_user_v3() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let token = link_token_for(
+            SIGNING_SECRET,
+            "T123",
+            "U456",
+            chrono::Utc::now() + chrono::Duration::minutes(5),
+        );
+
+        let content: ChatLinkV3 = test_request!(POST &format!("/api/v3/chat/links/{token}") => OK with content | state = state);
+        assert_eq!(content.team_id, "T123");
+        assert_eq!(content.user_id, "U456");
+        assert_eq!(content.principal_id, "00000000000000000000000000000000");
+
+        state
+            .store
+            .send(GetChatLink {
+                chat_user_hash: chat_user_hash("T123", "U456"),
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the chat account should be linked");
+    }
+
+    #[actix_rt::test]
+    async fn link_chat_user_v3_expired() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let token = link_token_for(
+            SIGNING_SECRET,
+            "T123",
+            "U456",
+            chrono::Utc::now() - chrono::Duration::minutes(5),
+        );
+
+        test_request!(POST &format!("/api/v3/chat/links/{token}") => GONE | state = state);
+    }
+}
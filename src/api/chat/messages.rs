@@ -0,0 +1,174 @@
+//! Builders for the Slack messages which Rex responds to chat commands with,
+//! described using Slack's Block Kit format.
+
+use crate::models::Idea;
+use serde_json::{Value, json};
+
+/// The action ID of the button which marks an idea as completed.
+pub const COMPLETE_ACTION: &str = "rex.complete";
+
+/// The action ID of the button which replaces an idea with another random one.
+pub const REROLL_ACTION: &str = "rex.reroll";
+
+/// Escapes the characters which Slack treats as control sequences in message text.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A message which is only shown to the user who ran the command.
+pub fn ephemeral(text: impl Into<String>) -> Value {
+    json!({
+        "response_type": "ephemeral",
+        "text": text.into(),
+    })
+}
+
+pub fn help(command: &str) -> Value {
+    ephemeral(format!(
+        "Here's what I can do:\n\
+         • `{command} random [tag]` suggests a random idea, optionally with the given tag.\n\
+         • `{command} link` links your chat account to your Rex account.\n\
+         • `{command} unlink` unlinks your chat account from your Rex account."
+    ))
+}
+
+pub fn link_account(url: &str, lifetime_minutes: i64) -> Value {
+    json!({
+        "response_type": "ephemeral",
+        "text": format!("Link your Rex account to get started: {url}"),
+        "blocks": [
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!("Before I can suggest ideas, you'll need to link your chat account to your Rex account. This link expires in {lifetime_minutes} minutes."),
+                },
+            },
+            {
+                "type": "actions",
+                "elements": [
+                    {
+                        "type": "button",
+                        "text": { "type": "plain_text", "text": "Link your Rex account" },
+                        "style": "primary",
+                        "url": url,
+                    },
+                ],
+            },
+        ],
+    })
+}
+
+/// A random idea, shared with the channel, along with buttons to mark it as done or
+/// to choose another idea with the same tag.
+pub fn idea(idea: &Idea, tag: Option<&str>) -> Value {
+    let mut tags: Vec<&str> = idea.tags.iter().map(|t| t.as_str()).collect();
+    tags.sort();
+
+    let mut blocks = vec![json!({
+        "type": "section",
+        "text": {
+            "type": "mrkdwn",
+            "text": if idea.description.is_empty() {
+                format!("*{}*", escape(&idea.name))
+            } else {
+                format!("*{}*\n{}", escape(&idea.name), escape(&idea.description))
+            },
+        },
+    })];
+
+    if !tags.is_empty() {
+        blocks.push(json!({
+            "type": "context",
+            "elements": [
+                {
+                    "type": "mrkdwn",
+                    "text": tags.iter().map(|t| format!("`{}`", escape(t))).collect::<Vec<_>>().join(" "),
+                },
+            ],
+        }));
+    }
+
+    blocks.push(json!({
+        "type": "actions",
+        "elements": [
+            {
+                "type": "button",
+                "text": { "type": "plain_text", "text": "Mark as done" },
+                "style": "primary",
+                "action_id": COMPLETE_ACTION,
+                "value": format!("{:0>32x}/{:0>32x}", idea.collection_id, idea.id),
+            },
+            {
+                "type": "button",
+                "text": { "type": "plain_text", "text": "Re-roll" },
+                "action_id": REROLL_ACTION,
+                "value": tag.unwrap_or_default(),
+            },
+        ],
+    }));
+
+    json!({
+        "response_type": "in_channel",
+        "text": format!("How about: {}", idea.name),
+        "blocks": blocks,
+    })
+}
+
+/// Replaces a suggested idea once it has been marked as done.
+pub fn completed(idea: &Idea, user_id: &str) -> Value {
+    json!({
+        "response_type": "in_channel",
+        "replace_original": true,
+        "text": format!("{} has been marked as done.", idea.name),
+        "blocks": [
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!("~{}~\n:white_check_mark: Marked as done by <@{}>.", escape(&idea.name), escape(user_id)),
+                },
+            },
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idea_message() {
+        let message = idea(
+            &Idea {
+                id: 2,
+                collection_id: 1,
+                name: "Go <outside>".into(),
+                description: "Fish & chips".into(),
+                tags: ["outdoor".to_string()].into_iter().collect(),
+                completed: false,
+                created_at: None,
+                completed_at: None,
+            },
+            Some("outdoor"),
+        );
+
+        assert_eq!(message["response_type"], "in_channel");
+        assert_eq!(
+            message["blocks"][0]["text"]["text"],
+            "*Go &lt;outside&gt;*\nFish &amp; chips"
+        );
+        assert_eq!(message["blocks"][1]["elements"][0]["text"], "`outdoor`");
+
+        let actions = &message["blocks"][2]["elements"];
+        assert_eq!(actions[0]["action_id"], COMPLETE_ACTION);
+        assert_eq!(
+            actions[0]["value"],
+            "00000000000000000000000000000001/00000000000000000000000000000002"
+        );
+        assert_eq!(actions[1]["action_id"], REROLL_ACTION);
+        assert_eq!(actions[1]["value"], "outdoor");
+    }
+}
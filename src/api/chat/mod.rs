@@ -0,0 +1,305 @@
+mod link_chat_page;
+mod link_chat_user;
+mod messages;
+mod slack_command;
+mod slack_interaction;
+
+use super::{APIError, AuthToken};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpRequest, web};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use utoipa::OpenApi;
+
+/// The number of seconds by which a Slack request's timestamp may differ from the
+/// current time before it is rejected as a possible replay.
+const MAX_REQUEST_AGE: i64 = 300;
+
+/// The number of minutes for which the link sent to an unlinked chat user remains valid.
+const LINK_TOKEN_LIFETIME: i64 = 15;
+
+pub fn configure(cfg: &mut web::ServiceConfig, config: &SlackConfig) {
+    cfg.app_data(web::Data::new(config.clone()))
+        .service(slack_command::slack_command_v3)
+        .service(slack_interaction::slack_interaction_v3)
+        .service(link_chat_page::link_chat_page_v3)
+        .service(link_chat_user::link_chat_user_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    slack_command::slack_command_v3,
+    slack_interaction::slack_interaction_v3,
+    link_chat_page::link_chat_page_v3,
+    link_chat_user::link_chat_user_v3,
+))]
+pub struct ApiDoc;
+
+/// The configuration for the Slack integration, which is disabled unless the
+/// signing secret of the Slack app which sends commands to Rex has been configured.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SlackConfig {
+    pub signing_secret: Option<String>,
+}
+
+impl SlackConfig {
+    fn secret(&self) -> Result<&str, APIError> {
+        self.signing_secret.as_deref().ok_or_else(|| {
+            APIError::new(
+                404,
+                "Not Found",
+                "The Slack integration has not been configured for this Rex instance.",
+            )
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct TokenFilter {
+    token: String,
+}
+
+/// Verifies that a request was sent by Slack by checking its signature against the
+/// signing secret, rejecting requests which are too old to guard against replays.
+fn verify_request(config: &SlackConfig, req: &HttpRequest, body: &[u8]) -> Result<(), APIError> {
+    let secret = config.secret()?;
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|h| h.to_str().ok())
+            .ok_or_else(|| {
+                warn!("Received a Slack request without the {} header.", name);
+                APIError::unauthorized()
+            })
+    };
+
+    let timestamp = header("X-Slack-Request-Timestamp")?;
+    let signature = header("X-Slack-Signature")?
+        .strip_prefix("v0=")
+        .and_then(|sig| hex::decode(sig).ok())
+        .ok_or_else(APIError::unauthorized)?;
+
+    let age = timestamp
+        .parse::<i64>()
+        .map(|ts| (Utc::now().timestamp() - ts).abs())
+        .map_err(|_| APIError::unauthorized())?;
+    if age > MAX_REQUEST_AGE {
+        warn!(
+            "Rejected a Slack request which was sent {} seconds ago.",
+            age
+        );
+        return Err(APIError::unauthorized());
+    }
+
+    request_signature(secret, timestamp, body)
+        .verify_slice(&signature)
+        .map_err(|_| {
+            warn!("Received a Slack request with an invalid signature.");
+            APIError::unauthorized()
+        })
+}
+
+fn request_signature(secret: &str, timestamp: &str, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("v0:{timestamp}:").as_bytes());
+    mac.update(body);
+    mac
+}
+
+fn link_signature(secret: &str, team_id: &str, user_id: &str, expires: i64) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("rex.chat.link:{team_id}.{user_id}.{expires}").as_bytes());
+    mac
+}
+
+/// Generates the token which allows a chat user to link their account to the Rex
+/// principal who redeems it, binding the chat user's identity to a signature so
+/// that tokens cannot be forged or altered.
+fn link_token_for(secret: &str, team_id: &str, user_id: &str, expires: DateTime<Utc>) -> String {
+    let expires = expires.timestamp();
+    let signature = link_signature(secret, team_id, user_id, expires)
+        .finalize()
+        .into_bytes();
+
+    format!("{team_id}.{user_id}.{expires}.{}", hex::encode(signature))
+}
+
+/// Verifies the signature on a link token and returns the team and user IDs it refers to.
+fn parse_link_token(secret: &str, token: &str) -> Result<(String, String), APIError> {
+    let not_found = || {
+        APIError::new(
+            404,
+            "Not Found",
+            "The link you followed is not valid. Please run the command again to get a new one.",
+        )
+    };
+
+    let parts: Vec<&str> = token.split('.').collect();
+    let [team_id, user_id, expires, signature] = parts[..] else {
+        return Err(not_found());
+    };
+
+    let expires: i64 = expires.parse().map_err(|_| not_found())?;
+    let signature = hex::decode(signature).map_err(|_| not_found())?;
+
+    link_signature(secret, team_id, user_id, expires)
+        .verify_slice(&signature)
+        .map_err(|_| {
+            warn!("Received a chat link token with an invalid signature.");
+            not_found()
+        })?;
+
+    if expires <= Utc::now().timestamp() {
+        return Err(APIError::new(
+            410,
+            "Gone",
+            "The link you followed has expired. Please run the command again to get a new one.",
+        ));
+    }
+
+    Ok((team_id.to_string(), user_id.to_string()))
+}
+
+/// Retrieves the Rex principal which a chat user has linked their account to.
+async fn linked_principal(
+    state: &GlobalState,
+    team_id: &str,
+    user_id: &str,
+) -> Result<Option<u128>, APIError> {
+    match state
+        .store
+        .send(
+            GetChatLink {
+                chat_user_hash: chat_user_hash(team_id, user_id),
+            }
+            .trace(),
+        )
+        .await?
+    {
+        Ok(link) => Ok(Some(link.principal_id)),
+        Err(err) if err.code == 404 => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Builds the message which invites an unlinked chat user to link their Rex account, by
+/// opening the page served by [`link_chat_page::link_chat_page_v3`].
+fn link_account_message(
+    config: &SlackConfig,
+    req: &HttpRequest,
+    team_id: &str,
+    user_id: &str,
+) -> Result<serde_json::Value, APIError> {
+    let token = link_token_for(
+        config.secret()?,
+        team_id,
+        user_id,
+        Utc::now() + chrono::Duration::minutes(LINK_TOKEN_LIFETIME),
+    );
+
+    let connection = req.connection_info();
+    Ok(messages::link_account(
+        &format!(
+            "{}://{}/api/v3/chat/link?token={token}",
+            connection.scheme(),
+            connection.host()
+        ),
+        LINK_TOKEN_LIFETIME,
+    ))
+}
+
+/// Chooses a random, incomplete idea from the principal's own collection.
+async fn random_idea_message(
+    state: &GlobalState,
+    principal_id: u128,
+    tag: Option<String>,
+) -> Result<serde_json::Value, APIError> {
+    match state
+        .store
+        .send(
+            GetRandomIdea {
+                collection: principal_id,
+                tag: tag.clone(),
+                is_completed: Some(false),
+            }
+            .trace(),
+        )
+        .await?
+    {
+        Ok(idea) => Ok(messages::idea(&idea, tag.as_deref())),
+        Err(err) if err.code == 404 => Ok(messages::ephemeral(match tag {
+            Some(tag) => format!(
+                "You don't have any ideas tagged *{}* which you haven't done yet.",
+                messages::escape(&tag)
+            ),
+            None => "You don't have any ideas which you haven't done yet.".to_string(),
+        })),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+pub(super) mod test {
+    use super::request_signature;
+    use actix_web::test::TestRequest;
+    use hmac::Mac;
+
+    /// The signing secret which the test application is configured with.
+    pub const SIGNING_SECRET: &str = "test-signing-secret";
+
+    /// Builds a request which has been signed in the same way as Slack signs them.
+    pub fn slack_request(uri: &str, body: &str) -> actix_http::Request {
+        let timestamp = chrono::Utc::now().timestamp().to_string();
+        let signature = request_signature(SIGNING_SECRET, &timestamp, body.as_bytes())
+            .finalize()
+            .into_bytes();
+
+        TestRequest::post()
+            .uri(uri)
+            .insert_header(("Content-Type", "application/x-www-form-urlencoded"))
+            .insert_header(("X-Slack-Request-Timestamp", timestamp))
+            .insert_header((
+                "X-Slack-Signature",
+                format!("v0={}", hex::encode(signature)),
+            ))
+            .set_payload(body.to_string())
+            .to_request()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_tokens() {
+        let expires = Utc::now() + chrono::Duration::minutes(5);
+        let token = link_token_for("secret", "T123", "U456", expires);
+
+        assert_eq!(
+            parse_link_token("secret", &token).expect("the token should be valid"),
+            ("T123".to_string(), "U456".to_string())
+        );
+
+        let err =
+            parse_link_token("other-secret", &token).expect_err("the signature should not match");
+        assert_eq!(err.code, 404);
+
+        let err = parse_link_token("secret", &token.replacen("U456", "U789", 1))
+            .expect_err("the token should not be alterable");
+        assert_eq!(err.code, 404);
+
+        let expired = link_token_for(
+            "secret",
+            "T123",
+            "U456",
+            Utc::now() - chrono::Duration::minutes(1),
+        );
+        let err = parse_link_token("secret", &expired).expect_err("the token should have expired");
+        assert_eq!(err.code, 410);
+    }
+}
@@ -0,0 +1,241 @@
+use super::{
+    APIError, SlackConfig, link_account_message, linked_principal, messages, random_idea_message,
+    verify_request,
+};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpRequest, post, web};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+/// The fields of a Slack slash command which Rex makes use of.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SlackCommand {
+    pub team_id: String,
+    pub user_id: String,
+    pub command: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+#[utoipa::path(
+    tag = "chat",
+    summary = "Handle a Slack slash command",
+    description = "Receives the slash commands sent by a Slack app, such as `/rex random outdoor`. Requests must be signed with the Slack app's signing secret.",
+    request_body(content = SlackCommand, content_type = "application/x-www-form-urlencoded"),
+    params(
+        ("X-Slack-Request-Timestamp" = String, Header, description = "The time at which Slack sent the request."),
+        ("X-Slack-Signature" = String, Header, description = "The signature Slack generated for the request.")
+    ),
+    responses(
+        (status = 200, description = "The message which should be shown in response to the command.", content_type = "application/json", body = Object),
+        (status = 401, description = "The request was not signed by Slack.", body = APIError),
+        (status = 404, description = "The Slack integration has not been configured.", body = APIError)
+    )
+)]
+#[instrument(err, skip(req, body, state, config), fields(otel.kind = "internal"))]
+#[post("/api/v3/chat/slack/command")]
+async fn slack_command_v3(
+    (req, body, state, config): (
+        HttpRequest,
+        web::Bytes,
+        web::Data<GlobalState>,
+        web::Data<SlackConfig>,
+    ),
+) -> Result<web::Json<serde_json::Value>, APIError> {
+    verify_request(&config, &req, &body)?;
+
+    let command: SlackCommand = serde_urlencoded::from_bytes(&body).map_err(|err| {
+        warn!("Unable to parse a Slack command: {}", err);
+        APIError::new(
+            400,
+            "Bad Request",
+            "The command you sent could not be parsed. Please check it and try again.",
+        )
+    })?;
+
+    let mut words = command.text.split_whitespace();
+    let action = words.next().unwrap_or("random");
+    let args = words.collect::<Vec<_>>().join(" ");
+
+    if action == "help" {
+        return Ok(web::Json(messages::help(&command.command)));
+    }
+
+    let Some(principal_id) = linked_principal(&state, &command.team_id, &command.user_id).await?
+    else {
+        return link_account_message(&config, &req, &command.team_id, &command.user_id)
+            .map(web::Json);
+    };
+
+    match action {
+        "random" => random_idea_message(&state, principal_id, Some(args).filter(|t| !t.is_empty()))
+            .await
+            .map(web::Json),
+        "link" => Ok(web::Json(messages::ephemeral(
+            "Your chat account is already linked to Rex. If you'd like to link it to a different account, unlink it first.",
+        ))),
+        "unlink" => {
+            state
+                .store
+                .send(
+                    RemoveChatLink {
+                        chat_user_hash: chat_user_hash(&command.team_id, &command.user_id),
+                    }
+                    .trace(),
+                )
+                .await??;
+
+            Ok(web::Json(messages::ephemeral(
+                "Your chat account has been unlinked from Rex.",
+            )))
+        }
+        _ => Ok(web::Json(messages::help(&command.command))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test::*;
+    use crate::api::test::*;
+    use crate::models::*;
+
+    async fn run(state: &GlobalState, text: &str) -> serde_json::Value {
+        let app = get_test_app(state.clone()).await;
+        let req = slack_request(
+            "/api/v3/chat/slack/command",
+            &format!(
+                "team_id=T123&user_id=U456&command=%2Frex&text={text}&response_url=https%3A%2F%2Fhooks.slack.com%2Fcommands%2F1"
+            ),
+        );
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        get_content(response).await
+    }
+
+    #[actix_rt::test]
+    async fn slack_command_v3_random() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreChatLink {
+                    team_id: "T123".into(),
+                    user_id: "U456".into(),
+                    principal_id: 1,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 2,
+                    name: "Go hiking".into(),
+                    description: "Somewhere with a view".into(),
+                    tags: ["outdoor".to_string()].into_iter().collect(),
+                    completed: false,
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 3,
+                    name: "Read a book".into(),
+                    description: "Something new".into(),
+                    tags: ["indoor".to_string()].into_iter().collect(),
+                    completed: false,
+                }
+            ]
+        );
+
+        let message = run(&state, "random+outdoor").await;
+        assert_eq!(message["response_type"], "in_channel");
+        assert_eq!(message["text"], "How about: Go hiking");
+        assert_eq!(message["blocks"][2]["elements"][1]["value"], "outdoor");
+
+        let message = run(&state, "random+board+games").await;
+        assert_eq!(message["response_type"], "ephemeral");
+    }
+
+    #[actix_rt::test]
+    async fn slack_command_v3_unlinked() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let message = run(&state, "random").await;
+        assert_eq!(message["response_type"], "ephemeral");
+
+        let url = message["blocks"][1]["elements"][0]["url"]
+            .as_str()
+            .expect("a link should be provided");
+
+        // The link must open the page which is served for it, with a token which that
+        // page can redeem.
+        let url = reqwest::Url::parse(url).expect("the link should be a valid URL");
+        let token = url
+            .query_pairs()
+            .find(|(key, _)| key == "token")
+            .map(|(_, value)| value.into_owned())
+            .expect("the link should carry a token");
+        assert!(token.starts_with("T123.U456."), "{token}");
+
+        let app = get_test_app(state.clone()).await;
+        let response = actix_web::test::call_service(
+            &app,
+            actix_web::test::TestRequest::get()
+                .uri(&format!(
+                    "{}?{}",
+                    url.path(),
+                    url.query().unwrap_or_default()
+                ))
+                .to_request(),
+        )
+        .await;
+        assert_status(response, actix_http::StatusCode::OK).await;
+
+        let content: ChatLinkV3 = test_request!(POST &format!("/api/v3/chat/links/{token}") => OK with content | state = state);
+        assert_eq!(content.team_id, "T123");
+    }
+
+    #[actix_rt::test]
+    async fn slack_command_v3_unlink() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreChatLink {
+                team_id: "T123".into(),
+                user_id: "U456".into(),
+                principal_id: 1,
+                created_at: chrono::Utc::now(),
+            }]
+        );
+
+        run(&state, "unlink").await;
+
+        state
+            .store
+            .send(GetChatLink {
+                chat_user_hash: chat_user_hash("T123", "U456"),
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("the link should have been removed");
+    }
+
+    #[actix_rt::test]
+    async fn slack_command_v3_bad_signature() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state).await;
+        let mut req = slack_request(
+            "/api/v3/chat/slack/command",
+            "team_id=T123&user_id=U456&command=%2Frex&text=random",
+        );
+        req.headers_mut().insert(
+            actix_web::http::header::HeaderName::from_static("x-slack-signature"),
+            actix_web::http::header::HeaderValue::from_static("v0=00"),
+        );
+
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::UNAUTHORIZED).await;
+    }
+}
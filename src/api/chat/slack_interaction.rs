@@ -0,0 +1,385 @@
+use super::{
+    APIError, SlackConfig, link_account_message, linked_principal, messages, random_idea_message,
+    verify_request,
+};
+use crate::api::authorize;
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpRequest, HttpResponse, post, web};
+use tracing::instrument;
+use utoipa::ToSchema;
+
+/// The form Slack submits when a user interacts with a message, containing the
+/// interaction's details as JSON.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SlackInteractionForm {
+    pub payload: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Interaction {
+    team: InteractionTeam,
+    user: InteractionUser,
+    #[serde(default)]
+    actions: Vec<InteractionAction>,
+    response_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InteractionTeam {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InteractionUser {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct InteractionAction {
+    action_id: String,
+    #[serde(default)]
+    value: String,
+}
+
+#[utoipa::path(
+    tag = "chat",
+    summary = "Handle a Slack message interaction",
+    description = "Receives the button presses on messages which Rex sent in response to a slash command, replying to Slack's `response_url` with the updated message. Requests must be signed with the Slack app's signing secret.",
+    request_body(content = SlackInteractionForm, content_type = "application/x-www-form-urlencoded"),
+    params(
+        ("X-Slack-Request-Timestamp" = String, Header, description = "The time at which Slack sent the request."),
+        ("X-Slack-Signature" = String, Header, description = "The signature Slack generated for the request.")
+    ),
+    responses(
+        (status = 200, description = "The interaction was handled."),
+        (status = 401, description = "The request was not signed by Slack.", body = APIError),
+        (status = 404, description = "The Slack integration has not been configured.", body = APIError)
+    )
+)]
+#[instrument(err, skip(req, body, state, config), fields(otel.kind = "internal"))]
+#[post("/api/v3/chat/slack/interaction")]
+async fn slack_interaction_v3(
+    (req, body, state, config): (
+        HttpRequest,
+        web::Bytes,
+        web::Data<GlobalState>,
+        web::Data<SlackConfig>,
+    ),
+) -> Result<HttpResponse, APIError> {
+    verify_request(&config, &req, &body)?;
+
+    let interaction: Interaction = serde_urlencoded::from_bytes::<SlackInteractionForm>(&body)
+        .map_err(|err| err.to_string())
+        .and_then(|form| serde_json::from_str(&form.payload).map_err(|err| err.to_string()))
+        .map_err(|err| {
+            warn!("Unable to parse a Slack interaction: {}", err);
+            APIError::new(
+                400,
+                "Bad Request",
+                "The interaction you sent could not be parsed. Please check it and try again.",
+            )
+        })?;
+
+    let (Some(action), Some(response_url)) = (
+        interaction.actions.first(),
+        interaction.response_url.as_deref(),
+    ) else {
+        return Ok(HttpResponse::Ok().finish());
+    };
+
+    let message = match linked_principal(&state, &interaction.team.id, &interaction.user.id).await?
+    {
+        None => link_account_message(&config, &req, &interaction.team.id, &interaction.user.id)?,
+        Some(principal_id) => match action.action_id.as_str() {
+            messages::COMPLETE_ACTION => {
+                match complete_idea(&state, principal_id, &action.value).await {
+                    Ok(idea) => messages::completed(&idea, &interaction.user.id),
+                    Err(err) if err.code < 500 => messages::ephemeral(err.message),
+                    Err(err) => return Err(err),
+                }
+            }
+            messages::REROLL_ACTION => {
+                let mut message = random_idea_message(
+                    &state,
+                    principal_id,
+                    Some(action.value.clone()).filter(|t| !t.is_empty()),
+                )
+                .await?;
+                message["replace_original"] = serde_json::Value::Bool(true);
+                message
+            }
+            _ => return Ok(HttpResponse::Ok().finish()),
+        },
+    };
+
+    respond(response_url, &message).await;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Marks the idea referenced by a button's value as completed, provided the principal
+/// is permitted to do so.
+async fn complete_idea(
+    state: &GlobalState,
+    principal_id: u128,
+    value: &str,
+) -> Result<Idea, APIError> {
+    let (cid, id) = value
+        .split_once('/')
+        .and_then(|(cid, id)| {
+            Some((
+                u128::from_str_radix(cid, 16).ok()?,
+                u128::from_str_radix(id, 16).ok()?,
+            ))
+        })
+        .ok_or_else(|| {
+            APIError::new(
+                400,
+                "Bad Request",
+                "The idea you selected could not be identified.",
+            )
+        })?;
+
+    authorize(state, cid, principal_id, Permission::IdeaComplete).await?;
+
+    let idea = state
+        .store
+        .send(
+            GetIdea {
+                id,
+                collection: cid,
+            }
+            .trace(),
+        )
+        .await??;
+
+    state
+        .store
+        .send(
+            StoreIdea {
+                id,
+                collection: cid,
+                name: idea.name,
+                description: idea.description,
+                tags: idea.tags,
+                completed: true,
+            }
+            .trace(),
+        )
+        .await?
+}
+
+/// Sends a message to the `response_url` which Slack provided with an interaction.
+#[instrument(skip(message), fields(otel.kind = "client", http.method = "POST"))]
+async fn respond(response_url: &str, message: &serde_json::Value) {
+    let result = reqwest::Client::new()
+        .post(response_url)
+        .header("Content-Type", "application/json")
+        .body(message.to_string())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+
+    if let Err(err) = result {
+        error!("Failed to respond to a Slack interaction: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test::*;
+    use crate::api::test::*;
+    use crate::models::*;
+    use actix_web::{App, HttpResponse, HttpServer, web};
+    use std::sync::{Arc, Mutex};
+
+    type Responses = Arc<Mutex<Vec<serde_json::Value>>>;
+
+    /// Starts a local HTTP server which records the messages posted to it in place of Slack.
+    fn start_response_url() -> (String, Responses) {
+        let responses: Responses = Default::default();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/actions/1", listener.local_addr().unwrap());
+
+        let data = web::Data::new(responses.clone());
+        let server = HttpServer::new(move || {
+            App::new().app_data(data.clone()).route(
+                "/actions/1",
+                web::post().to(
+                    |body: web::Json<serde_json::Value>, responses: web::Data<Responses>| async move {
+                        responses.lock().unwrap().push(body.into_inner());
+                        HttpResponse::Ok().finish()
+                    },
+                ),
+            )
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        actix_rt::spawn(server);
+
+        (url, responses)
+    }
+
+    async fn interact(state: &GlobalState, response_url: &str, action_id: &str, value: &str) {
+        let payload = serde_json::json!({
+            "type": "block_actions",
+            "team": { "id": "T123" },
+            "user": { "id": "U456" },
+            "response_url": response_url,
+            "actions": [{ "action_id": action_id, "value": value }],
+        });
+        let body = serde_urlencoded::to_string([("payload", payload.to_string())]).unwrap();
+
+        let app = get_test_app(state.clone()).await;
+        let response = actix_web::test::call_service(
+            &app,
+            slack_request("/api/v3/chat/slack/interaction", &body),
+        )
+        .await;
+        assert_status(response, actix_http::StatusCode::OK).await;
+    }
+
+    #[actix_rt::test]
+    async fn slack_interaction_v3_complete() {
+        test_log_init();
+
+        let (url, responses) = start_response_url();
+
+        test_state!(
+            state = [
+                StoreChatLink {
+                    team_id: "T123".into(),
+                    user_id: "U456".into(),
+                    principal_id: 1,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 1,
+                    role: Role::Owner,
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 2,
+                    name: "Go hiking".into(),
+                    description: "Somewhere with a view".into(),
+                    tags: Default::default(),
+                    completed: false,
+                }
+            ]
+        );
+
+        interact(
+            &state,
+            &url,
+            "rex.complete",
+            "00000000000000000000000000000001/00000000000000000000000000000002",
+        )
+        .await;
+
+        let idea = state
+            .store
+            .send(GetIdea {
+                collection: 1,
+                id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the idea should exist");
+        assert!(idea.completed);
+
+        let responses = responses.lock().unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["replace_original"], true);
+        assert_eq!(responses[0]["text"], "Go hiking has been marked as done.");
+    }
+
+    #[actix_rt::test]
+    async fn slack_interaction_v3_complete_forbidden() {
+        test_log_init();
+
+        let (url, responses) = start_response_url();
+
+        test_state!(
+            state = [
+                StoreChatLink {
+                    team_id: "T123".into(),
+                    user_id: "U456".into(),
+                    principal_id: 1,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 1,
+                    role: Role::Viewer,
+                },
+                StoreIdea {
+                    collection: 7,
+                    id: 2,
+                    name: "Go hiking".into(),
+                    description: "Somewhere with a view".into(),
+                    tags: Default::default(),
+                    completed: false,
+                }
+            ]
+        );
+
+        interact(
+            &state,
+            &url,
+            "rex.complete",
+            "00000000000000000000000000000007/00000000000000000000000000000002",
+        )
+        .await;
+
+        let idea = state
+            .store
+            .send(GetIdea {
+                collection: 7,
+                id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the idea should exist");
+        assert!(!idea.completed);
+
+        let responses = responses.lock().unwrap();
+        assert_eq!(responses[0]["response_type"], "ephemeral");
+    }
+
+    #[actix_rt::test]
+    async fn slack_interaction_v3_reroll() {
+        test_log_init();
+
+        let (url, responses) = start_response_url();
+
+        test_state!(
+            state = [
+                StoreChatLink {
+                    team_id: "T123".into(),
+                    user_id: "U456".into(),
+                    principal_id: 1,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 2,
+                    name: "Go hiking".into(),
+                    description: "Somewhere with a view".into(),
+                    tags: ["outdoor".to_string()].into_iter().collect(),
+                    completed: false,
+                }
+            ]
+        );
+
+        interact(&state, &url, "rex.reroll", "outdoor").await;
+
+        let responses = responses.lock().unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["replace_original"], true);
+        assert_eq!(responses[0]["text"], "How about: Go hiking");
+    }
+}
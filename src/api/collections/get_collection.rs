@@ -1,9 +1,21 @@
 use super::CollectionFilter;
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "collections",
+    summary = "Get a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The requested collection.", body = CollectionV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/collection/{collection}")]
 async fn get_collection_v3(
@@ -19,6 +31,8 @@ async fn get_collection_v3(
     let cid = parse_uuid!(info.collection, "collection ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
+    authorize(&state, cid, uid, Permission::CollectionRead).await?;
+
     state
         .store
         .send(
@@ -42,11 +56,18 @@ mod tests {
         test_log_init();
 
         test_state!(
-            state = [StoreCollection {
-                collection_id: 1,
-                principal_id: 0,
-                name: "Test Collection".into(),
-            }]
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 0,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Viewer,
+                }
+            ]
         );
 
         let content: CollectionV3 = test_request!(GET "/api/v3/collection/00000000000000000000000000000001" => OK with content | state = state);
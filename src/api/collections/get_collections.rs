@@ -3,6 +3,14 @@ use crate::{api::ensure_user_collection, models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "collections",
+    summary = "List your collections",
+    responses(
+        (status = 200, description = "The collections you have access to.", body = [CollectionV3])
+    ),
+    security(("oidc" = ["Collections.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/collections")]
 async fn get_collections_v3(
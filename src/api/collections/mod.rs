@@ -4,8 +4,9 @@ mod new_collection;
 mod remove_collection;
 mod store_collection;
 
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize, authorize_collection_entry};
 use actix_web::web;
+use utoipa::OpenApi;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_collection::get_collection_v3)
@@ -15,6 +16,16 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(remove_collection::remove_collection_v3);
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_collection::get_collection_v3,
+    get_collections::get_collections_v3,
+    new_collection::new_collection_v3,
+    store_collection::store_collection_v3,
+    remove_collection::remove_collection_v3,
+))]
+pub struct ApiDoc;
+
 #[derive(Debug, Deserialize, Serialize)]
 struct CollectionFilter {
     collection: String,
@@ -3,6 +3,16 @@ use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{post, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "collections",
+    summary = "Create a collection",
+    request_body = CollectionV3,
+    responses(
+        (status = 201, description = "The collection which was created.", body = CollectionV3),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[post("/api/v3/collections")]
 async fn new_collection_v3(
@@ -11,6 +21,8 @@ async fn new_collection_v3(
     require_role!(token, "Administrator", "User");
     require_scope!(token, "Collections.Write");
 
+    collection.validate()?;
+
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     let collection = state
@@ -62,4 +74,15 @@ mod tests {
         );
         assert_eq!(content.name, "Test Collection".to_string());
     }
+
+    #[actix_rt::test]
+    async fn new_collection_v3_invalid() {
+        test_log_init();
+
+        test_request!(POST "/api/v3/collections", CollectionV3 {
+            id: None,
+            user_id: None,
+            name: " ".into(),
+        } => UNPROCESSABLE_ENTITY);
+    }
 }
@@ -1,9 +1,21 @@
 use super::CollectionFilter;
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{HttpResponse, delete, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "collections",
+    summary = "Remove a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 204, description = "The collection was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[delete("/api/v3/collection/{collection}")]
 async fn remove_collection_v3(
@@ -19,6 +31,10 @@ async fn remove_collection_v3(
     let cid = parse_uuid!(info.collection, "collection ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
+    // Removing a collection only removes it from the caller's own list, so any member
+    // who can see it may do so.
+    authorize(&state, cid, uid, Permission::CollectionRead).await?;
+
     state
         .store
         .send(
@@ -1,9 +1,22 @@
 use super::CollectionFilter;
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize_collection_entry};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{put, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "collections",
+    summary = "Store a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    request_body = CollectionV3,
+    responses(
+        (status = 200, description = "The collection which was stored.", body = CollectionV3),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[put("/api/v3/collection/{collection}")]
 async fn store_collection_v3(
@@ -17,10 +30,16 @@ async fn store_collection_v3(
     require_role!(token, "Administrator", "User");
     require_scope!(token, "Collections.Write");
 
+    collection.validate()?;
+
     let cid = parse_uuid!(info.collection, "collection ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
-    state
+    // Storing a collection only changes the caller's own entry in their list, so any
+    // member may rename it, while a new collection is created with the caller as its owner.
+    let is_new = authorize_collection_entry(&state, cid, uid).await?;
+
+    let collection = state
         .store
         .send(
             StoreCollection {
@@ -30,8 +49,23 @@ async fn store_collection_v3(
             }
             .trace(),
         )
-        .await?
-        .map(|collection| collection.into())
+        .await??;
+
+    if is_new {
+        state
+            .store
+            .send(
+                StoreRoleAssignment {
+                    principal_id: uid,
+                    collection_id: cid,
+                    role: Role::Owner,
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    Ok(collection.into())
 }
 
 #[cfg(test)]
@@ -56,4 +90,68 @@ mod tests {
         );
         assert_eq!(content.name, "Test Collection".to_string());
     }
+
+    #[actix_rt::test]
+    async fn store_collection_v3_viewer() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 0,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Viewer,
+                }
+            ]
+        );
+
+        let content: CollectionV3 = test_request!(PUT "/api/v3/collection/00000000000000000000000000000001", CollectionV3 {
+            id: None,
+            user_id: None,
+            name: "Renamed".into(),
+        } => OK with content | state = state);
+        assert_eq!(content.name, "Renamed".to_string());
+
+        let role = state
+            .store
+            .send(GetRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the role assignment should still exist");
+        assert_eq!(role.role, Role::Viewer);
+    }
+
+    #[actix_rt::test]
+    async fn store_collection_v3_not_member() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 1,
+                    name: "Someone Else's".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 1,
+                    role: Role::Owner,
+                }
+            ]
+        );
+
+        test_request!(PUT "/api/v3/collection/00000000000000000000000000000001", CollectionV3 {
+            id: None,
+            user_id: None,
+            name: "Mine Now".into(),
+        } => FORBIDDEN | state = state);
+    }
 }
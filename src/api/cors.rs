@@ -0,0 +1,288 @@
+use actix_cors::Cors;
+use actix_web::http::Method;
+
+/// The cross-origin resource sharing policy applied to the API, allowing browser
+/// clients hosted on other origins to call it directly.
+///
+/// In addition to the `cors` section of the configuration file, the policy may be
+/// configured through the following environment variables (optionally prefixed with `REX_`):
+///  - `CORS_ALLOWED_ORIGINS`: a comma separated list of origins, or `*` to allow any origin.
+///  - `CORS_ALLOWED_METHODS`: a comma separated list of HTTP methods.
+///  - `CORS_ALLOWED_HEADERS`: a comma separated list of request headers.
+///  - `CORS_ALLOW_CREDENTIALS`: `true` or `false`, whether credentials may be sent with
+///    cross-origin requests. This cannot be combined with allowing any origin.
+///  - `CORS_MAX_AGE`: the number of seconds for which preflight responses may be cached,
+///    or empty to leave it to the browser.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    #[serde(deserialize_with = "deserialize_methods")]
+    pub allowed_methods: Vec<Method>,
+    pub allowed_headers: Vec<String>,
+    pub allow_credentials: bool,
+    pub max_age: Option<usize>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![],
+            allowed_methods: vec![Method::GET, Method::POST, Method::PUT, Method::DELETE],
+            allowed_headers: vec!["Authorization".into(), "Content-Type".into()],
+            allow_credentials: false,
+            max_age: Some(3600),
+        }
+    }
+}
+
+impl CorsConfig {
+    #[cfg(test)]
+    fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> Result<Self, String> {
+        Self::default().with_vars(var)
+    }
+
+    /// Overrides the parts of the policy which are set by the given environment variables,
+    /// failing if any of them cannot be understood rather than quietly ignoring them.
+    pub fn with_vars<F: Fn(&str) -> Option<String>>(self, var: F) -> Result<Self, String> {
+        let invalid = |name: &str, value: &str, err: String| {
+            format!("the {name} environment variable ('{value}') is not valid: {err}")
+        };
+
+        Ok(Self {
+            allowed_origins: var("CORS_ALLOWED_ORIGINS")
+                .map(|v| split_list(&v))
+                .unwrap_or(self.allowed_origins),
+            allowed_methods: match var("CORS_ALLOWED_METHODS") {
+                Some(v) => parse_methods(split_list(&v))
+                    .map_err(|err| invalid("CORS_ALLOWED_METHODS", &v, err))?,
+                None => self.allowed_methods,
+            },
+            allowed_headers: var("CORS_ALLOWED_HEADERS")
+                .map(|v| split_list(&v))
+                .unwrap_or(self.allowed_headers),
+            allow_credentials: match var("CORS_ALLOW_CREDENTIALS") {
+                Some(v) => v.trim().to_lowercase().parse().map_err(|_| {
+                    invalid(
+                        "CORS_ALLOW_CREDENTIALS",
+                        &v,
+                        "it must be either true or false".into(),
+                    )
+                })?,
+                None => self.allow_credentials,
+            },
+            // An empty value stops browsers from being told how long to cache preflight responses.
+            max_age: match var("CORS_MAX_AGE") {
+                Some(v) if v.trim().is_empty() => None,
+                Some(v) => Some(
+                    v.trim()
+                        .parse()
+                        .map_err(|err| invalid("CORS_MAX_AGE", &v, format!("{err}")))?,
+                ),
+                None => self.max_age,
+            },
+        })
+    }
+
+    pub fn build(&self) -> Cors {
+        let mut cors = Cors::default()
+            .allowed_methods(self.allowed_methods.clone())
+            .allowed_headers(self.allowed_headers.iter().map(String::as_str))
+            .max_age(self.max_age);
+
+        for origin in self.allowed_origins.iter() {
+            cors = if origin == "*" {
+                cors.allow_any_origin()
+            } else {
+                cors.allowed_origin(origin)
+            };
+        }
+
+        if self.allow_credentials {
+            cors = cors.supports_credentials();
+        }
+
+        cors
+    }
+}
+
+/// Parses the allowed methods, only accepting the standard HTTP methods so that a typo is
+/// reported instead of being allowed as an extension method which no client will send.
+fn parse_methods(methods: Vec<String>) -> Result<Vec<Method>, String> {
+    methods
+        .iter()
+        .map(|m| match m.to_uppercase().parse::<Method>() {
+            Ok(method)
+                if [
+                    Method::GET,
+                    Method::HEAD,
+                    Method::POST,
+                    Method::PUT,
+                    Method::DELETE,
+                    Method::CONNECT,
+                    Method::OPTIONS,
+                    Method::TRACE,
+                    Method::PATCH,
+                ]
+                .contains(&method) =>
+            {
+                Ok(method)
+            }
+            _ => Err(format!("'{m}' is not a recognized HTTP method")),
+        })
+        .collect()
+}
+
+fn deserialize_methods<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Method>, D::Error> {
+    parse_methods(<Vec<String> as serde::Deserialize>::deserialize(
+        deserializer,
+    )?)
+    .map_err(serde::de::Error::custom)
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn config_from_vars() {
+        let config = CorsConfig::from_vars(|name| match name {
+            "CORS_ALLOWED_ORIGINS" => {
+                Some("https://app.example.com, https://other.example.com".into())
+            }
+            "CORS_ALLOWED_METHODS" => Some("get,post".into()),
+            "CORS_ALLOW_CREDENTIALS" => Some("true".into()),
+            "CORS_MAX_AGE" => Some("60".into()),
+            _ => None,
+        })
+        .expect("the variables should be valid");
+
+        assert_eq!(
+            config.allowed_origins,
+            vec!["https://app.example.com", "https://other.example.com"]
+        );
+        assert_eq!(config.allowed_methods, vec![Method::GET, Method::POST]);
+        assert_eq!(
+            config.allowed_headers,
+            CorsConfig::default().allowed_headers
+        );
+        assert!(config.allow_credentials);
+        assert_eq!(config.max_age, Some(60));
+    }
+
+    #[test]
+    fn config_from_invalid_vars() {
+        for (name, value) in [
+            ("CORS_ALLOWED_METHODS", "GET,FETCH"),
+            ("CORS_ALLOW_CREDENTIALS", "1"),
+            ("CORS_MAX_AGE", "an hour"),
+        ] {
+            let err = CorsConfig::from_vars(|n| (n == name).then(|| value.to_string()))
+                .expect_err("the variable should be rejected");
+            assert!(err.contains(name), "{err}");
+        }
+
+        assert_eq!(
+            CorsConfig::from_vars(|n| (n == "CORS_MAX_AGE").then(String::new))
+                .expect("an empty max age should be accepted")
+                .max_age,
+            None
+        );
+    }
+
+    #[actix_rt::test]
+    async fn preflight_allowed_origin() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/api/v3/collection/00000000000000000000000000000001/ideas")
+            .insert_header(("Origin", "https://app.example.com"))
+            .insert_header(("Access-Control-Request-Method", "POST"))
+            .insert_header(("Access-Control-Request-Headers", "authorization"))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+
+        assert_eq!(
+            response
+                .headers()
+                .get("Access-Control-Allow-Origin")
+                .expect("an allowed origin header"),
+            "https://app.example.com"
+        );
+        assert!(
+            response
+                .headers()
+                .get("Access-Control-Allow-Methods")
+                .expect("an allowed methods header")
+                .to_str()
+                .expect("a valid header value")
+                .contains("POST")
+        );
+    }
+
+    #[actix_rt::test]
+    async fn preflight_unknown_origin() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::default()
+            .method(Method::OPTIONS)
+            .uri("/api/v3/ideas")
+            .insert_header(("Origin", "https://evil.example.com"))
+            .insert_header(("Access-Control-Request-Method", "GET"))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        assert!(
+            response
+                .headers()
+                .get("Access-Control-Allow-Origin")
+                .is_none()
+        );
+    }
+
+    #[actix_rt::test]
+    async fn simple_request_allowed_origin() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v1/health")
+            .insert_header(("Origin", "https://app.example.com"))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+
+        assert_eq!(
+            response
+                .headers()
+                .get("Access-Control-Allow-Origin")
+                .expect("an allowed origin header"),
+            "https://app.example.com"
+        );
+    }
+}
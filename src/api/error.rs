@@ -1,12 +1,55 @@
 use actix_http::body::BoxBody;
 use actix_web::{HttpResponse, error, http::StatusCode};
 use std::fmt;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct APIError {
     pub code: u16,
     pub error: String,
     pub message: String,
+
+    #[serde(skip)]
+    pub kind: Option<ErrorKind>,
+    #[serde(skip)]
+    pub fields: Vec<FieldError>,
+}
+
+/// A machine readable classification of an error, exposed as the `type` of an
+/// RFC 7807 problem document. These identifiers are part of the public API and
+/// must not be changed once published.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    Unauthorized,
+    ForbiddenRole,
+    ForbiddenScope,
+    NotFound,
+    Validation,
+    Conflict,
+    UpstreamUnavailable,
+}
+
+impl ErrorKind {
+    pub fn type_uri(&self) -> &'static str {
+        match self {
+            ErrorKind::Unauthorized => "https://rex.sierrasoftworks.com/problems/unauthorized",
+            ErrorKind::ForbiddenRole => "https://rex.sierrasoftworks.com/problems/forbidden-role",
+            ErrorKind::ForbiddenScope => "https://rex.sierrasoftworks.com/problems/forbidden-scope",
+            ErrorKind::NotFound => "https://rex.sierrasoftworks.com/problems/not-found",
+            ErrorKind::Validation => "https://rex.sierrasoftworks.com/problems/validation",
+            ErrorKind::Conflict => "https://rex.sierrasoftworks.com/problems/conflict",
+            ErrorKind::UpstreamUnavailable => {
+                "https://rex.sierrasoftworks.com/problems/upstream-unavailable"
+            }
+        }
+    }
+}
+
+/// Describes a problem with a specific field in the body of a request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
 }
 
 impl APIError {
@@ -16,6 +59,7 @@ impl APIError {
             "Unauthorized",
             "You have not provided a valid authentication token. Please authenticate and try again.",
         )
+        .with_kind(ErrorKind::Unauthorized)
     }
 
     pub fn new(code: u16, error: &str, message: &str) -> Self {
@@ -23,8 +67,37 @@ impl APIError {
             code,
             error: error.to_string(),
             message: message.to_string(),
+            kind: None,
+            fields: vec![],
         }
     }
+
+    pub fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    pub fn with_field(mut self, field: &str, message: &str) -> Self {
+        self.fields.push(FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        });
+        self
+    }
+
+    /// The kind of error, falling back to one inferred from the status code when
+    /// it has not been set explicitly.
+    pub fn kind(&self) -> Option<ErrorKind> {
+        self.kind.or(match self.code {
+            400 | 422 => Some(ErrorKind::Validation),
+            401 => Some(ErrorKind::Unauthorized),
+            403 => Some(ErrorKind::ForbiddenRole),
+            404 => Some(ErrorKind::NotFound),
+            409 => Some(ErrorKind::Conflict),
+            502..=504 => Some(ErrorKind::UpstreamUnavailable),
+            _ => None,
+        })
+    }
 }
 
 impl error::ResponseError for APIError {
@@ -54,6 +127,7 @@ impl From<azure_core::error::Error> for APIError {
             "Internal Server Error",
             "We ran into a problem, this has been reported and will be looked at.",
         )
+        .with_kind(ErrorKind::UpstreamUnavailable)
     }
 }
 
@@ -66,6 +140,7 @@ impl From<actix::MailboxError> for APIError {
             "Internal Server Error",
             "We ran into a problem, this has been reported and will be looked at.",
         )
+        .with_kind(ErrorKind::UpstreamUnavailable)
     }
 }
 
@@ -78,5 +153,6 @@ impl From<azure_storage::Error> for APIError {
             "Internal Server Error",
             "We ran into a problem, this has been reported and will be looked at.",
         )
+        .with_kind(ErrorKind::UpstreamUnavailable)
     }
 }
@@ -0,0 +1,295 @@
+use super::{APIError, AuthToken, CollectionFilter, EventStream, authorize};
+use crate::models::*;
+use actix_web::{HttpRequest, HttpResponse, get, web};
+
+#[utoipa::path(
+    tag = "events",
+    summary = "Stream the changes made to a collection",
+    description = "Streams server-sent events describing the ideas and members which are added to, changed in, or removed from a collection. Clients which reconnect with a `Last-Event-ID` header will receive any recent events they missed.",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("Last-Event-ID" = Option<String>, Header, description = "The ID of the last event which was received, used to resume the feed.")
+    ),
+    responses(
+        (status = 200, description = "A stream of events describing changes to the collection.", content_type = "text/event-stream", body = CollectionEventV3)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
+#[instrument(err, skip(req, state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/events")]
+async fn get_collection_events_v3(
+    (req, info, state, token): (
+        HttpRequest,
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Read");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::IdeaRead).await?;
+
+    let last_event_id = req
+        .headers()
+        .get("Last-Event-ID")
+        .and_then(|id| id.to_str().ok())
+        .and_then(|id| id.parse().ok());
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(EventStream::new(state.events.clone(), cid, uid, last_event_id).into_stream()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+    use actix_web::body::{BoxBody, MessageBody};
+    use actix_web::test::TestRequest;
+    use std::pin::Pin;
+
+    /// Reads frames from the event stream until one which isn't a keep-alive is received.
+    async fn next_event(body: &mut BoxBody) -> String {
+        loop {
+            let frame = std::future::poll_fn(|cx| Pin::new(&mut *body).poll_next(cx))
+                .await
+                .expect("the stream should not end")
+                .expect("the stream should not fail");
+
+            let frame = String::from_utf8_lossy(&frame).into_owned();
+            if !frame.starts_with(':') {
+                return frame;
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_events_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 7,
+                principal_id: 0,
+                role: Role::Viewer,
+            }]
+        );
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000007/events")
+            .insert_header(("Authorization", auth_token()))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "text/event-stream"
+        );
+
+        let mut body = response.into_body();
+
+        test_state!(
+            ::state = [
+                StoreIdea {
+                    id: 1,
+                    collection: 8,
+                    name: "Other Idea".into(),
+                    description: "This idea is in another collection".into(),
+                    tags: Default::default(),
+                    completed: false,
+                },
+                StoreIdea {
+                    id: 1,
+                    collection: 7,
+                    name: "Test Idea".into(),
+                    description: "This is a test idea".into(),
+                    tags: Default::default(),
+                    completed: false,
+                },
+                StoreIdea {
+                    id: 1,
+                    collection: 7,
+                    name: "Test Idea".into(),
+                    description: "This is a test idea".into(),
+                    tags: Default::default(),
+                    completed: true,
+                },
+                RemoveIdea {
+                    collection: 7,
+                    id: 1,
+                }
+            ]
+        );
+
+        let created = next_event(&mut body).await;
+        assert!(created.contains("event: idea.created\n"), "{created}");
+        assert!(created.contains("\"collection\":\"00000000000000000000000000000007\""));
+        assert!(created.contains("\"name\":\"Test Idea\""));
+
+        let completed = next_event(&mut body).await;
+        assert!(completed.contains("event: idea.completed\n"), "{completed}");
+
+        let removed = next_event(&mut body).await;
+        assert!(removed.contains("event: idea.removed\n"), "{removed}");
+        assert!(removed.contains("\"ideaId\":\"00000000000000000000000000000001\""));
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_events_v3_resume() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 1,
+                    role: Role::Viewer,
+                },
+                RemoveRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 1,
+                }
+            ]
+        );
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000007/events")
+            .insert_header(("Authorization", auth_token()))
+            .insert_header(("Last-Event-ID", "0"))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        let mut body = response.into_body();
+
+        let owner = next_event(&mut body).await;
+        assert!(owner.contains("event: member.added\n"), "{owner}");
+        assert!(owner.contains("\"userId\":\"00000000000000000000000000000000\""));
+
+        let added = next_event(&mut body).await;
+        assert!(added.contains("event: member.added\n"), "{added}");
+        assert!(added.contains("\"role\":\"Viewer\""));
+
+        let removed = next_event(&mut body).await;
+        assert!(removed.contains("event: member.removed\n"), "{removed}");
+
+        let last_id: u64 = removed
+            .lines()
+            .find_map(|line| line.strip_prefix("id: "))
+            .expect("the event should have an ID")
+            .parse()
+            .expect("the event ID should be numeric");
+
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000007/events")
+            .insert_header(("Authorization", auth_token()))
+            .insert_header(("Last-Event-ID", (last_id - 1).to_string()))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        let mut body = response.into_body();
+
+        let resumed = next_event(&mut body).await;
+        assert!(resumed.contains("event: member.removed\n"), "{resumed}");
+    }
+
+    /// Reads frames from the event stream, skipping keep-alives, until it ends.
+    async fn assert_ended(body: &mut BoxBody) {
+        loop {
+            match std::future::poll_fn(|cx| Pin::new(&mut *body).poll_next(cx)).await {
+                None => return,
+                Some(frame) => {
+                    let frame = frame.expect("the stream should not fail");
+                    let frame = String::from_utf8_lossy(&frame);
+                    assert!(frame.starts_with(':'), "unexpected event {frame}");
+                }
+            }
+        }
+    }
+
+    async fn open_stream(state: &GlobalState) -> BoxBody {
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000007/events")
+            .insert_header(("Authorization", auth_token()))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::OK)
+            .await
+            .into_body()
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_events_v3_member_removed() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 7,
+                principal_id: 0,
+                role: Role::Viewer,
+            }]
+        );
+
+        let mut body = open_stream(&state).await;
+
+        test_state!(
+            ::state = [
+                RemoveRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 0,
+                },
+                StoreIdea {
+                    id: 1,
+                    collection: 7,
+                    name: "Secret Idea".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let removed = next_event(&mut body).await;
+        assert!(removed.contains("event: member.removed\n"), "{removed}");
+        assert_ended(&mut body).await;
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_events_v3_shutdown() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 7,
+                principal_id: 0,
+                role: Role::Viewer,
+            }]
+        );
+
+        let mut body = open_stream(&state).await;
+
+        state.events.close();
+        assert_ended(&mut body).await;
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_events_v3_forbidden() {
+        test_log_init();
+
+        test_request!(GET "/api/v3/collection/00000000000000000000000000000007/events" => FORBIDDEN);
+    }
+}
@@ -0,0 +1,121 @@
+mod get_collection_events;
+
+use super::{APIError, AuthToken, authorize};
+use crate::models::*;
+use actix_web::web::{self, Bytes};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::{
+    broadcast::{Receiver, error::RecvError},
+    watch,
+};
+use utoipa::OpenApi;
+
+/// How often a comment is sent to idle clients to keep intermediate proxies from
+/// closing the connection.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_collection_events::get_collection_events_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(get_collection_events::get_collection_events_v3))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionFilter {
+    collection: String,
+}
+
+/// Produces the server-sent event frames for a collection, starting with any retained
+/// events published after `last_event_id` and then following the live feed.
+///
+/// The stream ends once its subscriber is removed from the collection, since they are no
+/// longer allowed to see its events, or when the [`EventBus`] is closed.
+struct EventStream {
+    events: EventBus,
+    collection_id: u128,
+    principal_id: u128,
+    last_event_id: u64,
+    pending: VecDeque<CollectionEvent>,
+    receiver: Receiver<CollectionEvent>,
+    closed: watch::Receiver<bool>,
+    ended: bool,
+    keep_alive: tokio::time::Interval,
+}
+
+impl EventStream {
+    fn new(
+        events: EventBus,
+        collection_id: u128,
+        principal_id: u128,
+        last_event_id: Option<u64>,
+    ) -> Self {
+        let (replay, receiver) = events.subscribe(collection_id, last_event_id);
+
+        Self {
+            closed: events.closed(),
+            events,
+            collection_id,
+            principal_id,
+            last_event_id: last_event_id.unwrap_or_default(),
+            pending: replay.into(),
+            receiver,
+            ended: false,
+            keep_alive: tokio::time::interval(KEEP_ALIVE_INTERVAL),
+        }
+    }
+
+    fn into_stream(self) -> impl futures::Stream<Item = Result<Bytes, std::convert::Infallible>> {
+        futures::stream::unfold(self, |mut stream| async move {
+            let frame = stream.next_frame().await?;
+            Some((Ok(frame), stream))
+        })
+    }
+
+    async fn next_frame(&mut self) -> Option<Bytes> {
+        if self.ended {
+            return None;
+        }
+
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                // Events may be seen twice after recovering from a lagging receiver.
+                if event.id > self.last_event_id {
+                    self.last_event_id = event.id;
+                    self.ended = matches!(event.kind, CollectionEventKind::MemberRemoved(id) if id == self.principal_id);
+                    return Some(Self::frame(event));
+                }
+
+                continue;
+            }
+
+            tokio::select! {
+                received = self.receiver.recv() => match received {
+                    Ok(event) if event.collection_id == self.collection_id => self.pending.push_back(event),
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "The event feed for collection {:0>32x} fell behind by {} events, replaying them from history.",
+                            self.collection_id, skipped
+                        );
+                        self.pending
+                            .extend(self.events.since(self.collection_id, self.last_event_id));
+                    }
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = self.closed.wait_for(|closed| *closed) => return None,
+                _ = self.keep_alive.tick() => return Some(Bytes::from_static(b": keep-alive\n\n")),
+            }
+        }
+    }
+
+    fn frame(event: CollectionEvent) -> Bytes {
+        let id = event.id;
+        let name = event.kind.name();
+        let data = serde_json::to_string(&CollectionEventV3::from(event)).unwrap_or_default();
+
+        Bytes::from(format!("id: {id}\nevent: {name}\ndata: {data}\n\n"))
+    }
+}
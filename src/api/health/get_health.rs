@@ -1,8 +1,21 @@
-use crate::api::APIError;
-use crate::{models::*, telemetry::TraceMessageExt};
-use actix_web::{get, web};
+use crate::api::{APIError, OidcActor, auth::CheckReady};
+use crate::{models::*, telemetry::TraceMessageExt, ui::UiConfig};
+use actix::Addr;
+use actix_rt::time::timeout;
+use actix_web::{HttpRequest, get, web};
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
 use tracing::instrument;
 
+/// How long each dependency has to respond before it is considered to be unavailable.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "The current health of the service.", body = HealthV1))
+)]
 #[instrument(err, skip(state), fields(otel.kind = "internal"))]
 #[get("/api/v1/health")]
 pub async fn get_health_v1(state: web::Data<GlobalState>) -> Result<HealthV1, APIError> {
@@ -13,6 +26,10 @@ pub async fn get_health_v1(state: web::Data<GlobalState>) -> Result<HealthV1, AP
         .map(|health| health.into())
 }
 
+#[utoipa::path(
+    tag = "health",
+    responses((status = 200, description = "The current health of the service.", body = HealthV2))
+)]
 #[instrument(err, skip(state), fields(otel.kind = "internal"))]
 #[get("/api/v2/health")]
 pub async fn get_health_v2(state: web::Data<GlobalState>) -> Result<HealthV2, APIError> {
@@ -23,6 +40,156 @@ pub async fn get_health_v2(state: web::Data<GlobalState>) -> Result<HealthV2, AP
         .map(|health| health.into())
 }
 
+#[utoipa::path(
+    tag = "health",
+    responses(
+        (status = 200, description = "The service and every dependency it relies on are ready to serve requests.", body = HealthV3),
+        (status = 503, description = "A dependency which the service relies on is unavailable.", body = HealthV3),
+    )
+)]
+#[instrument(err, skip(req, state, oidc), fields(otel.kind = "internal"))]
+#[get("/api/v3/health")]
+pub async fn get_health_v3(
+    (req, state, oidc): (
+        HttpRequest,
+        web::Data<GlobalState>,
+        web::Data<Addr<OidcActor>>,
+    ),
+) -> Result<HealthV3, APIError> {
+    let ui = req.app_data::<web::Data<UiConfig>>().cloned();
+
+    let ((store, started_at), auth, ui) =
+        futures::join!(probe_store(&state), probe_auth(&oidc), probe_ui(ui));
+
+    let components: BTreeMap<String, ComponentHealthV3> = [
+        ("store".to_string(), store),
+        ("auth".to_string(), auth),
+        ("ui".to_string(), ui),
+    ]
+    .into();
+
+    Ok(HealthV3 {
+        ok: components
+            .values()
+            .all(|c| !c.critical || c.status != ComponentStatus::Unhealthy),
+        started_at,
+        components,
+    })
+}
+
+async fn probe_store(
+    state: &GlobalState,
+) -> (ComponentHealthV3, Option<chrono::DateTime<chrono::Utc>>) {
+    let started = Instant::now();
+    match timeout(PROBE_TIMEOUT, state.store.send(GetHealth {}.trace())).await {
+        Ok(Ok(Ok(health))) if health.ok => (
+            component(true, ComponentStatus::Healthy, Some(started), None),
+            Some(health.started_at),
+        ),
+        Ok(Ok(Ok(health))) => (
+            component(
+                true,
+                ComponentStatus::Unhealthy,
+                Some(started),
+                Some("The store could not be reached.".into()),
+            ),
+            Some(health.started_at),
+        ),
+        Ok(Ok(Err(err))) => (
+            component(
+                true,
+                ComponentStatus::Unhealthy,
+                Some(started),
+                Some(err.to_string()),
+            ),
+            None,
+        ),
+        Ok(Err(err)) => (
+            component(
+                true,
+                ComponentStatus::Unhealthy,
+                Some(started),
+                Some(err.to_string()),
+            ),
+            None,
+        ),
+        Err(_) => (
+            component(
+                true,
+                ComponentStatus::Unhealthy,
+                Some(started),
+                Some("The store did not respond in time.".into()),
+            ),
+            None,
+        ),
+    }
+}
+
+async fn probe_auth(oidc: &Addr<OidcActor>) -> ComponentHealthV3 {
+    let started = Instant::now();
+    match timeout(PROBE_TIMEOUT, oidc.send(CheckReady)).await {
+        Ok(Ok(true)) => component(true, ComponentStatus::Healthy, Some(started), None),
+        Ok(Ok(false)) | Err(_) => component(
+            true,
+            ComponentStatus::Unhealthy,
+            Some(started),
+            Some("The OpenID Connect issuer's discovery document has not been loaded yet.".into()),
+        ),
+        Ok(Err(err)) => component(
+            true,
+            ComponentStatus::Unhealthy,
+            Some(started),
+            Some(err.to_string()),
+        ),
+    }
+}
+
+/// The UI is not critical, since the API remains usable while its backend is unavailable.
+async fn probe_ui(config: Option<web::Data<UiConfig>>) -> ComponentHealthV3 {
+    let Some(uri) = config.and_then(|c| c.backend_uri.clone()) else {
+        return component(false, ComponentStatus::Disabled, None, None);
+    };
+
+    let started = Instant::now();
+    let response = reqwest::Client::new()
+        .get(&uri)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await;
+
+    match response {
+        Ok(res) if !res.status().is_server_error() => {
+            component(false, ComponentStatus::Healthy, Some(started), None)
+        }
+        Ok(res) => component(
+            false,
+            ComponentStatus::Unhealthy,
+            Some(started),
+            Some(format!("The UI backend responded with {}.", res.status())),
+        ),
+        Err(err) => component(
+            false,
+            ComponentStatus::Unhealthy,
+            Some(started),
+            Some(format!("The UI backend could not be reached: {err}")),
+        ),
+    }
+}
+
+fn component(
+    critical: bool,
+    status: ComponentStatus,
+    started: Option<Instant>,
+    message: Option<String>,
+) -> ComponentHealthV3 {
+    ComponentHealthV3 {
+        status,
+        critical,
+        latency_ms: started.map(|s| s.elapsed().as_millis() as u64),
+        message,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::api::test::*;
@@ -36,6 +203,50 @@ mod tests {
         assert!(content.ok);
     }
 
+    #[actix_rt::test]
+    async fn health_v3() {
+        test_log_init();
+
+        let content: HealthV3 = test_request!(GET "/api/v3/health" => OK with content);
+        assert!(content.ok);
+        assert!(content.started_at.is_some());
+        assert_eq!(content.components["store"].status, ComponentStatus::Healthy);
+        assert!(content.components["store"].latency_ms.is_some());
+        assert_eq!(content.components["auth"].status, ComponentStatus::Healthy);
+        assert_eq!(content.components["ui"].status, ComponentStatus::Disabled);
+        assert!(!content.components["ui"].critical);
+    }
+
+    #[actix_rt::test]
+    async fn health_v3_unhealthy_ui() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = actix_web::test::init_service(
+            actix_web::App::new()
+                .app_data(actix_web::web::Data::new(state.clone()))
+                .app_data(actix_web::web::Data::new(actix::Actor::start(
+                    crate::api::OidcActor::new(Default::default()),
+                )))
+                .app_data(actix_web::web::Data::new(crate::ui::UiConfig {
+                    backend_uri: Some("http://127.0.0.1:1".into()),
+                }))
+                .service(super::get_health_v3),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/v3/health")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+
+        let content: HealthV3 = get_content(response).await;
+        assert!(content.ok);
+        assert_eq!(content.components["ui"].status, ComponentStatus::Unhealthy);
+    }
+
     #[actix_rt::test]
     async fn health_v2() {
         test_log_init();
@@ -1,8 +1,18 @@
 use actix_web::web;
+use utoipa::OpenApi;
 
 mod get_health;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_health::get_health_v1)
-        .service(get_health::get_health_v2);
+        .service(get_health::get_health_v2)
+        .service(get_health::get_health_v3);
 }
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_health::get_health_v1,
+    get_health::get_health_v2,
+    get_health::get_health_v3
+))]
+pub struct ApiDoc;
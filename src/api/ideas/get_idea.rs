@@ -1,9 +1,21 @@
-use super::{APIError, AuthToken, ensure_user_collection};
+use super::{APIError, AuthToken, authorize, ensure_user_collection};
 use super::{CollectionIdFilter, IdFilter};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get an idea (v1)",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 200, description = "The requested idea.", body = IdeaV1),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v1/idea/{id}")]
 async fn get_idea_v1(
@@ -28,6 +40,18 @@ async fn get_idea_v1(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get an idea (v2)",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 200, description = "The requested idea.", body = IdeaV2),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v2/idea/{id}")]
 async fn get_idea_v2(
@@ -52,6 +76,18 @@ async fn get_idea_v2(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get an idea",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 200, description = "The requested idea.", body = IdeaV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/idea/{id}")]
 async fn get_idea_v3(
@@ -64,6 +100,7 @@ async fn get_idea_v3(
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
+    authorize(&state, uid, uid, Permission::IdeaRead).await?;
 
     state
         .store
@@ -78,6 +115,19 @@ async fn get_idea_v3(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get an idea from a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 200, description = "The requested idea.", body = IdeaV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/collection/{collection}/idea/{id}")]
 async fn get_collection_idea_v3(
@@ -95,17 +145,7 @@ async fn get_collection_idea_v3(
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
-
-    state
-        .store
-        .send(
-            GetRoleAssignment {
-                principal_id: uid,
-                collection_id: cid,
-            }
-            .trace(),
-        )
-        .await??;
+    authorize(&state, cid, uid, Permission::IdeaRead).await?;
 
     state
         .store
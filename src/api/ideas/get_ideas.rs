@@ -1,8 +1,16 @@
-use super::{APIError, AuthToken, ensure_user_collection};
+use super::{APIError, AuthToken, authorize, ensure_user_collection};
 use super::{CollectionFilter, QueryFilter};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "List your ideas (v1)",
+    responses(
+        (status = 200, description = "The ideas in your collection.", body = [IdeaV1])
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v1/ideas")]
 async fn get_ideas_v1(
@@ -28,6 +36,17 @@ async fn get_ideas_v1(
         .map(|ideas| web::Json(ideas.iter().map(|i| i.clone().into()).collect()))
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "List your ideas (v2)",
+    params(
+        QueryFilter
+    ),
+    responses(
+        (status = 200, description = "The ideas in your collection which match the filter.", body = [IdeaV2])
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v2/ideas")]
 async fn get_ideas_v2(
@@ -52,6 +71,17 @@ async fn get_ideas_v2(
         .map(|ideas| web::Json(ideas.iter().map(|i| i.clone().into()).collect()))
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "List your ideas",
+    params(
+        QueryFilter
+    ),
+    responses(
+        (status = 200, description = "The ideas in your collection which match the filter.", body = [IdeaV3])
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/ideas")]
 async fn get_ideas_v3(
@@ -63,13 +93,7 @@ async fn get_ideas_v3(
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
-    state
-        .store
-        .send(GetRoleAssignment {
-            principal_id: uid,
-            collection_id: uid,
-        })
-        .await??;
+    authorize(&state, uid, uid, Permission::IdeaRead).await?;
 
     state
         .store
@@ -85,6 +109,18 @@ async fn get_ideas_v3(
         .map(|ideas| web::Json(ideas.iter().map(|i| i.clone().into()).collect()))
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "List the ideas in a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        QueryFilter
+    ),
+    responses(
+        (status = 200, description = "The ideas in the collection which match the filter.", body = [IdeaV3])
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/collection/{collection}/ideas")]
 async fn get_collection_ideas_v3(
@@ -102,16 +138,7 @@ async fn get_collection_ideas_v3(
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
-    state
-        .store
-        .send(
-            GetRoleAssignment {
-                principal_id: uid,
-                collection_id: cid,
-            }
-            .trace(),
-        )
-        .await??;
+    authorize(&state, cid, uid, Permission::IdeaRead).await?;
 
     state
         .store
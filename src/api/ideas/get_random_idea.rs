@@ -1,8 +1,17 @@
-use super::{APIError, AuthToken, ensure_user_collection};
+use super::{APIError, AuthToken, authorize, ensure_user_collection};
 use super::{CollectionFilter, QueryFilter};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get a random idea (v1)",
+    responses(
+        (status = 200, description = "A randomly selected idea.", body = IdeaV1),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v1/idea/random")]
 async fn get_random_idea_v1(
@@ -28,6 +37,18 @@ async fn get_random_idea_v1(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get a random idea (v2)",
+    params(
+        QueryFilter
+    ),
+    responses(
+        (status = 200, description = "A randomly selected idea which matches the filter.", body = IdeaV2),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v2/idea/random")]
 async fn get_random_idea_v2(
@@ -52,6 +73,18 @@ async fn get_random_idea_v2(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get a random idea",
+    params(
+        QueryFilter
+    ),
+    responses(
+        (status = 200, description = "A randomly selected idea which matches the filter.", body = IdeaV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/idea/random")]
 async fn get_random_idea_v3(
@@ -63,16 +96,7 @@ async fn get_random_idea_v3(
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
-    state
-        .store
-        .send(
-            GetRoleAssignment {
-                principal_id: uid,
-                collection_id: uid,
-            }
-            .trace(),
-        )
-        .await??;
+    authorize(&state, uid, uid, Permission::IdeaRead).await?;
 
     state
         .store
@@ -88,6 +112,19 @@ async fn get_random_idea_v3(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Get a random idea from a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        QueryFilter
+    ),
+    responses(
+        (status = 200, description = "A randomly selected idea which matches the filter.", body = IdeaV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/collection/{collection}/idea/random")]
 async fn get_random_collection_idea_v3(
@@ -104,16 +141,7 @@ async fn get_random_collection_idea_v3(
     let cid = parse_uuid!(info.collection, "collection ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
-    state
-        .store
-        .send(
-            GetRoleAssignment {
-                principal_id: uid,
-                collection_id: cid,
-            }
-            .trace(),
-        )
-        .await??;
+    authorize(&state, cid, uid, Permission::IdeaRead).await?;
 
     state
         .store
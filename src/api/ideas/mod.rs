@@ -1,5 +1,6 @@
-use super::{APIError, AuthToken, ensure_user_collection};
+use super::{APIError, AuthToken, authorize, ensure_user_collection, store_idea_permission};
 use actix_web::web;
+use utoipa::{IntoParams, OpenApi};
 
 mod get_idea;
 mod get_ideas;
@@ -37,6 +38,35 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(remove_idea::remove_collection_idea_v3);
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_ideas::get_ideas_v1,
+    get_random_idea::get_random_idea_v1,
+    get_idea::get_idea_v1,
+    new_idea::new_idea_v1,
+    store_idea::store_idea_v1,
+    remove_idea::remove_idea_v1,
+    get_ideas::get_ideas_v2,
+    get_random_idea::get_random_idea_v2,
+    get_idea::get_idea_v2,
+    new_idea::new_idea_v2,
+    store_idea::store_idea_v2,
+    remove_idea::remove_idea_v2,
+    get_ideas::get_ideas_v3,
+    get_ideas::get_collection_ideas_v3,
+    get_random_idea::get_random_idea_v3,
+    get_random_idea::get_random_collection_idea_v3,
+    get_idea::get_idea_v3,
+    get_idea::get_collection_idea_v3,
+    new_idea::new_idea_v3,
+    new_idea::new_collection_idea_v3,
+    store_idea::store_idea_v3,
+    store_idea::store_collection_idea_v3,
+    remove_idea::remove_idea_v3,
+    remove_idea::remove_collection_idea_v3,
+))]
+pub struct ApiDoc;
+
 #[derive(Debug, Deserialize, Serialize)]
 struct IdFilter {
     id: String,
@@ -53,8 +83,11 @@ struct CollectionIdFilter {
     id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct QueryFilter {
+    /// Only include ideas which have been given this tag.
     tag: Option<String>,
+    /// Only include ideas which have (or have not) been completed.
     complete: Option<bool>,
 }
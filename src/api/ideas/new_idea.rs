@@ -1,9 +1,19 @@
 use super::CollectionFilter;
-use super::{APIError, AuthToken, ensure_user_collection};
+use super::{APIError, AuthToken, authorize, ensure_user_collection};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{post, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Create an idea (v1)",
+    request_body = IdeaV1,
+    responses(
+        (status = 201, description = "The idea which was created.", body = IdeaV1),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[post("/api/v1/ideas")]
 async fn new_idea_v1(
@@ -13,6 +23,8 @@ async fn new_idea_v1(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
@@ -34,6 +46,16 @@ async fn new_idea_v1(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Create an idea (v2)",
+    request_body = IdeaV2,
+    responses(
+        (status = 201, description = "The idea which was created.", body = IdeaV2),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[post("/api/v2/ideas")]
 async fn new_idea_v2(
@@ -43,6 +65,8 @@ async fn new_idea_v2(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
@@ -64,6 +88,16 @@ async fn new_idea_v2(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Create an idea",
+    request_body = IdeaV3,
+    responses(
+        (status = 201, description = "The idea which was created.", body = IdeaV3),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[post("/api/v3/ideas")]
 async fn new_idea_v3(
@@ -73,9 +107,12 @@ async fn new_idea_v3(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
+    authorize(&state, uid, uid, Permission::IdeaCreate).await?;
 
     state
         .store
@@ -94,6 +131,19 @@ async fn new_idea_v3(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Create an idea in a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    request_body = IdeaV3,
+    responses(
+        (status = 201, description = "The idea which was created.", body = IdeaV3),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[post("/api/v3/collection/{collection}/ideas")]
 async fn new_collection_idea_v3(
@@ -108,6 +158,8 @@ async fn new_collection_idea_v3(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let cid = parse_uuid!(info.collection, "collection ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
@@ -115,36 +167,23 @@ async fn new_collection_idea_v3(
         ensure_user_collection(&state, &token).await?;
     }
 
-    let role = state
+    authorize(&state, cid, uid, Permission::IdeaCreate).await?;
+
+    state
         .store
-        .send(GetRoleAssignment {
-            principal_id: uid,
-            collection_id: cid,
-        })
-        .await??;
-
-    match role.role {
-        Role::Owner | Role::Contributor => state
-            .store
-            .send(
-                StoreIdea {
-                    id: new_id(),
-                    collection: cid,
-                    name: idea.name,
-                    description: idea.description,
-                    tags: idea.tags,
-                    completed: idea.completed,
-                }
-                .trace(),
-            )
-            .await?
-            .map(|idea| idea.into()),
-        _ => Err(APIError::new(
-            403,
-            "Forbidden",
-            "You do not have permission to add an idea to this collection.",
-        )),
-    }
+        .send(
+            StoreIdea {
+                id: new_id(),
+                collection: cid,
+                name: idea.name,
+                description: idea.description,
+                tags: idea.tags,
+                completed: idea.completed,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|idea| idea.into())
 }
 
 #[cfg(test)]
@@ -294,4 +333,66 @@ mod tests {
             .expect("the actor should have run")
             .expect("The idea should exist in the store");
     }
+
+    #[actix_rt::test]
+    async fn new_collection_idea_v3_viewer() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 7,
+                    principal_id: 0,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 0,
+                    role: Role::Viewer,
+                }
+            ]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000007/ideas", IdeaV3 {
+            id: None,
+            collection: None,
+            name: "Test Idea".to_string(),
+            description: "This is a test idea".to_string(),
+            tags: Some(hashset!("test")),
+            completed: None
+        } => FORBIDDEN | state = state);
+    }
+
+    #[actix_rt::test]
+    async fn new_idea_v1_invalid() {
+        test_log_init();
+
+        test_request!(POST "/api/v1/ideas", IdeaV1 {
+            id: None,
+            name: "".to_string(),
+            description: "This is a test idea".to_string(),
+        } => UNPROCESSABLE_ENTITY);
+    }
+
+    #[actix_rt::test]
+    async fn new_collection_idea_v3_invalid_tags() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/ideas", IdeaV3 {
+            id: None,
+            collection: None,
+            name: "Test Idea".into(),
+            description: "This is a test idea".into(),
+            tags: Some(hashset!("one,two")),
+            completed: None
+        } => UNPROCESSABLE_ENTITY | state = state);
+    }
 }
@@ -1,9 +1,21 @@
-use super::{APIError, AuthToken, ensure_user_collection};
+use super::{APIError, AuthToken, authorize, ensure_user_collection};
 use super::{CollectionIdFilter, IdFilter};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{HttpResponse, delete, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Remove an idea (v1)",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 204, description = "The idea was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[delete("/api/v1/idea/{id}")]
 async fn remove_idea_v1(
@@ -29,6 +41,18 @@ async fn remove_idea_v1(
     Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Remove an idea (v2)",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 204, description = "The idea was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[delete("/api/v2/idea/{id}")]
 async fn remove_idea_v2(
@@ -54,6 +78,18 @@ async fn remove_idea_v2(
     Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Remove an idea",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 204, description = "The idea was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[delete("/api/v3/idea/{id}")]
 async fn remove_idea_v3(
@@ -65,6 +101,9 @@ async fn remove_idea_v3(
     let id = parse_uuid!(info.id, "idea ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
+    ensure_user_collection(&state, &token).await?;
+    authorize(&state, uid, uid, Permission::IdeaRemove).await?;
+
     state
         .store
         .send(
@@ -79,6 +118,19 @@ async fn remove_idea_v3(
     Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Remove an idea from a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    responses(
+        (status = 204, description = "The idea was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[delete("/api/v3/collection/{collection}/idea/{id}")]
 async fn remove_collection_idea_v3(
@@ -97,38 +149,20 @@ async fn remove_collection_idea_v3(
 
     ensure_user_collection(&state, &token).await?;
 
-    let role = state
+    authorize(&state, cid, uid, Permission::IdeaRemove).await?;
+
+    state
         .store
         .send(
-            GetRoleAssignment {
-                principal_id: uid,
-                collection_id: cid,
+            RemoveIdea {
+                collection: cid,
+                id,
             }
             .trace(),
         )
         .await??;
 
-    match role.role {
-        Role::Owner | Role::Contributor => {
-            state
-                .store
-                .send(
-                    RemoveIdea {
-                        collection: cid,
-                        id,
-                    }
-                    .trace(),
-                )
-                .await??;
-
-            Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
-        }
-        _ => Err(APIError::new(
-            403,
-            "Forbidden",
-            "You do not have permission to remove an idea from this collection.",
-        )),
-    }
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
 }
 
 #[cfg(test)]
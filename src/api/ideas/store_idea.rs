@@ -1,9 +1,22 @@
-use super::{APIError, AuthToken, ensure_user_collection};
+use super::{APIError, AuthToken, authorize, ensure_user_collection, store_idea_permission};
 use super::{CollectionIdFilter, IdFilter};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{put, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Store an idea (v1)",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    request_body = IdeaV1,
+    responses(
+        (status = 200, description = "The idea which was stored.", body = IdeaV1),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[put("/api/v1/idea/{id}")]
 async fn store_idea_v1(
@@ -18,6 +31,8 @@ async fn store_idea_v1(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let id = parse_uuid!(info.id, "idea ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
@@ -40,6 +55,19 @@ async fn store_idea_v1(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Store an idea (v2)",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    request_body = IdeaV2,
+    responses(
+        (status = 200, description = "The idea which was stored.", body = IdeaV2),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[put("/api/v2/idea/{id}")]
 async fn store_idea_v2(
@@ -54,6 +82,8 @@ async fn store_idea_v2(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let id = parse_uuid!(info.id, "idea ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
@@ -76,6 +106,19 @@ async fn store_idea_v2(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Store an idea",
+    params(
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    request_body = IdeaV3,
+    responses(
+        (status = 200, description = "The idea which was stored.", body = IdeaV3),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[put("/api/v3/idea/{id}")]
 async fn store_idea_v3(
@@ -90,11 +133,16 @@ async fn store_idea_v3(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let id = parse_uuid!(info.id, "idea ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
 
+    let permission = store_idea_permission(&state, uid, id, &idea).await?;
+    authorize(&state, uid, uid, permission).await?;
+
     state
         .store
         .send(
@@ -112,6 +160,20 @@ async fn store_idea_v3(
         .map(|idea| idea.into())
 }
 
+#[utoipa::path(
+    tag = "ideas",
+    summary = "Store an idea in a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("id" = String, Path, description = "The ID of the idea.")
+    ),
+    request_body = IdeaV3,
+    responses(
+        (status = 200, description = "The idea which was stored.", body = IdeaV3),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[put("/api/v3/collection/{collection}/idea/{id}")]
 async fn store_collection_idea_v3(
@@ -126,45 +188,32 @@ async fn store_collection_idea_v3(
     require_scope!(token, "Ideas.Write");
 
     let idea: Idea = new_idea.into_inner().into();
+    idea.validate()?;
+
     let id = parse_uuid!(info.id, "idea ID");
     let cid = parse_uuid!(info.collection, "collection ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
     ensure_user_collection(&state, &token).await?;
 
-    let role = state
+    let permission = store_idea_permission(&state, cid, id, &idea).await?;
+    authorize(&state, cid, uid, permission).await?;
+
+    state
         .store
         .send(
-            GetRoleAssignment {
-                principal_id: uid,
-                collection_id: cid,
+            StoreIdea {
+                id,
+                collection: cid,
+                name: idea.name,
+                description: idea.description,
+                tags: idea.tags,
+                completed: idea.completed,
             }
             .trace(),
         )
-        .await??;
-
-    match role.role {
-        Role::Owner | Role::Contributor => state
-            .store
-            .send(
-                StoreIdea {
-                    id,
-                    collection: cid,
-                    name: idea.name,
-                    description: idea.description,
-                    tags: idea.tags,
-                    completed: idea.completed,
-                }
-                .trace(),
-            )
-            .await?
-            .map(|idea| idea.into()),
-        _ => Err(APIError::new(
-            403,
-            "Forbidden",
-            "You do not have permission to modify an idea within this collection.",
-        )),
-    }
+        .await?
+        .map(|idea| idea.into())
 }
 
 #[cfg(test)]
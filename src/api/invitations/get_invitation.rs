@@ -0,0 +1,87 @@
+use super::CollectionInvitationFilter;
+use super::{APIError, AuthToken, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "Get a pending invitation",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("invitation" = String, Path, description = "The hash of the invited user's email address.")
+    ),
+    responses(
+        (status = 200, description = "The requested invitation.", body = InvitationV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/invite/{invitation}")]
+async fn get_invitation_v3(
+    (info, state, token): (
+        web::Path<CollectionInvitationFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<InvitationV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let email_hash = parse_uuid!(info.invitation, "invitation ID");
+
+    authorize(&state, cid, uid, Permission::MemberList).await?;
+
+    state
+        .store
+        .send(
+            GetInvitation {
+                collection_id: cid,
+                email_hash,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|invitation| invitation.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_invitation_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreInvitation {
+                    collection_id: 1,
+                    email_hash: 2,
+                    role: Role::Contributor,
+                    principal_id: 0,
+                }
+            ]
+        );
+
+        let content: InvitationV3 = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/invite/00000000000000000000000000000002" => OK with content | state = state);
+        assert_eq!(
+            content.collection_id,
+            Some("00000000000000000000000000000001".into())
+        );
+        assert_eq!(
+            content.email_hash,
+            Some("00000000000000000000000000000002".into())
+        );
+        assert_eq!(content.role, "Contributor".to_string());
+    }
+}
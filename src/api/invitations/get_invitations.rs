@@ -0,0 +1,75 @@
+use super::CollectionFilter;
+use super::{APIError, AuthToken, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "List the pending invitations for a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The pending invitations for the collection.", body = [InvitationV3])
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/invites")]
+async fn get_invitations_v3(
+    (info, state, token): (
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<web::Json<Vec<InvitationV3>>, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::MemberList).await?;
+
+    state
+        .store
+        .send(GetInvitations { collection_id: cid }.trace())
+        .await?
+        .map(|invitations| web::Json(invitations.into_iter().map(|i| i.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_invitations_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreInvitation {
+                    collection_id: 1,
+                    email_hash: 2,
+                    role: Role::Viewer,
+                    principal_id: 0,
+                }
+            ]
+        );
+
+        let content: Vec<InvitationV3> = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/invites" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(
+            content[0].email_hash,
+            Some("00000000000000000000000000000002".into())
+        );
+        assert_eq!(content[0].role, "Viewer".to_string());
+    }
+}
@@ -0,0 +1,35 @@
+mod get_invitation;
+mod get_invitations;
+mod new_invitation;
+mod remove_invitation;
+
+use super::{APIError, AuthToken, accept_invitations, authorize};
+use actix_web::web;
+use utoipa::OpenApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_invitation::get_invitation_v3)
+        .service(get_invitations::get_invitations_v3)
+        .service(new_invitation::new_invitation_v3)
+        .service(remove_invitation::remove_invitation_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_invitation::get_invitation_v3,
+    get_invitations::get_invitations_v3,
+    new_invitation::new_invitation_v3,
+    remove_invitation::remove_invitation_v3,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionFilter {
+    collection: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionInvitationFilter {
+    collection: String,
+    invitation: String,
+}
@@ -0,0 +1,276 @@
+use super::CollectionFilter;
+use super::{APIError, AuthToken, accept_invitations, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{post, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "Invite someone to a collection by email",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    request_body = InvitationV3,
+    responses(
+        (status = 201, description = "The invitation which was created.", body = InvitationV3),
+        (status = 400, description = "The request was not valid.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
+#[instrument(err, skip(invitation, state, token), fields(otel.kind = "internal"))]
+#[post("/api/v3/collection/{collection}/invites")]
+async fn new_invitation_v3(
+    (info, invitation, state, token): (
+        web::Path<CollectionFilter>,
+        web::Json<InvitationV3>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<InvitationV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    let email = match invitation.email.as_deref().map(str::trim) {
+        Some(email) if email.contains('@') => email,
+        _ => {
+            return Err(APIError::new(
+                400,
+                "Bad Request",
+                "You must provide the email address of the person you wish to invite. Please check it and try again.",
+            )
+            .with_field("email", "A valid email address is required."));
+        }
+    };
+
+    let role: Role = invitation.role.as_str().into();
+    if role == Role::Invalid {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "The role you provided is not recognized. Please use one of Owner, Contributor or Viewer.",
+        )
+        .with_field("role", "The role must be one of Owner, Contributor or Viewer."));
+    }
+
+    let hash = email_hash(email);
+    if hash == email_hash(token.email()) {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "You cannot invite yourself to a collection. Please request that another collection owner performs this task for you.",
+        ));
+    }
+
+    authorize(&state, cid, uid, Permission::MemberInvite).await?;
+
+    let invitation = state
+        .store
+        .send(
+            StoreInvitation {
+                collection_id: cid,
+                email_hash: hash,
+                role,
+                principal_id: uid,
+            }
+            .trace(),
+        )
+        .await??;
+
+    // Invitations are otherwise only accepted when the invitee's user record is created,
+    // so people who already use Rex are added to the collection straight away when the
+    // issuer is trusted to have verified the email address their record was created with.
+    if token.issuer_emails_trusted()
+        && let Ok(user) = state
+            .store
+            .send(GetUser { email_hash: hash }.trace())
+            .await?
+        && let Err(err) = accept_invitations(&state, user.principal_id, hash).await
+    {
+        warn!(
+            "Unable to accept the invitation for user {:0>32x}: {}",
+            user.principal_id, err
+        );
+    }
+
+    Ok(invitation.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn new_invitation_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 0,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                }
+            ]
+        );
+
+        let content: InvitationV3 = test_request!(POST "/api/v3/collection/00000000000000000000000000000001/invites", InvitationV3 {
+            collection_id: None,
+            email: Some("Friend@Example.com ".into()),
+            email_hash: None,
+            role: "Contributor".into(),
+            invited_by: None,
+        } => CREATED with location =~ "/api/v3/collection/00000000000000000000000000000001/invite/", content | state = state);
+
+        assert_eq!(
+            content.email_hash,
+            Some(format!("{:0>32x}", email_hash("friend@example.com")))
+        );
+        assert_eq!(content.role, "Contributor".to_string());
+        assert_eq!(
+            content.invited_by,
+            Some("00000000000000000000000000000000".into())
+        );
+    }
+
+    #[actix_rt::test]
+    async fn new_invitation_v3_accepted() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 7,
+                    principal_id: 1,
+                    name: "Shared Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 1,
+                    role: Role::Owner,
+                },
+                StoreInvitation {
+                    collection_id: 7,
+                    email_hash: email_hash("testy@example.com"),
+                    role: Role::Contributor,
+                    principal_id: 1,
+                }
+            ]
+        );
+
+        let content: Vec<CollectionV3> =
+            test_request!(GET "/api/v3/collections" => OK with content | state = state);
+        assert!(
+            content
+                .iter()
+                .any(|c| c.id == Some("00000000000000000000000000000007".into())
+                    && c.name == "Shared Collection")
+        );
+
+        let role = state
+            .store
+            .send(GetRoleAssignment {
+                collection_id: 7,
+                principal_id: 0,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the invitation should have been converted into a role assignment");
+        assert_eq!(role.role, Role::Contributor);
+
+        state
+            .store
+            .send(GetInvitation {
+                collection_id: 7,
+                email_hash: email_hash("testy@example.com"),
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("the invitation should have been consumed");
+
+        // The test token carries no email_verified claim, which the default issuer does
+        // not send either, so the user is still recorded for others to find.
+        let user = state
+            .store
+            .send(GetUser {
+                email_hash: email_hash("testy@example.com"),
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the user should have been recorded");
+        assert_eq!(user.principal_id, 0);
+    }
+
+    #[actix_rt::test]
+    async fn new_invitation_v3_existing_user() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 0,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreUser {
+                    email_hash: email_hash("friend@example.com"),
+                    principal_id: 5,
+                    first_name: "Friend".into(),
+                }
+            ]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/invites", InvitationV3 {
+            collection_id: None,
+            email: Some("friend@example.com".into()),
+            email_hash: None,
+            role: "Viewer".into(),
+            invited_by: None,
+        } => CREATED | state = state);
+
+        let role = state
+            .store
+            .send(GetRoleAssignment {
+                collection_id: 1,
+                principal_id: 5,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the existing user should have been added to the collection");
+        assert_eq!(role.role, Role::Viewer);
+    }
+
+    #[actix_rt::test]
+    async fn new_invitation_v3_viewer() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Viewer,
+            }]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/invites", InvitationV3 {
+            collection_id: None,
+            email: Some("friend@example.com".into()),
+            email_hash: None,
+            role: "Contributor".into(),
+            invited_by: None,
+        } => FORBIDDEN | state = state);
+    }
+}
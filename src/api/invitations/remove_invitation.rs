@@ -0,0 +1,89 @@
+use super::CollectionInvitationFilter;
+use super::{APIError, AuthToken, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, delete, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "Cancel a pending invitation",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("invitation" = String, Path, description = "The hash of the invited user's email address.")
+    ),
+    responses(
+        (status = 204, description = "The invitation was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[delete("/api/v3/collection/{collection}/invite/{invitation}")]
+async fn remove_invitation_v3(
+    (info, state, token): (
+        web::Path<CollectionInvitationFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let email_hash = parse_uuid!(info.invitation, "invitation ID");
+
+    authorize(&state, cid, uid, Permission::MemberRemove).await?;
+
+    state
+        .store
+        .send(
+            RemoveInvitation {
+                collection_id: cid,
+                email_hash,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn remove_invitation_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreInvitation {
+                    collection_id: 1,
+                    email_hash: 2,
+                    role: Role::Viewer,
+                    principal_id: 0,
+                }
+            ]
+        );
+
+        test_request!(DELETE "/api/v3/collection/00000000000000000000000000000001/invite/00000000000000000000000000000002" => NO_CONTENT | state = state);
+
+        state
+            .store
+            .send(GetInvitation {
+                collection_id: 1,
+                email_hash: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("The invitation should not exist anymore");
+    }
+}
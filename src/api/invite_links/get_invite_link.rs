@@ -0,0 +1,122 @@
+use super::{APIError, AuthToken, InviteLinkConfig, TokenFilter, parse_token, to_v3};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "Preview an invite link",
+    params(
+        ("token" = String, Path, description = "The signed token for the invite link.")
+    ),
+    responses(
+        (status = 200, description = "The invite link.", body = InviteLinkV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError),
+        (status = 410, description = "The invite link has expired or has been used up.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Read"]))
+)]
+#[instrument(err, skip(info, state, config, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/invites/{token}")]
+async fn get_invite_link_v3(
+    (info, state, config, token): (
+        web::Path<TokenFilter>,
+        web::Data<GlobalState>,
+        web::Data<InviteLinkConfig>,
+        AuthToken,
+    ),
+) -> Result<InviteLinkV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Read");
+
+    let (cid, id) = parse_token(&config, &info.token)?;
+
+    let link = state
+        .store
+        .send(
+            GetInviteLink {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    if link.is_expired() || link.is_exhausted() {
+        return Err(APIError::new(
+            410,
+            "Gone",
+            "This invite link has expired. Please ask the collection owner for a new one.",
+        ));
+    }
+
+    Ok(to_v3(&config, link))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{InviteLinkConfig, token_for};
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_invite_link_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreInviteLink {
+                collection_id: 1,
+                id: 2,
+                role: Role::Viewer,
+                max_uses: 5,
+                uses: 1,
+                expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+                principal_id: 3,
+            }]
+        );
+
+        let token = token_for(
+            &InviteLinkConfig::default(),
+            &InviteLink {
+                id: 2,
+                collection_id: 1,
+                ..Default::default()
+            },
+        );
+
+        let content: InviteLinkV3 = test_request!(GET &format!("/api/v3/invites/{token}") => OK with content | state = state);
+        assert_eq!(content.id, Some("00000000000000000000000000000002".into()));
+        assert_eq!(content.role, "Viewer".to_string());
+        assert_eq!(content.max_uses, Some(5));
+        assert_eq!(content.uses, Some(1));
+        assert_eq!(content.token, Some(token));
+    }
+
+    #[actix_rt::test]
+    async fn get_invite_link_v3_expired() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreInviteLink {
+                collection_id: 1,
+                id: 2,
+                role: Role::Viewer,
+                max_uses: 5,
+                uses: 0,
+                expires_at: chrono::Utc::now() - chrono::Duration::days(1),
+                principal_id: 3,
+            }]
+        );
+
+        let token = token_for(
+            &InviteLinkConfig::default(),
+            &InviteLink {
+                id: 2,
+                collection_id: 1,
+                ..Default::default()
+            },
+        );
+
+        test_request!(GET &format!("/api/v3/invites/{token}") => GONE | state = state);
+    }
+}
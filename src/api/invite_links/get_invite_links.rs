@@ -0,0 +1,79 @@
+use super::{APIError, AuthToken, CollectionFilter, InviteLinkConfig, authorize, to_v3};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "List the invite links for a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The invite links for the collection.", body = [InviteLinkV3])
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
+#[instrument(err, skip(state, config, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/links")]
+async fn get_invite_links_v3(
+    (info, state, config, token): (
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        web::Data<InviteLinkConfig>,
+        AuthToken,
+    ),
+) -> Result<web::Json<Vec<InviteLinkV3>>, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::MemberList).await?;
+
+    state
+        .store
+        .send(GetInviteLinks { collection_id: cid }.trace())
+        .await?
+        .map(|links| web::Json(links.into_iter().map(|link| to_v3(&config, link)).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_invite_links_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreInviteLink {
+                    collection_id: 1,
+                    id: 2,
+                    role: Role::Viewer,
+                    max_uses: 10,
+                    uses: 0,
+                    expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+                    principal_id: 0,
+                }
+            ]
+        );
+
+        let content: Vec<InviteLinkV3> = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/links" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(
+            content[0].id,
+            Some("00000000000000000000000000000002".into())
+        );
+        assert_eq!(content[0].role, "Viewer".to_string());
+        assert!(content[0].token.is_some());
+    }
+}
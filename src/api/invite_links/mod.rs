@@ -0,0 +1,176 @@
+mod get_invite_link;
+mod get_invite_links;
+mod new_invite_link;
+mod redeem_invite_link;
+mod remove_invite_link;
+
+use super::{APIError, AuthToken, authorize};
+use crate::models::*;
+use actix_web::web;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use utoipa::OpenApi;
+
+pub fn configure(cfg: &mut web::ServiceConfig, config: &InviteLinkConfig) {
+    cfg.app_data(web::Data::new(config.clone()))
+        .service(get_invite_link::get_invite_link_v3)
+        .service(redeem_invite_link::redeem_invite_link_v3)
+        .service(get_invite_links::get_invite_links_v3)
+        .service(new_invite_link::new_invite_link_v3)
+        .service(remove_invite_link::remove_invite_link_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_invite_link::get_invite_link_v3,
+    redeem_invite_link::redeem_invite_link_v3,
+    get_invite_links::get_invite_links_v3,
+    new_invite_link::new_invite_link_v3,
+    remove_invite_link::remove_invite_link_v3,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct TokenFilter {
+    token: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionFilter {
+    collection: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionLinkFilter {
+    collection: String,
+    link: String,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InviteLinkConfig {
+    /// The secret which invite link tokens are signed with. If this is not set, a random
+    /// secret is used and invite links will not remain valid after a restart.
+    pub secret: Option<String>,
+}
+
+impl InviteLinkConfig {
+    fn signing_key(&self) -> &[u8] {
+        self.secret
+            .as_deref()
+            .map(str::as_bytes)
+            .unwrap_or(&EPHEMERAL_SIGNING_KEY)
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref EPHEMERAL_SIGNING_KEY: Vec<u8> = {
+        warn!("No invite link secret has been configured, invite links will not remain valid after a restart.");
+        [new_id().to_be_bytes(), new_id().to_be_bytes()].concat()
+    };
+}
+
+fn signature(config: &InviteLinkConfig, collection_id: u128, id: u128) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(config.signing_key())
+        .expect("HMAC accepts keys of any length");
+    mac.update(format!("{collection_id:0>32x}.{id:0>32x}").as_bytes());
+    mac
+}
+
+/// Generates the token which is shared with invitees, binding the link's collection
+/// and ID together with a signature so that tokens cannot be forged or altered.
+fn token_for(config: &InviteLinkConfig, link: &InviteLink) -> String {
+    let signature = signature(config, link.collection_id, link.id)
+        .finalize()
+        .into_bytes();
+
+    format!(
+        "{:0>32x}.{:0>32x}.{}",
+        link.collection_id,
+        link.id,
+        hex::encode(signature)
+    )
+}
+
+/// Verifies the signature on an invite token and returns the collection and link IDs it refers to.
+fn parse_token(config: &InviteLinkConfig, token: &str) -> Result<(u128, u128), APIError> {
+    let not_found = || {
+        APIError::new(
+            404,
+            "Not Found",
+            "The invite link you provided could not be found. Please check it and try again.",
+        )
+    };
+
+    let mut parts = token.split('.');
+    let (Some(cid), Some(id), Some(sig), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(not_found());
+    };
+
+    let cid = u128::from_str_radix(cid, 16).map_err(|_| not_found())?;
+    let id = u128::from_str_radix(id, 16).map_err(|_| not_found())?;
+    let sig = hex::decode(sig).map_err(|_| not_found())?;
+
+    signature(config, cid, id).verify_slice(&sig).map_err(|_| {
+        warn!("Received an invite link token with an invalid signature.");
+        not_found()
+    })?;
+
+    Ok((cid, id))
+}
+
+fn to_v3(config: &InviteLinkConfig, link: InviteLink) -> InviteLinkV3 {
+    let token = token_for(config, &link);
+    InviteLinkV3 {
+        token: Some(token),
+        ..link.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_round_trip() {
+        let link = InviteLink {
+            id: 2,
+            collection_id: 1,
+            ..Default::default()
+        };
+
+        let config = InviteLinkConfig::default();
+        let token = token_for(&config, &link);
+        assert_eq!(parse_token(&config, &token).expect("a valid token"), (1, 2));
+    }
+
+    #[test]
+    fn token_tampered() {
+        let link = InviteLink {
+            id: 2,
+            collection_id: 1,
+            ..Default::default()
+        };
+
+        let config = InviteLinkConfig::default();
+        let token = token_for(&config, &link).replacen(
+            "00000000000000000000000000000001",
+            "00000000000000000000000000000003",
+            1,
+        );
+        assert_eq!(
+            parse_token(&config, &token)
+                .expect_err("an invalid token")
+                .code,
+            404
+        );
+        assert_eq!(
+            parse_token(&config, "not-a-token")
+                .expect_err("an invalid token")
+                .code,
+            404
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use super::{APIError, AuthToken, CollectionFilter, InviteLinkConfig, authorize, to_v3};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{post, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "Create an invite link for a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    request_body = InviteLinkV3,
+    responses(
+        (status = 201, description = "The invite link which was created.", body = InviteLinkV3),
+        (status = 400, description = "The request was not valid.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
+#[instrument(err, skip(link, state, config, token), fields(otel.kind = "internal"))]
+#[post("/api/v3/collection/{collection}/links")]
+async fn new_invite_link_v3(
+    (info, link, state, config, token): (
+        web::Path<CollectionFilter>,
+        web::Json<InviteLinkV3>,
+        web::Data<GlobalState>,
+        web::Data<InviteLinkConfig>,
+        AuthToken,
+    ),
+) -> Result<InviteLinkV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    let role: Role = link.role.as_str().into();
+    if role == Role::Invalid {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "The role you provided is not recognized. Please use one of Owner, Contributor or Viewer.",
+        )
+        .with_field("role", "The role must be one of Owner, Contributor or Viewer."));
+    }
+
+    let max_uses = link.max_uses.unwrap_or(1);
+    if max_uses == 0 {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "An invite link must allow at least one use. Please check the maxUses you provided and try again.",
+        )
+        .with_field("maxUses", "The maximum number of uses must be at least 1."));
+    }
+
+    let expires_at = link
+        .expires_at
+        .unwrap_or_else(|| chrono::Utc::now() + chrono::Duration::days(7));
+    if expires_at <= chrono::Utc::now() {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "An invite link must expire in the future. Please check the expiresAt you provided and try again.",
+        )
+        .with_field("expiresAt", "The expiry time must be in the future."));
+    }
+
+    authorize(&state, cid, uid, Permission::MemberInvite).await?;
+
+    state
+        .store
+        .send(
+            StoreInviteLink {
+                collection_id: cid,
+                id: new_id(),
+                role,
+                max_uses,
+                uses: 0,
+                expires_at,
+                principal_id: uid,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|link| to_v3(&config, link))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn new_invite_link_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        let content: InviteLinkV3 = test_request!(POST "/api/v3/collection/00000000000000000000000000000001/links", InviteLinkV3 {
+            id: None,
+            collection_id: None,
+            role: "Contributor".into(),
+            max_uses: Some(3),
+            uses: None,
+            expires_at: None,
+            token: None,
+        } => CREATED with content | state = state);
+
+        assert_eq!(
+            content.collection_id,
+            Some("00000000000000000000000000000001".into())
+        );
+        assert_eq!(content.role, "Contributor".to_string());
+        assert_eq!(content.max_uses, Some(3));
+        assert_eq!(content.uses, Some(0));
+        assert!(content.expires_at.expect("an expiry") > chrono::Utc::now());
+        assert!(content.token.is_some());
+    }
+
+    #[actix_rt::test]
+    async fn new_invite_link_v3_viewer() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Viewer,
+            }]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/links", InviteLinkV3 {
+            id: None,
+            collection_id: None,
+            role: "Owner".into(),
+            max_uses: None,
+            uses: None,
+            expires_at: None,
+            token: None,
+        } => FORBIDDEN | state = state);
+    }
+}
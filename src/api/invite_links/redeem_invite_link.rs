@@ -0,0 +1,251 @@
+use super::{APIError, AuthToken, InviteLinkConfig, TokenFilter, parse_token};
+use crate::{api::ensure_user_collection, models::*, telemetry::TraceMessageExt};
+use actix_web::{post, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "Join a collection using an invite link",
+    params(
+        ("token" = String, Path, description = "The signed token for the invite link.")
+    ),
+    responses(
+        (status = 201, description = "The role you have been granted on the collection.", body = RoleAssignmentV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError),
+        (status = 410, description = "The invite link has expired or has been used up.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
+#[instrument(err, skip(info, state, config, token), fields(otel.kind = "internal"))]
+#[post("/api/v3/invites/{token}")]
+async fn redeem_invite_link_v3(
+    (info, state, config, token): (
+        web::Path<TokenFilter>,
+        web::Data<GlobalState>,
+        web::Data<InviteLinkConfig>,
+        AuthToken,
+    ),
+) -> Result<RoleAssignmentV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let (cid, id) = parse_token(&config, &info.token)?;
+
+    ensure_user_collection(&state, &token).await?;
+
+    let link = state
+        .store
+        .send(
+            GetInviteLink {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    if link.is_expired() || link.is_exhausted() {
+        return Err(APIError::new(
+            410,
+            "Gone",
+            "This invite link has expired. Please ask the collection owner for a new one.",
+        ));
+    }
+
+    // Redeeming a link never downgrades a role the user already holds on the collection.
+    if let Ok(existing) = state
+        .store
+        .send(
+            GetRoleAssignment {
+                collection_id: cid,
+                principal_id: uid,
+            }
+            .trace(),
+        )
+        .await?
+    {
+        return Ok(existing.into());
+    }
+
+    let collection = state
+        .store
+        .send(
+            GetCollection {
+                id: cid,
+                principal_id: link.created_by,
+            }
+            .trace(),
+        )
+        .await?
+        .map_err(|_| {
+            APIError::new(
+                410,
+                "Gone",
+                "The collection this invite link refers to is no longer available.",
+            )
+        })?;
+
+    state
+        .store
+        .send(
+            RedeemInviteLink {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    state
+        .store
+        .send(
+            StoreCollection {
+                collection_id: cid,
+                principal_id: uid,
+                name: collection.name,
+            }
+            .trace(),
+        )
+        .await??;
+
+    state
+        .store
+        .send(
+            StoreRoleAssignment {
+                collection_id: cid,
+                principal_id: uid,
+                role: link.role,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|role| role.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{InviteLinkConfig, token_for};
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn redeem_invite_link_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 3,
+                    name: "Shared Collection".into(),
+                },
+                StoreInviteLink {
+                    collection_id: 1,
+                    id: 2,
+                    role: Role::Contributor,
+                    max_uses: 1,
+                    uses: 0,
+                    expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+                    principal_id: 3,
+                }
+            ]
+        );
+
+        let token = token_for(
+            &InviteLinkConfig::default(),
+            &InviteLink {
+                id: 2,
+                collection_id: 1,
+                ..Default::default()
+            },
+        );
+
+        let content: RoleAssignmentV3 = test_request!(POST &format!("/api/v3/invites/{token}") => CREATED with content | state = state);
+        assert_eq!(
+            content.collection_id,
+            Some("00000000000000000000000000000001".into())
+        );
+        assert_eq!(
+            content.user_id,
+            Some("00000000000000000000000000000000".into())
+        );
+        assert_eq!(content.role, "Contributor".to_string());
+
+        let collection = state
+            .store
+            .send(GetCollection {
+                id: 1,
+                principal_id: 0,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the user should now have the collection");
+        assert_eq!(collection.name, "Shared Collection");
+
+        let link = state
+            .store
+            .send(GetInviteLink {
+                collection_id: 1,
+                id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the link should still exist");
+        assert_eq!(link.uses, 1);
+
+        test_request!(POST &format!("/api/v3/invites/{token}") => GONE | state = state);
+    }
+
+    #[actix_rt::test]
+    async fn concurrent_redemptions() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreInviteLink {
+                collection_id: 1,
+                id: 2,
+                role: Role::Viewer,
+                max_uses: 3,
+                uses: 0,
+                expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+                principal_id: 3,
+            }]
+        );
+
+        let redemptions = futures::future::join_all((0..10).map(|_| {
+            state.store.send(RedeemInviteLink {
+                collection_id: 1,
+                id: 2,
+            })
+        }))
+        .await;
+
+        let redeemed = redemptions
+            .into_iter()
+            .map(|result| result.expect("the actor should have run"))
+            .filter(|result| match result {
+                Ok(_) => true,
+                Err(err) => {
+                    assert_eq!(err.code, 410);
+                    false
+                }
+            })
+            .count();
+        assert_eq!(
+            redeemed, 3,
+            "the link should not be used more times than it allows"
+        );
+
+        let link = state
+            .store
+            .send(GetInviteLink {
+                collection_id: 1,
+                id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the link should still exist");
+        assert_eq!(link.uses, 3);
+    }
+}
@@ -0,0 +1,91 @@
+use super::{APIError, AuthToken, CollectionLinkFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, delete, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "invitations",
+    summary = "Revoke an invite link",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("link" = String, Path, description = "The ID of the invite link.")
+    ),
+    responses(
+        (status = 204, description = "The invite link was revoked."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[delete("/api/v3/collection/{collection}/link/{link}")]
+async fn remove_invite_link_v3(
+    (info, state, token): (
+        web::Path<CollectionLinkFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let id = parse_uuid!(info.link, "invite link ID");
+
+    authorize(&state, cid, uid, Permission::MemberRemove).await?;
+
+    state
+        .store
+        .send(
+            RemoveInviteLink {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn remove_invite_link_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreInviteLink {
+                    collection_id: 1,
+                    id: 2,
+                    role: Role::Viewer,
+                    max_uses: 1,
+                    uses: 0,
+                    expires_at: chrono::Utc::now() + chrono::Duration::days(1),
+                    principal_id: 0,
+                }
+            ]
+        );
+
+        test_request!(DELETE "/api/v3/collection/00000000000000000000000000000001/link/00000000000000000000000000000002" => NO_CONTENT | state = state);
+
+        state
+            .store
+            .send(GetInviteLink {
+                collection_id: 1,
+                id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect_err("The invite link should not exist anymore");
+    }
+}
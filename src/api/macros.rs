@@ -25,7 +25,7 @@ macro_rules! require_role {
 
     ($token:expr, $($role:expr),+) => {
         if !$token.roles().iter().any(|r| require_role!(_cond: r -> $($role),*)) {
-            return Err(APIError::new(403, "Forbidden", "You are not authorized to perform this action. Please contact your administrator for permission before trying again."));
+            return Err(APIError::new(403, "Forbidden", "You are not authorized to perform this action. Please contact your administrator for permission before trying again.").with_kind($crate::api::ErrorKind::ForbiddenRole));
         }
     };
 }
@@ -34,7 +34,7 @@ macro_rules! require_role {
 macro_rules! require_scope {
     ($token:expr, $scope:expr) => {
         if !$token.scopes().iter().any(|&s| s == $scope) {
-            return Err(APIError::new(403, "Forbidden", "Your client has not been granted permission to access this resource. Please request the necessary access scopes and try again."));
+            return Err(APIError::new(403, "Forbidden", "Your client has not been granted permission to access this resource. Please request the necessary access scopes and try again.").with_kind($crate::api::ErrorKind::ForbiddenScope));
         }
     };
 }
@@ -52,7 +52,7 @@ macro_rules! test_state {
     };
 
     ($state:ident = [ $($init:expr),* ]) => {
-        let $state = $crate::models::GlobalState::new();
+        let $state = $crate::models::GlobalState::new(&Default::default());
 
         test_state!(:: $state = [ $($init),* ]);
     }
@@ -122,7 +122,7 @@ macro_rules! test_request {
 
     ($method:ident $path:expr => $status:ident) => {
         {
-            let state = $crate::models::GlobalState::new();
+            let state = $crate::models::GlobalState::new(&Default::default());
 
             test_request!($method $path => $status | state = state)
         }
@@ -130,7 +130,7 @@ macro_rules! test_request {
 
     ($method:ident $path:expr, $body:expr => $status:ident) => {
         {
-            let state = $crate::models::GlobalState::new();
+            let state = $crate::models::GlobalState::new(&Default::default());
 
             test_request!($method $path, $body => $status | state = state)
         }
@@ -0,0 +1,153 @@
+use crate::api::APIError;
+use crate::{
+    models::*,
+    telemetry::{TraceMessageExt, metrics, render_gauge},
+};
+use actix_web::{HttpResponse, get, web};
+use std::collections::BTreeSet;
+use tracing::instrument;
+
+/// Exposes the metrics recorded by this instance in the Prometheus text format.
+#[instrument(err, skip(state), fields(otel.kind = "internal"))]
+#[get("/metrics")]
+pub async fn get_metrics(state: web::Data<GlobalState>) -> Result<HttpResponse, APIError> {
+    // The gauges are totals for the whole service rather than per collection, which keeps
+    // the number of series fixed and avoids exposing collection IDs. Counting them still
+    // visits every collection, so the scrape interval should allow for that.
+    let collections: BTreeSet<u128> = state
+        .store
+        .send(GetAllCollections {}.trace())
+        .await??
+        .into_iter()
+        .map(|c| c.collection_id)
+        .collect();
+
+    let mut ideas = 0;
+    for collection in collections.iter() {
+        ideas += state
+            .store
+            .send(
+                CountIdeas {
+                    collection: *collection,
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    let users: BTreeSet<u128> = state
+        .store
+        .send(GetUsers {}.trace())
+        .await??
+        .into_iter()
+        .map(|u| u.principal_id)
+        .collect();
+
+    let mut body = String::new();
+    metrics().render(&mut body);
+    render_gauge(
+        &mut body,
+        "rex_collections",
+        "The number of collections stored by the service.",
+        [(vec![], collections.len() as f64)],
+    );
+    render_gauge(
+        &mut body,
+        "rex_ideas",
+        "The number of ideas stored across every collection.",
+        [(vec![], ideas as f64)],
+    );
+    render_gauge(
+        &mut body,
+        "rex_users",
+        "The number of users who have signed in to the service.",
+        [(vec![], users.len() as f64)],
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_metrics() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 2,
+                    name: "Test Collection".into(),
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 3,
+                    name: "Go hiking".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let app = get_test_app(state.clone()).await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/v3/collections")
+            .insert_header(("Authorization", auth_token()))
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::OK).await;
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/metrics")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::NOT_FOUND).await;
+
+        let metrics_app = actix_web::test::init_service(
+            actix_web::App::new()
+                .app_data(actix_web::web::Data::new(state.clone()))
+                .configure(super::super::configure),
+        )
+        .await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/metrics")
+            .to_request();
+        let response = actix_web::test::call_service(&metrics_app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "text/plain; version=0.0.4; charset=utf-8"
+        );
+
+        let body = actix_web::test::read_body(response).await;
+        let metrics = String::from_utf8_lossy(&body);
+        assert!(
+            !metrics.contains("00000000000000000000000000000001"),
+            "{metrics}"
+        );
+        // Listing the collections also created a default collection for the signed in user.
+        assert!(metrics.contains("rex_collections 2\n"), "{metrics}");
+        assert!(metrics.contains("rex_ideas 1\n"), "{metrics}");
+        assert!(metrics.contains("rex_users 1\n"), "{metrics}");
+        assert!(
+            metrics.contains(
+                "rex_http_request_duration_seconds_count{method=\"GET\",route=\"/api/v3/collections\",status=\"200\"}"
+            ),
+            "{metrics}"
+        );
+        assert!(
+            metrics
+                .contains("rex_store_message_duration_seconds_count{message=\"GetCollections\"}"),
+            "{metrics}"
+        );
+        assert!(
+            metrics.contains("rex_oidc_verifications_total{outcome=\"verified\"}"),
+            "{metrics}"
+        );
+    }
+}
@@ -0,0 +1,9 @@
+use actix_web::web;
+
+mod get_metrics;
+
+/// Registers the metrics endpoint, which is served on its own address so that it is not
+/// reachable through the public API.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_metrics::get_metrics);
+}
@@ -1,28 +1,65 @@
 #[macro_use]
 mod macros;
 
+mod admin;
 mod auth;
+mod calendar;
+mod chat;
 mod collections;
+mod cors;
 mod error;
+mod events;
 mod health;
 mod ideas;
+mod invitations;
+mod invite_links;
+mod metrics;
+mod openapi;
+mod problem;
 mod role_assignments;
+mod shares;
 mod users;
 mod utils;
+mod webhooks;
 
 #[cfg(test)]
 pub mod test;
 
+use crate::config::Config;
 use actix_web::web;
 
-pub use auth::{AuthToken, OidcActor};
-pub use error::APIError;
-pub use utils::ensure_user_collection;
+pub use auth::{AuthConfig, AuthToken, OidcActor};
+pub use chat::SlackConfig;
+pub use cors::CorsConfig;
+pub use error::{APIError, ErrorKind, FieldError};
+pub use invite_links::InviteLinkConfig;
+pub use metrics::configure as configure_metrics;
+pub use problem::ProblemDetails;
+pub use utils::{
+    accept_invitations, authorize, authorize_collection_entry, ensure_user_collection,
+    store_idea_permission,
+};
 
-pub fn configure(cfg: &mut web::ServiceConfig) {
-    health::configure(cfg);
-    collections::configure(cfg);
-    role_assignments::configure(cfg);
-    ideas::configure(cfg);
-    users::configure(cfg);
+pub fn configure(config: &Config) -> impl FnOnce(&mut web::ServiceConfig) + use<> {
+    let auth = config.auth.clone();
+    let slack = config.slack.clone();
+    let invite_links = config.invite_links.clone();
+    let webhooks = config.webhooks.clone();
+
+    move |cfg| {
+        health::configure(cfg);
+        openapi::configure(cfg, &auth);
+        admin::configure(cfg);
+        collections::configure(cfg);
+        events::configure(cfg);
+        role_assignments::configure(cfg);
+        shares::configure(cfg);
+        webhooks::configure(cfg, &webhooks);
+        invitations::configure(cfg);
+        invite_links::configure(cfg, &invite_links);
+        ideas::configure(cfg);
+        calendar::configure(cfg);
+        chat::configure(cfg, &slack);
+        users::configure(cfg);
+    }
 }
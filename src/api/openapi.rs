@@ -0,0 +1,412 @@
+use super::{APIError, AuthConfig};
+use actix_web::{HttpResponse, get, web};
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        self, RefOr, Response,
+        security::{OpenIdConnect, SecurityScheme},
+    },
+};
+use utoipa_swagger_ui::{Config, SwaggerUi, oauth};
+
+/// The scopes which the API explorer requests when signing in, allowing it to call every route.
+const EXPLORER_SCOPES: [&str; 6] = [
+    "Ideas.Read",
+    "Ideas.Write",
+    "Collections.Read",
+    "Collections.Write",
+    "RoleAssignments.Write",
+    "Users.Read",
+];
+
+pub fn configure(cfg: &mut web::ServiceConfig, auth: &AuthConfig) {
+    cfg.app_data(web::Data::new(openapi(auth)))
+        .service(get_openapi)
+        .service(web::redirect("/api/docs", "/api/docs/").permanent())
+        .service(SwaggerUi::new("/api/docs/{_:.*}").config(explorer_config(auth)));
+}
+
+/// Configures the embedded Swagger UI to load the document served by [`get_openapi`] and
+/// to sign users in through our OpenID Connect provider using PKCE.
+fn explorer_config(auth: &AuthConfig) -> Config<'static> {
+    Config::with_oauth_config(
+        ["/api/openapi.json"],
+        oauth::Config::new()
+            .client_id(&auth.client_id)
+            .scopes(
+                std::iter::once("openid".to_string())
+                    .chain(
+                        EXPLORER_SCOPES
+                            .iter()
+                            .map(|scope| format!("{}/{scope}", auth.client_id)),
+                    )
+                    .collect(),
+            )
+            .use_pkce_with_authorization_code_grant(true),
+    )
+    .oauth2_redirect_url("/api/docs/oauth2-redirect.html")
+    .persist_authorization(true)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Rex",
+        description = "Rex is a tool for keeping track of ideas for things to do and providing, on demand, a random one.",
+        license(name = "MIT", url = "https://sierrasoftworks.com/licenses/MIT"),
+    ),
+    servers(
+        (url = "https://rex.sierrasoftworks.com"),
+        (url = "http://localhost:8000"),
+    ),
+    tags(
+        (name = "health", description = "APIs used to determine the health of a Rex instance."),
+        (name = "ideas", description = "APIs used to manage and retrieve ideas."),
+        (name = "collections", description = "APIs used to manage collections of ideas."),
+        (name = "events", description = "APIs used to follow the changes made to a collection as they happen."),
+        (name = "role-assignments", description = "APIs used to manage who has access to a collection."),
+        (name = "invitations", description = "APIs used to invite people to a collection."),
+        (name = "sharing", description = "APIs used to share a collection publicly, without requiring authentication."),
+        (name = "webhooks", description = "APIs used to notify other services of the changes made to a collection."),
+        (name = "calendar", description = "APIs used to schedule ideas and to follow them from a calendar client."),
+        (name = "chat", description = "APIs used to interact with Rex from chat tools like Slack."),
+        (name = "users", description = "APIs used to retrieve information about users."),
+        (name = "admin", description = "APIs used by administrators to manage the service."),
+    ),
+    components(schemas(APIError)),
+)]
+struct ApiDoc;
+
+/// Generates the OpenAPI document describing every route exposed by [`super::configure`].
+pub fn openapi(auth: &AuthConfig) -> openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+
+    for api in [
+        super::health::ApiDoc::openapi(),
+        super::admin::ApiDoc::openapi(),
+        super::collections::ApiDoc::openapi(),
+        super::events::ApiDoc::openapi(),
+        super::role_assignments::ApiDoc::openapi(),
+        super::shares::ApiDoc::openapi(),
+        super::webhooks::ApiDoc::openapi(),
+        super::invitations::ApiDoc::openapi(),
+        super::invite_links::ApiDoc::openapi(),
+        super::ideas::ApiDoc::openapi(),
+        super::calendar::ApiDoc::openapi(),
+        super::chat::ApiDoc::openapi(),
+        super::users::ApiDoc::openapi(),
+    ] {
+        doc.merge(api);
+    }
+
+    Security(auth).modify(&mut doc);
+
+    doc
+}
+
+/// Registers the OpenID Connect security scheme and documents the errors which are
+/// returned by every authenticated route when the caller is not permitted to use it.
+struct Security<'a>(&'a AuthConfig);
+
+impl Modify for Security<'_> {
+    fn modify(&self, openapi: &mut openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "oidc",
+            SecurityScheme::OpenIdConnect(OpenIdConnect::new(self.0.discovery_url())),
+        );
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.delete,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if operation.security.as_ref().is_none_or(|s| s.is_empty()) {
+                    continue;
+                }
+
+                for (status, description) in [
+                    ("401", "You have not provided a valid authentication token."),
+                    ("403", "You are not permitted to perform this action."),
+                ] {
+                    operation
+                        .responses
+                        .responses
+                        .entry(status.to_string())
+                        .or_insert_with(|| RefOr::T(error_response(description)));
+                }
+            }
+        }
+    }
+}
+
+fn error_response(description: &str) -> Response {
+    openapi::ResponseBuilder::new()
+        .description(description)
+        .content(
+            "application/json",
+            openapi::ContentBuilder::new()
+                .schema(Some(openapi::Ref::from_schema_name("APIError")))
+                .build(),
+        )
+        .build()
+}
+
+#[get("/api/openapi.json")]
+async fn get_openapi(doc: web::Data<openapi::OpenApi>) -> HttpResponse {
+    HttpResponse::Ok().json(doc.get_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use actix_web::{App, HttpRequest, test::TestRequest, web};
+    use std::collections::{BTreeMap, BTreeSet};
+
+    /// Lists the name and pattern of every resource registered by the API's handlers.
+    ///
+    /// actix-web has no API for enumerating its routes, so they are read from the `Debug`
+    /// representation of the resource map which it builds as they are registered. This is
+    /// only needed to find undocumented handlers, since documented operations are checked
+    /// with [`resolve_operations`] instead.
+    async fn registered_routes() -> BTreeMap<String, String> {
+        let app = actix_web::test::init_service(
+            App::new()
+                .configure(crate::api::configure(&test_config()))
+                .route(
+                    "/__routes",
+                    web::get()
+                        .to(|req: HttpRequest| async move { format!("{:?}", req.resource_map()) }),
+                ),
+        )
+        .await;
+
+        let body = actix_web::test::call_and_read_body(
+            &app,
+            TestRequest::get().uri("/__routes").to_request(),
+        )
+        .await;
+        let map = String::from_utf8_lossy(&body).into_owned();
+
+        let routes: BTreeMap<String, String> = map
+            .split("name: Some(\"")
+            .skip(1)
+            .filter_map(|resource| {
+                let (name, rest) = resource.split_once('"')?;
+                let (_, rest) = rest.split_once("patterns: Single(\"")?;
+                let (pattern, _) = rest.split_once('"')?;
+
+                pattern
+                    .starts_with("/api/v")
+                    .then(|| (name.to_string(), pattern.to_string()))
+            })
+            .collect();
+
+        assert!(
+            !routes.is_empty(),
+            "no routes could be read from the resource map, which probably means that actix-web \
+             has changed how it formats `ResourceMap` with `Debug` and `registered_routes` needs \
+             to be updated to match:\n{}",
+            map.chars().take(2000).collect::<String>()
+        );
+
+        routes
+    }
+
+    /// Resolves each operation ID as the name of a registered resource with actix-web's
+    /// `url_for`, returning the path it resolves to or `None` if nothing is registered.
+    async fn resolve_operations(
+        operations: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, Option<String>> {
+        let operations = operations.clone();
+        let app = actix_web::test::init_service(
+            App::new()
+                .configure(crate::api::configure(&test_config()))
+                .route(
+                    "/__routes",
+                    web::get().to(move |req: HttpRequest| {
+                        let operations = operations.clone();
+                        async move {
+                            let resolved: BTreeMap<String, Option<String>> = operations
+                                .iter()
+                                .map(|(id, path)| {
+                                    let parameters = vec![
+                                        "00000000000000000000000000000001";
+                                        path.matches('{').count()
+                                    ];
+                                    let url = req.url_for(id, parameters).ok();
+                                    (id.clone(), url.map(|u| u.path().to_string()))
+                                })
+                                .collect();
+
+                            web::Json(resolved)
+                        }
+                    }),
+                ),
+        )
+        .await;
+
+        actix_web::test::call_and_read_body_json(
+            &app,
+            TestRequest::get().uri("/__routes").to_request(),
+        )
+        .await
+    }
+
+    /// Replaces each `{parameter}` in a route with a placeholder ID.
+    fn fill_parameters(route: &str) -> String {
+        route
+            .split('/')
+            .map(|segment| {
+                if segment.starts_with('{') {
+                    "00000000000000000000000000000001"
+                } else {
+                    segment
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    async fn served_spec() -> serde_json::Value {
+        test_state!(state = []);
+
+        test_request!(GET "/api/openapi.json" => OK with content | state = state)
+    }
+
+    fn spec_routes(spec: &serde_json::Value) -> BTreeSet<(String, String)> {
+        spec["paths"]
+            .as_object()
+            .expect("the spec should have paths")
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .expect("a path item")
+                    .keys()
+                    .filter(|method| ["get", "post", "put", "delete"].contains(&method.as_str()))
+                    .map(|method| (method.to_uppercase(), path.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn get_openapi() {
+        test_log_init();
+
+        let spec = served_spec().await;
+        assert!(
+            spec["openapi"]
+                .as_str()
+                .expect("an openapi version")
+                .starts_with("3.")
+        );
+        assert_eq!(spec["info"]["title"], "Rex");
+        assert!(spec["components"]["securitySchemes"]["oidc"].is_object());
+        assert!(spec["components"]["schemas"]["IdeaV3"].is_object());
+    }
+
+    #[actix_rt::test]
+    async fn get_docs() {
+        test_log_init();
+
+        test_state!(state = []);
+        let app = get_test_app(state.clone()).await;
+
+        let req = TestRequest::get().uri("/api/docs").to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_eq!(
+            response.status(),
+            actix_http::StatusCode::PERMANENT_REDIRECT
+        );
+
+        let req = TestRequest::get().uri("/api/docs/").to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        let body = actix_web::test::read_body(response).await;
+        assert!(String::from_utf8_lossy(&body).contains("swagger-ui"));
+
+        let req = TestRequest::get()
+            .uri("/api/docs/swagger-initializer.js")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        let body = actix_web::test::read_body(response).await;
+        let initializer = String::from_utf8_lossy(&body);
+        assert!(initializer.contains("/api/openapi.json"));
+        assert!(initializer.contains(crate::api::auth::OIDC_CLIENT_ID));
+    }
+
+    #[actix_rt::test]
+    async fn spec_routes_are_registered() {
+        test_log_init();
+
+        let spec = served_spec().await;
+
+        test_state!(state = []);
+        let app = get_test_app(state.clone()).await;
+
+        for (method, path) in spec_routes(&spec) {
+            let uri = fill_parameters(&path);
+
+            let req = TestRequest::default()
+                .method(method.parse().expect("a valid method"))
+                .uri(&uri)
+                .insert_header(("Authorization", auth_token()))
+                .to_request();
+
+            let response = actix_web::test::call_service(&app, req).await;
+            let status = response.status();
+            let body = actix_web::test::read_body(response).await;
+
+            assert!(
+                !(status == actix_http::StatusCode::NOT_FOUND && body.is_empty()),
+                "{method} {path} is documented but no handler is registered for it"
+            );
+        }
+
+        // Each handler is registered under its function's name, which is also the ID of the
+        // operation describing it, so every handler must have a matching operation.
+        let operations: BTreeMap<String, String> = spec["paths"]
+            .as_object()
+            .expect("the spec should have paths")
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .expect("a path item")
+                    .values()
+                    .filter_map(|operation| operation["operationId"].as_str())
+                    .map(|id| (id.to_string(), path.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        // Every documented operation must resolve, through actix-web's public API, to a
+        // resource registered under its ID at the documented path.
+        let resolved = resolve_operations(&operations).await;
+        let unregistered: Vec<_> = operations
+            .iter()
+            .filter(|(id, path)| resolved.get(*id) != Some(&Some(fill_parameters(path))))
+            .map(|(id, _)| id)
+            .collect();
+        assert!(
+            unregistered.is_empty(),
+            "these operations are documented but have no registered handler: {unregistered:?}"
+        );
+
+        let undocumented: Vec<_> = registered_routes()
+            .await
+            .into_iter()
+            .filter(|(name, pattern)| operations.get(name) != Some(pattern))
+            .collect();
+        assert!(
+            undocumented.is_empty(),
+            "these handlers are registered but missing from the OpenAPI document: {undocumented:?}"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::{APIError, FieldError};
+use actix_service::*;
+use actix_web::body::EitherBody;
+use actix_web::dev::*;
+use actix_web::http::header::{ACCEPT, HeaderMap};
+use actix_web::{Error, HttpResponse};
+use futures::{
+    Future, FutureExt,
+    future::{Ready, ok},
+};
+use opentelemetry::trace::TraceContextExt;
+use tracing_batteries::prelude::*;
+use utoipa::ToSchema;
+
+/// Renders [`APIError`] responses as RFC 7807 `application/problem+json` documents
+/// for clients which request them through their `Accept` header, and for all `/api/v4`
+/// routes. Other clients continue to receive the legacy `{code, error, message}` body.
+pub struct ProblemDetails;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    pub instance: String,
+    #[serde(rename = "traceId")]
+    pub trace_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+impl Problem {
+    fn new(err: &APIError, instance: &str, trace_id: String) -> Self {
+        Self {
+            problem_type: err
+                .kind()
+                .map(|k| k.type_uri())
+                .unwrap_or("about:blank")
+                .to_string(),
+            title: err.error.clone(),
+            status: err.code,
+            detail: err.message.clone(),
+            instance: instance.to_string(),
+            trace_id,
+            errors: err.fields.clone(),
+        }
+    }
+}
+
+fn wants_problem(path: &str, headers: &HeaderMap) -> bool {
+    path.starts_with("/api/v4/")
+        || headers
+            .get_all(ACCEPT)
+            .filter_map(|h| h.to_str().ok())
+            .any(|h| h.contains("application/problem+json"))
+}
+
+/// Identifies the request using its OpenTelemetry trace, so that problems reported
+/// by users can be correlated with our telemetry. A random ID is used when the
+/// request is not being traced.
+fn trace_id() -> String {
+    let context = Span::current().context();
+    let span_context = context.span().span_context().clone();
+
+    if span_context.is_valid() {
+        span_context.trace_id().to_string()
+    } else {
+        format!("{:0>32x}", crate::models::new_id())
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ProblemDetails
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ProblemDetailsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(ProblemDetailsMiddleware { service })
+    }
+}
+
+#[doc(hidden)]
+pub struct ProblemDetailsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for ProblemDetailsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if !wants_problem(req.path(), req.headers()) {
+            return Box::pin(
+                self.service
+                    .call(req)
+                    .map(|outcome| outcome.map(|res| res.map_into_left_body())),
+            );
+        }
+
+        let instance = req.path().to_string();
+
+        Box::pin(self.service.call(req).map(move |outcome| {
+            let res = outcome?;
+
+            let problem = match res
+                .response()
+                .error()
+                .and_then(|e| e.as_error::<APIError>())
+            {
+                Some(err) => Problem::new(err, &instance, trace_id()),
+                None => return Ok(res.map_into_left_body()),
+            };
+
+            let (req, res) = res.into_parts();
+            let mut builder = HttpResponse::build(res.status());
+            for (name, value) in res.headers().iter() {
+                if name != actix_web::http::header::CONTENT_TYPE {
+                    builder.append_header((name.clone(), value.clone()));
+                }
+            }
+
+            let response = builder
+                .content_type("application/problem+json")
+                .json(problem);
+
+            Ok(ServiceResponse::new(req, response).map_into_right_body())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test::*;
+    use actix_web::test::TestRequest;
+
+    #[actix_rt::test]
+    async fn problem_forbidden() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002")
+            .insert_header(("Authorization", auth_token()))
+            .insert_header(("Accept", "application/problem+json"))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::FORBIDDEN).await;
+        assert_eq!(
+            response
+                .headers()
+                .get("Content-Type")
+                .expect("a content type header"),
+            "application/problem+json"
+        );
+
+        let problem: Problem = get_content(response).await;
+        assert_eq!(
+            problem.problem_type,
+            "https://rex.sierrasoftworks.com/problems/forbidden-role"
+        );
+        assert_eq!(problem.status, 403);
+        assert_eq!(
+            problem.instance,
+            "/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002"
+        );
+        assert_eq!(problem.trace_id.len(), 32);
+    }
+
+    #[actix_rt::test]
+    async fn problem_validation_fields() {
+        test_log_init();
+
+        test_state!(
+            state = [crate::models::StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: crate::models::Role::Owner,
+            }]
+        );
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::post()
+            .uri("/api/v3/collection/00000000000000000000000000000001/invites")
+            .insert_header(("Authorization", auth_token()))
+            .insert_header(("Accept", "application/problem+json"))
+            .set_json(serde_json::json!({ "email": "not-an-email", "role": "Viewer" }))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::BAD_REQUEST).await;
+
+        let problem: Problem = get_content(response).await;
+        assert_eq!(
+            problem.problem_type,
+            "https://rex.sierrasoftworks.com/problems/validation"
+        );
+        assert_eq!(problem.errors.len(), 1);
+        assert_eq!(problem.errors[0].field, "email");
+    }
+
+    #[actix_rt::test]
+    async fn legacy_error_format() {
+        test_log_init();
+
+        test_state!(state = []);
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000001/idea/00000000000000000000000000000002")
+            .insert_header(("Authorization", auth_token()))
+            .to_request();
+
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::FORBIDDEN).await;
+
+        let err: APIError = get_content(response).await;
+        assert_eq!(err.code, 403);
+        assert_eq!(err.error, "Forbidden");
+    }
+}
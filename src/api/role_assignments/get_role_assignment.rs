@@ -1,9 +1,22 @@
 use super::CollectionUserFilter;
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "role-assignments",
+    summary = "Get a user's role on a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("user" = String, Path, description = "The ID of the user.")
+    ),
+    responses(
+        (status = 200, description = "The role assignment for the user.", body = RoleAssignmentV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/collection/{collection}/user/{user}")]
 async fn get_role_assignment_v3(
@@ -21,24 +34,7 @@ async fn get_role_assignment_v3(
     let tuid = parse_uuid!(info.user, "user ID");
 
     if uid != tuid {
-        let role = state
-            .store
-            .send(
-                GetRoleAssignment {
-                    collection_id: cid,
-                    principal_id: uid,
-                }
-                .trace(),
-            )
-            .await??;
-
-        if role.role != Role::Owner {
-            return Err(APIError::new(
-                403,
-                "Forbidden",
-                "You do not have permission to view or manage the list of users for this collection.",
-            ));
-        }
+        authorize(&state, cid, uid, Permission::MemberList).await?;
     }
 
     state
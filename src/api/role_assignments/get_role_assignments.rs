@@ -1,9 +1,20 @@
 use super::CollectionFilter;
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "role-assignments",
+    summary = "List the users with access to a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The role assignments for the collection.", body = [RoleAssignmentV3])
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/collection/{collection}/users")]
 async fn get_role_assignments_v3(
@@ -19,28 +30,13 @@ async fn get_role_assignments_v3(
     let cid = parse_uuid!(info.collection, "collection ID");
     let uid = parse_uuid!(token.oid(), "auth token oid");
 
-    let role = state
+    authorize(&state, cid, uid, Permission::MemberList).await?;
+
+    state
         .store
-        .send(
-            GetRoleAssignment {
-                collection_id: cid,
-                principal_id: uid,
-            }
-            .trace(),
-        )
-        .await??;
-    match role.role {
-        Role::Owner => state
-            .store
-            .send(GetRoleAssignments { collection_id: cid }.trace())
-            .await?
-            .map(|roles| web::Json(roles.iter().map(|i| i.clone().into()).collect())),
-        _ => Err(APIError::new(
-            403,
-            "Forbidden",
-            "You do not have permission to view or manage the list of users for this collection.",
-        )),
-    }
+        .send(GetRoleAssignments { collection_id: cid }.trace())
+        .await?
+        .map(|roles| web::Json(roles.iter().map(|i| i.clone().into()).collect()))
 }
 
 #[cfg(test)]
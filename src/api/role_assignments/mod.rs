@@ -3,8 +3,9 @@ mod get_role_assignments;
 mod remove_role_assignment;
 mod store_role_assignment;
 
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize};
 use actix_web::web;
+use utoipa::OpenApi;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_role_assignment::get_role_assignment_v3)
@@ -13,6 +14,15 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(remove_role_assignment::remove_role_assignment_v3);
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_role_assignment::get_role_assignment_v3,
+    get_role_assignments::get_role_assignments_v3,
+    store_role_assignment::store_role_assignment_v3,
+    remove_role_assignment::remove_role_assignment_v3,
+))]
+pub struct ApiDoc;
+
 #[derive(Debug, Deserialize, Serialize)]
 struct CollectionFilter {
     collection: String,
@@ -1,9 +1,22 @@
 use super::CollectionUserFilter;
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{HttpResponse, delete, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "role-assignments",
+    summary = "Remove a user's access to a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("user" = String, Path, description = "The ID of the user.")
+    ),
+    responses(
+        (status = 204, description = "The role assignment was removed."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[delete("/api/v3/collection/{collection}/user/{user}")]
 async fn remove_role_assignment_v3(
@@ -28,37 +41,20 @@ async fn remove_role_assignment_v3(
         ));
     }
 
-    let role = state
+    authorize(&state, cid, uid, Permission::MemberRemove).await?;
+
+    state
         .store
         .send(
-            GetRoleAssignment {
+            RemoveRoleAssignment {
                 collection_id: cid,
-                principal_id: uid,
+                principal_id: tuid,
             }
             .trace(),
         )
         .await??;
-    match role.role {
-        Role::Owner => {
-            state
-                .store
-                .send(
-                    RemoveRoleAssignment {
-                        collection_id: cid,
-                        principal_id: tuid,
-                    }
-                    .trace(),
-                )
-                .await??;
 
-            Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
-        }
-        _ => Err(APIError::new(
-            403,
-            "Forbidden",
-            "You do not have permission to view or manage the list of users for this collection.",
-        )),
-    }
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
 }
 
 #[cfg(test)]
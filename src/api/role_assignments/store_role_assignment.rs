@@ -1,9 +1,23 @@
 use super::CollectionUserFilter;
-use super::{APIError, AuthToken};
+use super::{APIError, AuthToken, authorize};
 use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{put, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "role-assignments",
+    summary = "Set a user's role on a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("user" = String, Path, description = "The ID of the user.")
+    ),
+    request_body = RoleAssignmentV3,
+    responses(
+        (status = 200, description = "The role assignment which was stored.", body = RoleAssignmentV3),
+        (status = 400, description = "The request was not valid.", body = APIError)
+    ),
+    security(("oidc" = ["RoleAssignments.Write"]))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[put("/api/v3/collection/{collection}/user/{user}")]
 async fn store_role_assignment_v3(
@@ -40,65 +54,48 @@ async fn store_role_assignment_v3(
         ));
     }
 
-    let role = state
+    authorize(&state, cid, uid, Permission::MemberInvite).await?;
+
+    match state
         .store
         .send(
-            GetRoleAssignment {
-                collection_id: cid,
-                principal_id: uid,
+            GetCollection {
+                principal_id: tuid,
+                id: cid,
             }
             .trace(),
         )
-        .await??;
-    match role.role {
-        Role::Owner => {
-            match state
-                .store
-                .send(
-                    GetCollection {
-                        principal_id: tuid,
-                        id: cid,
-                    }
-                    .trace(),
-                )
-                .await?
-            {
-                Ok(_) => {}
-                Err(err) if err.code == 404 => {
-                    state
-                        .store
-                        .send(
-                            StoreCollection {
-                                principal_id: tuid,
-                                collection_id: cid,
-                                name: original_collection.name,
-                            }
-                            .trace(),
-                        )
-                        .await??;
-                }
-                Err(err) => return Err(err),
-            }
-
+        .await?
+    {
+        Ok(_) => {}
+        Err(err) if err.code == 404 => {
             state
                 .store
                 .send(
-                    StoreRoleAssignment {
+                    StoreCollection {
                         principal_id: tuid,
                         collection_id: cid,
-                        role: collection.role.as_str().into(),
+                        name: original_collection.name,
                     }
                     .trace(),
                 )
-                .await?
-                .map(|collection| collection.into())
+                .await??;
         }
-        _ => Err(APIError::new(
-            403,
-            "Forbidden",
-            "You do not have permission to view or manage the list of users for this collection.",
-        )),
+        Err(err) => return Err(err),
     }
+
+    state
+        .store
+        .send(
+            StoreRoleAssignment {
+                principal_id: tuid,
+                collection_id: cid,
+                role: collection.role.as_str().into(),
+            }
+            .trace(),
+        )
+        .await?
+        .map(|collection| collection.into())
 }
 
 #[cfg(test)]
@@ -0,0 +1,181 @@
+//! A minimal writer for the Atom syndication format (RFC 4287), used to publish the
+//! ideas which have recently been added to, or completed in, a collection.
+
+use crate::models::Idea;
+use chrono::{DateTime, Utc};
+
+pub struct Feed {
+    id: String,
+    title: String,
+    link: String,
+    entries: Vec<(DateTime<Utc>, String)>,
+}
+
+impl Feed {
+    pub fn new(collection_id: u128, title: impl Into<String>, link: impl Into<String>) -> Self {
+        Self {
+            id: urn(collection_id),
+            title: title.into(),
+            link: link.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds an entry for an idea, identified by the idea's ID so that feed readers
+    /// treat its completion as an update to the entry for the idea rather than a new one.
+    /// Ideas which were stored before Rex recorded when ideas are created are skipped.
+    pub fn add_idea(&mut self, idea: &Idea) {
+        let Some(published) = idea.created_at else {
+            return;
+        };
+        let updated = idea.completed_at.unwrap_or(published).max(published);
+
+        let mut tags: Vec<&str> = idea.tags.iter().map(|t| t.as_str()).collect();
+        tags.sort();
+
+        let mut entry = String::new();
+        entry.push_str("  <entry>\n");
+        entry.push_str(&format!("    <id>{}</id>\n", urn(idea.id)));
+        entry.push_str(&format!("    <title>{}</title>\n", escape(&idea.name)));
+        entry.push_str(&format!(
+            "    <published>{}</published>\n",
+            published.to_rfc3339()
+        ));
+        entry.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            updated.to_rfc3339()
+        ));
+        for tag in tags {
+            entry.push_str(&format!("    <category term=\"{}\" />\n", escape(tag)));
+        }
+        if idea.completed {
+            entry.push_str("    <category term=\"completed\" scheme=\"https://rex.sierrasoftworks.com/status\" />\n");
+        }
+        entry.push_str(&format!(
+            "    <content type=\"text\">{}</content>\n",
+            escape(&idea.description)
+        ));
+        entry.push_str("  </entry>\n");
+
+        self.entries.push((updated, entry));
+    }
+
+    /// Renders the feed, including at most `limit` of the most recently updated entries.
+    pub fn render(mut self, limit: usize) -> String {
+        self.entries
+            .sort_by_key(|(updated, _)| std::cmp::Reverse(*updated));
+        self.entries.truncate(limit);
+
+        let updated = self
+            .entries
+            .first()
+            .map(|(updated, _)| *updated)
+            .unwrap_or(DateTime::UNIX_EPOCH);
+
+        let mut feed = String::new();
+        feed.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        feed.push_str(&format!("  <id>{}</id>\n", self.id));
+        feed.push_str(&format!("  <title>{}</title>\n", escape(&self.title)));
+        feed.push_str(&format!("  <updated>{}</updated>\n", updated.to_rfc3339()));
+        feed.push_str(&format!(
+            "  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}\" />\n",
+            escape(&self.link)
+        ));
+        feed.push_str("  <author><name>Rex</name></author>\n");
+        feed.push_str(
+            "  <generator uri=\"https://github.com/SierraSoftworks/rex-rs\">Rex</generator>\n",
+        );
+        for (_, entry) in self.entries {
+            feed.push_str(&entry);
+        }
+        feed.push_str("</feed>\n");
+
+        feed
+    }
+}
+
+fn urn(id: u128) -> String {
+    format!("urn:uuid:{}", uuid::Uuid::from_u128(id).hyphenated())
+}
+
+/// Escapes the characters which have special meaning in XML text and attribute values.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn idea(id: u128, name: &str, created_at: Option<DateTime<Utc>>) -> Idea {
+        Idea {
+            id,
+            collection_id: 1,
+            name: name.into(),
+            description: "Fish & chips".into(),
+            tags: ["outdoor".to_string()].into_iter().collect(),
+            completed: false,
+            created_at,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn feed() {
+        let created = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let completed = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+
+        let mut feed = Feed::new(1, "My <Ideas>", "https://rex.example.com/feed.atom?share=2");
+        feed.add_idea(&idea(2, "Go hiking", Some(created)));
+        feed.add_idea(&Idea {
+            completed: true,
+            completed_at: Some(completed),
+            ..idea(3, "Read a book", Some(created))
+        });
+        feed.add_idea(&idea(4, "Legacy idea", None));
+
+        let output = feed.render(10);
+        assert!(output.contains("<id>urn:uuid:00000000-0000-0000-0000-000000000001</id>"));
+        assert!(output.contains("<title>My &lt;Ideas&gt;</title>"));
+        assert!(output.contains("href=\"https://rex.example.com/feed.atom?share=2\""));
+        assert!(output.contains("<updated>2024-02-01T00:00:00+00:00</updated>"));
+        assert!(output.contains("<content type=\"text\">Fish &amp; chips</content>"));
+        assert!(!output.contains("Legacy idea"));
+
+        let read = output
+            .find("urn:uuid:00000000-0000-0000-0000-000000000003")
+            .unwrap();
+        let hike = output
+            .find("urn:uuid:00000000-0000-0000-0000-000000000002")
+            .unwrap();
+        assert!(
+            read < hike,
+            "the most recently updated entry should come first"
+        );
+    }
+
+    #[test]
+    fn limit() {
+        let created = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let mut feed = Feed::new(1, "My Ideas", "https://rex.example.com/feed.atom");
+        for id in 0..5 {
+            feed.add_idea(&idea(
+                id,
+                "Go hiking",
+                Some(created + chrono::Duration::days(id as i64)),
+            ));
+        }
+
+        let output = feed.render(2);
+        assert_eq!(output.matches("<entry>").count(), 2);
+        assert!(output.contains("urn:uuid:00000000-0000-0000-0000-000000000004"));
+        assert!(!output.contains("urn:uuid:00000000-0000-0000-0000-000000000002"));
+    }
+}
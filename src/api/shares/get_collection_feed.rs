@@ -0,0 +1,305 @@
+use super::{
+    APIError, AuthToken, CollectionFilter, MAX_FEED_ENTRIES, atom::Feed, authorize,
+    resolve_share_link,
+};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{FromRequest, HttpRequest, HttpResponse, get, web};
+use tracing::instrument;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct FeedQuery {
+    /// The ID of a share link for the collection, which may be used in place of an auth token.
+    share: Option<String>,
+    /// The ID of the member whose calendar feed is provided in `feed`.
+    user: Option<String>,
+    /// The ID of one of the member's calendar feeds, which may be used in place of an auth
+    /// token by members who can read the collection.
+    feed: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "sharing",
+    summary = "Subscribe to a collection's recent ideas",
+    description = "Retrieves the ideas which were most recently added to, or completed in, a collection as an Atom feed. Feed readers which cannot send an auth token may provide the ID of one of the collection's share links instead, or a member's ID along with the ID of one of their calendar feeds, which acts as a credential for each of the collections that member can read. Removing the calendar feed revokes access through it.",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        FeedQuery
+    ),
+    responses(
+        (status = 200, description = "The collection's recent ideas.", content_type = "application/atom+xml", body = String),
+        (status = 401, description = "Neither a share link, a calendar feed nor an auth token was provided.", body = APIError),
+        (status = 403, description = "The member cannot read the collection.", body = APIError),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Ideas.Read"]))
+)]
+#[instrument(err, skip(req, state), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/feed.atom")]
+async fn get_collection_feed_v3(
+    (req, info, query, state): (
+        HttpRequest,
+        web::Path<CollectionFilter>,
+        web::Query<FeedQuery>,
+        web::Data<GlobalState>,
+    ),
+) -> Result<HttpResponse, APIError> {
+    let cid = parse_uuid!(info.collection, "collection ID");
+
+    let principal_id = match (&query.share, &query.user, &query.feed) {
+        (Some(share), _, _) => {
+            let sid = parse_uuid!(share, "share link ID");
+            resolve_share_link(&state, cid, sid).await?.created_by
+        }
+        (None, Some(user), Some(feed)) => {
+            let uid = parse_uuid!(user, "user ID");
+            let fid = parse_uuid!(feed, "calendar feed ID");
+
+            state
+                .store
+                .send(
+                    GetCalendarFeed {
+                        principal_id: uid,
+                        id: fid,
+                    }
+                    .trace(),
+                )
+                .await??;
+
+            authorize(&state, cid, uid, Permission::IdeaRead).await?;
+            uid
+        }
+        _ => {
+            let token = AuthToken::extract(&req).await?;
+            require_role!(token, "Administrator", "User");
+            require_scope!(token, "Ideas.Read");
+
+            let uid = parse_uuid!(token.oid(), "auth token oid");
+            authorize(&state, cid, uid, Permission::IdeaRead).await?;
+            uid
+        }
+    };
+
+    let title = match state
+        .store
+        .send(
+            GetCollection {
+                id: cid,
+                principal_id,
+            }
+            .trace(),
+        )
+        .await?
+    {
+        Ok(collection) => collection.name,
+        Err(err) if err.code == 404 => "Rex".to_string(),
+        Err(err) => return Err(err),
+    };
+
+    let ideas = state
+        .store
+        .send(
+            GetIdeas {
+                collection: cid,
+                is_completed: None,
+                tag: None,
+            }
+            .trace(),
+        )
+        .await??;
+
+    let connection = req.connection_info();
+    let mut feed = Feed::new(
+        cid,
+        title,
+        format!(
+            "{}://{}{}",
+            connection.scheme(),
+            connection.host(),
+            req.uri()
+        ),
+    );
+    for idea in ideas.iter() {
+        feed.add_idea(idea);
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/atom+xml; charset=utf-8")
+        .body(feed.render(MAX_FEED_ENTRIES)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+    use actix_web::test::TestRequest;
+
+    async fn get_feed(state: &GlobalState, req: TestRequest) -> String {
+        let app = get_test_app(state.clone()).await;
+        let response = actix_web::test::call_service(&app, req.to_request()).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+        assert_eq!(
+            response.headers().get("Content-Type").unwrap(),
+            "application/atom+xml; charset=utf-8"
+        );
+
+        let body = actix_web::test::read_body(response).await;
+        String::from_utf8_lossy(&body).to_string()
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_feed_v3_share() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 3,
+                    role: Role::Owner,
+                },
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 3,
+                    name: "Weekend Plans".into(),
+                },
+                StoreShareLink {
+                    collection_id: 1,
+                    id: 2,
+                    principal_id: 3,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreIdea {
+                    id: 4,
+                    collection: 1,
+                    name: "Go hiking".into(),
+                    description: "Somewhere with a view".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let feed = get_feed(
+            &state,
+            TestRequest::get().uri("/api/v3/collection/00000000000000000000000000000001/feed.atom?share=00000000000000000000000000000002"),
+        )
+        .await;
+
+        assert!(feed.contains("<title>Weekend Plans</title>"));
+        assert!(feed.contains("<id>urn:uuid:00000000-0000-0000-0000-000000000004</id>"));
+        assert!(feed.contains("<title>Go hiking</title>"));
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_feed_v3_token() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Viewer,
+                },
+                StoreIdea {
+                    id: 4,
+                    collection: 1,
+                    name: "Go hiking".into(),
+                    completed: true,
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let feed = get_feed(
+            &state,
+            TestRequest::get()
+                .uri("/api/v3/collection/00000000000000000000000000000001/feed.atom")
+                .insert_header(("Authorization", auth_token())),
+        )
+        .await;
+
+        assert!(feed.contains("<title>Rex</title>"));
+        assert!(feed.contains("<category term=\"completed\""));
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_feed_v3_calendar_feed() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 3,
+                    role: Role::Viewer,
+                },
+                StoreCalendarFeed {
+                    principal_id: 3,
+                    id: 6,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreCalendarFeed {
+                    principal_id: 7,
+                    id: 8,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreIdea {
+                    id: 4,
+                    collection: 1,
+                    name: "Go hiking".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let feed = get_feed(
+            &state,
+            TestRequest::get().uri("/api/v3/collection/00000000000000000000000000000001/feed.atom?user=00000000000000000000000000000003&feed=00000000000000000000000000000006"),
+        )
+        .await;
+        assert!(feed.contains("<title>Go hiking</title>"));
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000001/feed.atom?user=00000000000000000000000000000003&feed=00000000000000000000000000000008")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::NOT_FOUND).await;
+
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000001/feed.atom?user=00000000000000000000000000000007&feed=00000000000000000000000000000008")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::FORBIDDEN).await;
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_feed_v3_unauthorized() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Viewer,
+            }]
+        );
+
+        let app = get_test_app(state.clone()).await;
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000001/feed.atom")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::UNAUTHORIZED).await;
+
+        let req = TestRequest::get()
+            .uri("/api/v3/collection/00000000000000000000000000000001/feed.atom?share=00000000000000000000000000000002")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::NOT_FOUND).await;
+
+        test_request!(GET "/api/v3/collection/00000000000000000000000000000005/feed.atom" => FORBIDDEN | state = state);
+    }
+}
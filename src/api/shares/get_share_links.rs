@@ -0,0 +1,73 @@
+use super::{APIError, AuthToken, CollectionFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "sharing",
+    summary = "List the public share links for a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The share links for the collection.", body = [ShareLinkV3])
+    ),
+    security(("oidc" = ["Collections.Read"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/shares")]
+async fn get_share_links_v3(
+    (info, state, token): (
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<web::Json<Vec<ShareLinkV3>>, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Read");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::ShareManage).await?;
+
+    state
+        .store
+        .send(GetShareLinks { collection_id: cid }.trace())
+        .await?
+        .map(|links| web::Json(links.into_iter().map(|l| l.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_share_links_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreShareLink {
+                    collection_id: 1,
+                    id: 2,
+                    principal_id: 0,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        let content: Vec<ShareLinkV3> = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/shares" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(
+            content[0].id,
+            Some("00000000000000000000000000000002".into())
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use super::{APIError, CollectionShareFilter, QueryFilter, resolve_share_link};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "sharing",
+    summary = "Get a random idea from a shared collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("share" = String, Path, description = "The ID of the share link."),
+        QueryFilter
+    ),
+    responses(
+        (status = 200, description = "A randomly selected idea which matches the filter.", body = IdeaV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    )
+)]
+#[instrument(err, skip(state), fields(otel.kind = "internal"))]
+#[get("/api/v3/shared/{collection}/{share}/idea/random")]
+async fn get_shared_random_idea_v3(
+    (info, query, state): (
+        web::Path<CollectionShareFilter>,
+        web::Query<QueryFilter>,
+        web::Data<GlobalState>,
+    ),
+) -> Result<IdeaV3, APIError> {
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let sid = parse_uuid!(info.share, "share link ID");
+
+    resolve_share_link(&state, cid, sid).await?;
+
+    state
+        .store
+        .send(
+            GetRandomIdea {
+                collection: cid,
+                is_completed: query.complete,
+                tag: query.tag.clone(),
+            }
+            .trace(),
+        )
+        .await?
+        .map(|idea| idea.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_shared_random_idea_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 3,
+                    role: Role::Owner,
+                },
+                StoreShareLink {
+                    collection_id: 1,
+                    id: 2,
+                    principal_id: 3,
+                    created_at: chrono::Utc::now(),
+                },
+                StoreIdea {
+                    id: 4,
+                    collection: 1,
+                    name: "Test Idea".into(),
+                    description: "This is a test idea".into(),
+                    tags: hashset!("test"),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let app = get_test_app(state.clone()).await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/v3/shared/00000000000000000000000000000001/00000000000000000000000000000002/idea/random")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        let response = assert_status(response, actix_http::StatusCode::OK).await;
+
+        let content: IdeaV3 = get_content(response).await;
+        assert_eq!(content.name, "Test Idea".to_string());
+    }
+}
@@ -0,0 +1,85 @@
+mod atom;
+mod get_collection_feed;
+mod get_share_links;
+mod get_shared_ideas;
+mod get_shared_random_idea;
+mod new_share_link;
+mod remove_share_link;
+
+use super::{APIError, AuthToken, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::web;
+use utoipa::{IntoParams, OpenApi};
+
+/// The maximum number of ideas which are included in a collection's feed.
+const MAX_FEED_ENTRIES: usize = 50;
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_shared_ideas::get_shared_ideas_v3)
+        .service(get_shared_random_idea::get_shared_random_idea_v3)
+        .service(get_collection_feed::get_collection_feed_v3)
+        .service(get_share_links::get_share_links_v3)
+        .service(new_share_link::new_share_link_v3)
+        .service(remove_share_link::remove_share_link_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_shared_ideas::get_shared_ideas_v3,
+    get_shared_random_idea::get_shared_random_idea_v3,
+    get_collection_feed::get_collection_feed_v3,
+    get_share_links::get_share_links_v3,
+    new_share_link::new_share_link_v3,
+    remove_share_link::remove_share_link_v3,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionFilter {
+    collection: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionShareFilter {
+    collection: String,
+    share: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct QueryFilter {
+    /// Only include ideas which have been given this tag.
+    tag: Option<String>,
+    /// Only include ideas which have (or have not) been completed.
+    complete: Option<bool>,
+}
+
+/// Resolves an anonymous share link, ensuring that it still exists and that the
+/// owner who created it remains permitted to share the collection.
+async fn resolve_share_link(
+    state: &GlobalState,
+    collection_id: u128,
+    id: u128,
+) -> Result<ShareLink, APIError> {
+    let link = state
+        .store
+        .send(GetShareLink { collection_id, id }.trace())
+        .await??;
+
+    authorize(
+        state,
+        collection_id,
+        link.created_by,
+        Permission::ShareManage,
+    )
+    .await
+    .map_err(|_| {
+        APIError::new(
+            404,
+            "Not Found",
+            "The share link you provided could not be found. Please check it and try again.",
+        )
+    })?;
+
+    Ok(link)
+}
@@ -0,0 +1,92 @@
+use super::{APIError, AuthToken, CollectionFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{post, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "sharing",
+    summary = "Create a public share link for a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 201, description = "The share link which was created.", body = ShareLinkV3)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[post("/api/v3/collection/{collection}/shares")]
+async fn new_share_link_v3(
+    (info, state, token): (
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<ShareLinkV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::ShareManage).await?;
+
+    state
+        .store
+        .send(
+            StoreShareLink {
+                collection_id: cid,
+                id: new_id(),
+                principal_id: uid,
+                created_at: chrono::Utc::now(),
+            }
+            .trace(),
+        )
+        .await?
+        .map(|link| link.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn new_share_link_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        let content: ShareLinkV3 = test_request!(POST "/api/v3/collection/00000000000000000000000000000001/shares" => CREATED with content | state = state);
+        assert_eq!(
+            content.collection_id,
+            Some("00000000000000000000000000000001".into())
+        );
+        assert_eq!(
+            content.created_by,
+            Some("00000000000000000000000000000000".into())
+        );
+        assert!(content.id.is_some());
+    }
+
+    #[actix_rt::test]
+    async fn new_share_link_v3_contributor() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Contributor,
+            }]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/shares" => FORBIDDEN | state = state);
+    }
+}
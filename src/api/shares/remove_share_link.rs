@@ -0,0 +1,85 @@
+use super::{APIError, AuthToken, CollectionShareFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{HttpResponse, delete, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "sharing",
+    summary = "Revoke a public share link",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("share" = String, Path, description = "The ID of the share link.")
+    ),
+    responses(
+        (status = 204, description = "The share link was revoked."),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[delete("/api/v3/collection/{collection}/share/{share}")]
+async fn remove_share_link_v3(
+    (info, state, token): (
+        web::Path<CollectionShareFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<HttpResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let id = parse_uuid!(info.share, "share link ID");
+
+    authorize(&state, cid, uid, Permission::ShareManage).await?;
+
+    state
+        .store
+        .send(
+            RemoveShareLink {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(HttpResponse::build(actix_http::StatusCode::NO_CONTENT).finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn remove_share_link_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreShareLink {
+                    collection_id: 1,
+                    id: 2,
+                    principal_id: 0,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        test_request!(DELETE "/api/v3/collection/00000000000000000000000000000001/share/00000000000000000000000000000002" => NO_CONTENT | state = state);
+
+        let app = get_test_app(state.clone()).await;
+        let req = actix_web::test::TestRequest::get()
+            .uri("/api/v3/shared/00000000000000000000000000000001/00000000000000000000000000000002/ideas")
+            .to_request();
+        let response = actix_web::test::call_service(&app, req).await;
+        assert_status(response, actix_http::StatusCode::NOT_FOUND).await;
+    }
+}
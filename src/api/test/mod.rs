@@ -1,4 +1,5 @@
 use crate::api::configure;
+use crate::config::Config;
 use crate::models::*;
 use actix_web::{
     App,
@@ -20,6 +21,16 @@ pub fn test_log_init() {
         .try_init();
 }
 
+/// The configuration which the test app is started with.
+pub fn test_config() -> Config {
+    Config {
+        slack: crate::api::SlackConfig {
+            signing_secret: Some(crate::api::chat::test::SIGNING_SECRET.into()),
+        },
+        ..Default::default()
+    }
+}
+
 pub async fn get_test_app(
     state: GlobalState,
 ) -> impl actix_web::dev::Service<
@@ -27,12 +38,27 @@ pub async fn get_test_app(
     Response = actix_web::dev::ServiceResponse,
     Error = actix_web::Error,
 > {
-    let oidc = actix_web::web::Data::new(actix::Actor::start(crate::api::OidcActor::new()));
+    let oidc = actix_web::web::Data::new(actix::Actor::start(crate::api::OidcActor::new(
+        Default::default(),
+    )));
     test::init_service(
         App::new()
             .app_data(actix_web::web::Data::new(state.clone()))
             .app_data(oidc)
-            .configure(configure),
+            .wrap(actix_web::middleware::Compat::new(
+                crate::api::ProblemDetails,
+            ))
+            .wrap(actix_web::middleware::Compat::new(
+                crate::telemetry::TracingLogger,
+            ))
+            .wrap(actix_web::middleware::Compat::new(
+                crate::api::CorsConfig {
+                    allowed_origins: vec!["https://app.example.com".into()],
+                    ..Default::default()
+                }
+                .build(),
+            ))
+            .configure(configure(&test_config())),
     )
     .await
 }
@@ -54,34 +80,41 @@ pub fn assert_location_header(header: &actix_web::http::header::HeaderMap, prefi
 }
 
 pub fn auth_token() -> String {
-    let mut localized_name = LocalizedClaim::new();
-    localized_name.insert(None, EndUserName::new("Testy McTesterson".to_string()));
-
     let token = AuthIdToken::new(
-        AuthIdTokenClaims::new(
-            IssuerUrl::new("https://auth.example.com".to_string()).expect("Issuer should always parse correctly."),
-            vec![Audience::new("https://test.example.com".to_string())],
-            Utc::now() + Duration::seconds(300),
-            Utc::now(),
-            StandardClaims::new(
-                SubjectIdentifier::new("testy@example.com".to_string()),
-            ).set_name(Some(localized_name)),
-            AuthAdditionalClaims {
-                oid: "00000000-0000-0000-0000-000000000000".into(),
-                scp: "Ideas.Read Ideas.Write Collections.Read Collections.Write RoleAssignments.Write Users.Read".into(),
-                roles: vec!["Administrator".into()],
-                unique_name: "testy@example.com".into(),
-            }
-        ),
+        auth_claims(None),
         &CoreHmacKey::new("test"),
         CoreJwsSigningAlgorithm::HmacSha256,
         None,
         None,
-    ).expect("The token should be generated correctly");
+    )
+    .expect("The token should be generated correctly");
 
     format!("Bearer {}", token.to_string())
 }
 
+/// The claims of the test user's token, which only claims that their email address has
+/// been verified when `email_verified` is provided.
+pub fn auth_claims(email_verified: Option<bool>) -> AuthIdTokenClaims {
+    let mut localized_name = LocalizedClaim::new();
+    localized_name.insert(None, EndUserName::new("Testy McTesterson".to_string()));
+
+    AuthIdTokenClaims::new(
+        IssuerUrl::new("https://auth.example.com".to_string()).expect("Issuer should always parse correctly."),
+        vec![Audience::new("https://test.example.com".to_string())],
+        Utc::now() + Duration::seconds(300),
+        Utc::now(),
+        StandardClaims::new(
+            SubjectIdentifier::new("testy@example.com".to_string()),
+        ).set_name(Some(localized_name)).set_email_verified(email_verified),
+        AuthAdditionalClaims {
+            oid: "00000000-0000-0000-0000-000000000000".into(),
+            scp: "Ideas.Read Ideas.Write Collections.Read Collections.Write RoleAssignments.Write Users.Read".into(),
+            roles: vec!["Administrator".into()],
+            unique_name: "testy@example.com".into(),
+        }
+    )
+}
+
 pub async fn assert_status(
     resp: actix_web::dev::ServiceResponse,
     expected_status: actix_http::StatusCode,
@@ -4,6 +4,18 @@ use crate::{models::*, telemetry::TraceMessageExt};
 use actix_web::{get, web};
 use tracing::instrument;
 
+#[utoipa::path(
+    tag = "users",
+    summary = "Get a user",
+    params(
+        ("user" = String, Path, description = "The ID of the user.")
+    ),
+    responses(
+        (status = 200, description = "The requested user.", body = UserV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = []))
+)]
 #[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
 #[get("/api/v3/user/{user}")]
 async fn get_user_v3(
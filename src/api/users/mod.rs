@@ -2,11 +2,16 @@ mod get_user;
 
 use super::{APIError, AuthToken};
 use actix_web::web;
+use utoipa::OpenApi;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_user::get_user_v3);
 }
 
+#[derive(OpenApi)]
+#[openapi(paths(get_user::get_user_v3,))]
+pub struct ApiDoc;
+
 #[derive(Debug, Deserialize, Serialize)]
 struct UserFilter {
     user: String,
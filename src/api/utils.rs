@@ -14,28 +14,15 @@ pub async fn ensure_user_collection(
         )
     })?;
 
-    match state
-        .store
-        .send(
-            StoreUser {
-                principal_id: uid,
-                email_hash: u128::from_be_bytes(
-                    md5::compute(token.email().to_lowercase().trim().as_bytes()).into(),
-                ),
-                first_name: token.name().split(' ').next().unwrap_or("").to_string(),
-            }
-            .trace(),
-        )
-        .await?
-    {
-        Ok(_) => {}
-        Err(err) => {
-            warn!(
-                "Unable to store an entry in the users table for this user: {}",
-                err
-            );
-        }
-    }
+    let email_hash = email_hash(token.email());
+
+    let user_changed = store_user(
+        state,
+        uid,
+        email_hash,
+        token.name().split(' ').next().unwrap_or(""),
+    )
+    .await?;
 
     match state
         .store
@@ -81,5 +68,250 @@ pub async fn ensure_user_collection(
         )
         .await??;
 
+    // Invitations are only accepted for verified email addresses. When the issuer's
+    // addresses are trusted, invitations to people who already have a user record are
+    // accepted when they are created, so there can only be new ones to accept when the
+    // record has changed.
+    if token.email_verified()
+        && (user_changed || !token.issuer_emails_trusted())
+        && let Err(err) = accept_invitations(state, uid, email_hash).await
+    {
+        warn!(
+            "Unable to accept the pending invitations for user {:0>32x}: {}",
+            uid, err
+        );
+    }
+
     Ok(())
 }
+
+/// Records the user in the users table, returning whether their entry was created or
+/// changed. Failures are logged rather than returned since the table is not required to
+/// serve the request.
+async fn store_user(
+    state: &GlobalState,
+    principal_id: u128,
+    email_hash: u128,
+    first_name: &str,
+) -> Result<bool, APIError> {
+    if let Ok(user) = state.store.send(GetUser { email_hash }.trace()).await?
+        && user.principal_id == principal_id
+        && user.first_name == first_name
+    {
+        return Ok(false);
+    }
+
+    match state
+        .store
+        .send(
+            StoreUser {
+                principal_id,
+                email_hash,
+                first_name: first_name.to_string(),
+            }
+            .trace(),
+        )
+        .await?
+    {
+        Ok(_) => Ok(true),
+        Err(err) => {
+            warn!(
+                "Unable to store an entry in the users table for this user: {}",
+                err
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// Converts any pending invitations for the user's email address into role assignments
+/// on the collections they were invited to.
+#[tracing::instrument(err, skip(state))]
+pub async fn accept_invitations(
+    state: &GlobalState,
+    principal_id: u128,
+    email_hash: u128,
+) -> Result<(), APIError> {
+    let invitations = state
+        .store
+        .send(GetUserInvitations { email_hash }.trace())
+        .await??;
+
+    for invitation in invitations {
+        let collection_id = invitation.collection_id;
+
+        match state
+            .store
+            .send(
+                GetCollection {
+                    id: collection_id,
+                    principal_id: invitation.invited_by,
+                }
+                .trace(),
+            )
+            .await?
+        {
+            Ok(collection) => {
+                info!(
+                    "Accepting invitation to collection {:0>32x} for user {:0>32x}",
+                    collection_id, principal_id
+                );
+
+                state
+                    .store
+                    .send(
+                        StoreCollection {
+                            collection_id,
+                            principal_id,
+                            name: collection.name,
+                        }
+                        .trace(),
+                    )
+                    .await??;
+
+                // We never downgrade a role the user already holds on the collection.
+                match state
+                    .store
+                    .send(
+                        GetRoleAssignment {
+                            collection_id,
+                            principal_id,
+                        }
+                        .trace(),
+                    )
+                    .await?
+                {
+                    Ok(_) => {}
+                    Err(_) => {
+                        state
+                            .store
+                            .send(
+                                StoreRoleAssignment {
+                                    collection_id,
+                                    principal_id,
+                                    role: invitation.role,
+                                }
+                                .trace(),
+                            )
+                            .await??;
+                    }
+                }
+            }
+            Err(err) if err.code == 404 => {
+                info!(
+                    "Discarding invitation to collection {:0>32x} which is no longer available",
+                    collection_id
+                );
+            }
+            Err(err) => return Err(err),
+        }
+
+        state
+            .store
+            .send(
+                RemoveInvitation {
+                    collection_id,
+                    email_hash,
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    Ok(())
+}
+
+/// Retrieves the role assigned to a principal on a collection and ensures that it
+/// grants the requested permission, returning a `403 Forbidden` error otherwise.
+#[tracing::instrument(err, skip(state))]
+pub async fn authorize(
+    state: &GlobalState,
+    collection_id: u128,
+    principal_id: u128,
+    permission: Permission,
+) -> Result<RoleAssignment, APIError> {
+    let role = state
+        .store
+        .send(
+            GetRoleAssignment {
+                collection_id,
+                principal_id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    if !role.role.can(permission) {
+        debug!(
+            "Principal {:0>32x} has the {:?} role on collection {:0>32x} which does not grant {}",
+            principal_id, role.role, collection_id, permission
+        );
+
+        return Err(APIError::new(
+            403,
+            "Forbidden",
+            &format!(
+                "You do not have permission to {}.",
+                permission.description()
+            ),
+        ));
+    }
+
+    Ok(role)
+}
+
+/// Ensures that a principal may store their own entry for a collection, which any member
+/// may do, returning whether the collection is new because nobody holds a role on it yet.
+#[tracing::instrument(err, skip(state))]
+pub async fn authorize_collection_entry(
+    state: &GlobalState,
+    collection_id: u128,
+    principal_id: u128,
+) -> Result<bool, APIError> {
+    let is_new = match state
+        .store
+        .send(GetRoleAssignments { collection_id }.trace())
+        .await?
+    {
+        Ok(assignments) => assignments.is_empty(),
+        Err(err) if err.code == 404 => true,
+        Err(err) => return Err(err),
+    };
+
+    if !is_new {
+        authorize(
+            state,
+            collection_id,
+            principal_id,
+            Permission::CollectionRead,
+        )
+        .await?;
+    }
+
+    Ok(is_new)
+}
+
+/// Determines which permission is needed to store the provided idea, based on
+/// whether it is new, only changes its completion state, or modifies its content.
+pub async fn store_idea_permission(
+    state: &GlobalState,
+    collection: u128,
+    id: u128,
+    idea: &Idea,
+) -> Result<Permission, APIError> {
+    match state.store.send(GetIdea { collection, id }.trace()).await? {
+        Ok(existing) => {
+            if existing.name == idea.name
+                && existing.description == idea.description
+                && existing.tags == idea.tags
+                && existing.completed != idea.completed
+            {
+                Ok(Permission::IdeaComplete)
+            } else {
+                Ok(Permission::IdeaUpdate)
+            }
+        }
+        Err(err) if err.code == 404 => Ok(Permission::IdeaCreate),
+        Err(err) => Err(err),
+    }
+}
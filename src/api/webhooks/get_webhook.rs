@@ -0,0 +1,100 @@
+use super::{APIError, AuthToken, CollectionWebhookFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "webhooks",
+    summary = "Get a webhook",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("webhook" = String, Path, description = "The ID of the webhook.")
+    ),
+    responses(
+        (status = 200, description = "The webhook which was requested.", body = WebhookV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Read"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/webhook/{webhook}")]
+async fn get_webhook_v3(
+    (info, state, token): (
+        web::Path<CollectionWebhookFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<WebhookV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Read");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let id = parse_uuid!(info.webhook, "webhook ID");
+
+    authorize(&state, cid, uid, Permission::WebhookManage).await?;
+
+    state
+        .store
+        .send(
+            GetWebhook {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|webhook| webhook.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_webhook_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreWebhook {
+                    collection_id: 1,
+                    id: 2,
+                    url: "https://example.com/hooks/rex".into(),
+                    secret: "secret".into(),
+                    events: vec![],
+                    enabled: true,
+                    failures: 0,
+                    principal_id: 0,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        let content: WebhookV3 = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/webhook/00000000000000000000000000000002" => OK with content | state = state);
+        assert_eq!(content.url, "https://example.com/hooks/rex");
+        assert_eq!(content.enabled, Some(true));
+        assert_eq!(content.secret, None);
+    }
+
+    #[actix_rt::test]
+    async fn get_webhook_v3_not_found() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        test_request!(GET "/api/v3/collection/00000000000000000000000000000001/webhook/00000000000000000000000000000002" => NOT_FOUND | state = state);
+    }
+}
@@ -0,0 +1,155 @@
+use super::{APIError, AuthToken, CollectionWebhookFilter, MAX_DELIVERIES, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "webhooks",
+    summary = "List the recent deliveries for a webhook",
+    description = "Lists each attempt which was made to deliver an event to the webhook, most recent first.",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("webhook" = String, Path, description = "The ID of the webhook.")
+    ),
+    responses(
+        (status = 200, description = "The recent deliveries for the webhook.", body = [WebhookDeliveryV3]),
+        (status = 404, description = "The requested resource could not be found.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Read"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/webhook/{webhook}/deliveries")]
+async fn get_webhook_deliveries_v3(
+    (info, state, token): (
+        web::Path<CollectionWebhookFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<web::Json<Vec<WebhookDeliveryV3>>, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Read");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let id = parse_uuid!(info.webhook, "webhook ID");
+
+    authorize(&state, cid, uid, Permission::WebhookManage).await?;
+
+    // Ensure that the webhook belongs to this collection before revealing its deliveries.
+    state
+        .store
+        .send(
+            GetWebhook {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    let mut deliveries = state
+        .store
+        .send(GetWebhookDeliveries { webhook_id: id }.trace())
+        .await??;
+
+    deliveries.sort_by_key(|d| std::cmp::Reverse(d.timestamp));
+
+    Ok(web::Json(
+        deliveries
+            .into_iter()
+            .take(MAX_DELIVERIES)
+            .map(|d| d.into())
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_webhook_deliveries_v3() {
+        test_log_init();
+
+        let now = chrono::Utc::now();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreWebhook {
+                    collection_id: 1,
+                    id: 2,
+                    url: "https://example.com/hooks/rex".into(),
+                    secret: "secret".into(),
+                    events: vec![],
+                    enabled: true,
+                    failures: 0,
+                    principal_id: 0,
+                    created_at: now,
+                },
+                StoreWebhookDelivery {
+                    webhook_id: 2,
+                    id: 3,
+                    event_id: 10,
+                    event_type: "idea.created".into(),
+                    attempt: 1,
+                    status_code: Some(500),
+                    error: None,
+                    succeeded: false,
+                    timestamp: now - chrono::Duration::seconds(1),
+                },
+                StoreWebhookDelivery {
+                    webhook_id: 2,
+                    id: 4,
+                    event_id: 10,
+                    event_type: "idea.created".into(),
+                    attempt: 2,
+                    status_code: Some(200),
+                    error: None,
+                    succeeded: true,
+                    timestamp: now,
+                }
+            ]
+        );
+
+        let content: Vec<WebhookDeliveryV3> = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/webhook/00000000000000000000000000000002/deliveries" => OK with content | state = state);
+        assert_eq!(content.len(), 2);
+        assert_eq!(content[0].attempt, 2);
+        assert!(content[0].succeeded);
+        assert_eq!(content[1].status_code, Some(500));
+        assert_eq!(content[1].event_id, "10");
+    }
+
+    #[actix_rt::test]
+    async fn get_webhook_deliveries_v3_other_collection() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreWebhook {
+                    collection_id: 7,
+                    id: 2,
+                    url: "https://example.com/hooks/rex".into(),
+                    secret: "secret".into(),
+                    events: vec![],
+                    enabled: true,
+                    failures: 0,
+                    principal_id: 1,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        test_request!(GET "/api/v3/collection/00000000000000000000000000000001/webhook/00000000000000000000000000000002/deliveries" => NOT_FOUND | state = state);
+    }
+}
@@ -0,0 +1,95 @@
+use super::{APIError, AuthToken, CollectionFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix_web::{get, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "webhooks",
+    summary = "List the webhooks for a collection",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    responses(
+        (status = 200, description = "The webhooks for the collection.", body = [WebhookV3])
+    ),
+    security(("oidc" = ["Collections.Read"]))
+)]
+#[instrument(err, skip(state, token), fields(otel.kind = "internal"))]
+#[get("/api/v3/collection/{collection}/webhooks")]
+async fn get_webhooks_v3(
+    (info, state, token): (
+        web::Path<CollectionFilter>,
+        web::Data<GlobalState>,
+        AuthToken,
+    ),
+) -> Result<web::Json<Vec<WebhookV3>>, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Read");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::WebhookManage).await?;
+
+    state
+        .store
+        .send(GetWebhooks { collection_id: cid }.trace())
+        .await?
+        .map(|webhooks| web::Json(webhooks.into_iter().map(|w| w.into()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn get_webhooks_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreWebhook {
+                    collection_id: 1,
+                    id: 2,
+                    url: "https://example.com/hooks/rex".into(),
+                    secret: "secret".into(),
+                    events: vec!["idea.created".into()],
+                    enabled: true,
+                    failures: 0,
+                    principal_id: 0,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        let content: Vec<WebhookV3> = test_request!(GET "/api/v3/collection/00000000000000000000000000000001/webhooks" => OK with content | state = state);
+        assert_eq!(content.len(), 1);
+        assert_eq!(
+            content[0].id,
+            Some("00000000000000000000000000000002".into())
+        );
+        assert_eq!(content[0].url, "https://example.com/hooks/rex");
+        assert_eq!(content[0].secret, None);
+    }
+
+    #[actix_rt::test]
+    async fn get_webhooks_v3_contributor() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Contributor,
+            }]
+        );
+
+        test_request!(GET "/api/v3/collection/00000000000000000000000000000001/webhooks" => FORBIDDEN | state = state);
+    }
+}
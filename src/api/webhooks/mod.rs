@@ -0,0 +1,46 @@
+mod get_webhook;
+mod get_webhook_deliveries;
+mod get_webhooks;
+mod new_webhook;
+mod remove_webhook;
+mod store_webhook;
+
+use super::{APIError, AuthToken, authorize};
+use crate::webhooks::WebhookConfig;
+use actix_web::web;
+use utoipa::OpenApi;
+
+/// The maximum number of recent deliveries which are returned for a webhook.
+const MAX_DELIVERIES: usize = 50;
+
+pub fn configure(cfg: &mut web::ServiceConfig, config: &WebhookConfig) {
+    cfg.app_data(web::Data::new(config.clone()))
+        .service(get_webhooks::get_webhooks_v3)
+        .service(new_webhook::new_webhook_v3)
+        .service(get_webhook::get_webhook_v3)
+        .service(store_webhook::store_webhook_v3)
+        .service(remove_webhook::remove_webhook_v3)
+        .service(get_webhook_deliveries::get_webhook_deliveries_v3);
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_webhooks::get_webhooks_v3,
+    new_webhook::new_webhook_v3,
+    get_webhook::get_webhook_v3,
+    store_webhook::store_webhook_v3,
+    remove_webhook::remove_webhook_v3,
+    get_webhook_deliveries::get_webhook_deliveries_v3,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionFilter {
+    collection: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CollectionWebhookFilter {
+    collection: String,
+    webhook: String,
+}
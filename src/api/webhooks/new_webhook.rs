@@ -0,0 +1,158 @@
+use super::{APIError, AuthToken, CollectionFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt, webhooks::WebhookConfig};
+use actix_web::{post, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "webhooks",
+    summary = "Create a webhook for a collection",
+    description = "Registers a URL which will receive a signed `POST` request whenever one of the selected events occurs in the collection.",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection.")
+    ),
+    request_body = WebhookV3,
+    responses(
+        (status = 201, description = "The webhook which was created.", body = WebhookV3),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
+#[instrument(err, skip(state, config, token, webhook), fields(otel.kind = "internal"))]
+#[post("/api/v3/collection/{collection}/webhooks")]
+async fn new_webhook_v3(
+    (info, webhook, state, config, token): (
+        web::Path<CollectionFilter>,
+        web::Json<WebhookV3>,
+        web::Data<GlobalState>,
+        web::Data<WebhookConfig>,
+        AuthToken,
+    ),
+) -> Result<WebhookV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    webhook.validate()?;
+    validate_webhook_target(&webhook.url, config.allow_private_addresses).await?;
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(&state, cid, uid, Permission::WebhookManage).await?;
+
+    let webhook = webhook.into_inner();
+    state
+        .store
+        .send(
+            StoreWebhook {
+                collection_id: cid,
+                id: new_id(),
+                url: webhook.url,
+                secret: webhook.secret.unwrap_or_default(),
+                events: webhook.events,
+                enabled: webhook.enabled.unwrap_or(true),
+                failures: 0,
+                principal_id: uid,
+                created_at: chrono::Utc::now(),
+            }
+            .trace(),
+        )
+        .await?
+        .map(|webhook| webhook.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn new_webhook_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        let content: WebhookV3 = test_request!(POST "/api/v3/collection/00000000000000000000000000000001/webhooks", serde_json::json!({
+            "url": "https://example.com/hooks/rex",
+            "secret": "secret",
+            "events": ["idea.created", "idea.completed"]
+        }) => CREATED with content | state = state);
+
+        assert_eq!(
+            content.collection_id,
+            Some("00000000000000000000000000000001".into())
+        );
+        assert_eq!(content.events, vec!["idea.created", "idea.completed"]);
+        assert_eq!(content.enabled, Some(true));
+        assert_eq!(content.failures, Some(0));
+        assert_eq!(content.secret, None);
+
+        let webhooks = state
+            .store
+            .send(GetWebhooks { collection_id: 1 })
+            .await
+            .expect("the actor should have run")
+            .expect("the webhooks should be listed");
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0].secret, "secret");
+    }
+
+    #[actix_rt::test]
+    async fn new_webhook_v3_invalid() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/webhooks", serde_json::json!({
+            "url": "not a url",
+            "events": []
+        }) => UNPROCESSABLE_ENTITY | state = state);
+    }
+
+    #[actix_rt::test]
+    async fn new_webhook_v3_private_address() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/webhooks", serde_json::json!({
+            "url": "http://127.0.0.1:8080/hooks/rex",
+            "secret": "secret"
+        }) => UNPROCESSABLE_ENTITY | state = state);
+    }
+
+    #[actix_rt::test]
+    async fn new_webhook_v3_contributor() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Contributor,
+            }]
+        );
+
+        test_request!(POST "/api/v3/collection/00000000000000000000000000000001/webhooks", serde_json::json!({
+            "url": "https://example.com/hooks/rex",
+            "secret": "secret"
+        }) => FORBIDDEN | state = state);
+    }
+}
@@ -0,0 +1,154 @@
+use super::{APIError, AuthToken, CollectionWebhookFilter, authorize};
+use crate::{models::*, telemetry::TraceMessageExt, webhooks::WebhookConfig};
+use actix_web::{put, web};
+use tracing::instrument;
+
+#[utoipa::path(
+    tag = "webhooks",
+    summary = "Update a webhook",
+    description = "Updates the URL, secret, events or status of a webhook. The existing secret is kept if one is not provided, and re-enabling a webhook which was disabled after repeated failures resets its failure count.",
+    params(
+        ("collection" = String, Path, description = "The ID of the collection."),
+        ("webhook" = String, Path, description = "The ID of the webhook.")
+    ),
+    request_body = WebhookV3,
+    responses(
+        (status = 200, description = "The webhook which was stored.", body = WebhookV3),
+        (status = 404, description = "The requested resource could not be found.", body = APIError),
+        (status = 422, description = "The request body failed validation.", body = APIError)
+    ),
+    security(("oidc" = ["Collections.Write"]))
+)]
+#[instrument(err, skip(state, config, token, webhook), fields(otel.kind = "internal"))]
+#[put("/api/v3/collection/{collection}/webhook/{webhook}")]
+async fn store_webhook_v3(
+    (info, webhook, state, config, token): (
+        web::Path<CollectionWebhookFilter>,
+        web::Json<WebhookV3>,
+        web::Data<GlobalState>,
+        web::Data<WebhookConfig>,
+        AuthToken,
+    ),
+) -> Result<WebhookV3, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    let cid = parse_uuid!(info.collection, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let id = parse_uuid!(info.webhook, "webhook ID");
+
+    authorize(&state, cid, uid, Permission::WebhookManage).await?;
+
+    let existing = state
+        .store
+        .send(
+            GetWebhook {
+                collection_id: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    let mut webhook = webhook.into_inner();
+    webhook.secret.get_or_insert(existing.secret);
+    webhook.validate()?;
+    validate_webhook_target(&webhook.url, config.allow_private_addresses).await?;
+
+    let enabled = webhook.enabled.unwrap_or(existing.enabled);
+
+    state
+        .store
+        .send(
+            StoreWebhook {
+                collection_id: cid,
+                id,
+                url: webhook.url,
+                secret: webhook.secret.unwrap_or_default(),
+                events: webhook.events,
+                enabled,
+                failures: if enabled && !existing.enabled {
+                    0
+                } else {
+                    existing.failures
+                },
+                principal_id: existing.created_by,
+                created_at: existing.created_at,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|webhook| webhook.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::test::*;
+    use crate::models::*;
+
+    #[actix_rt::test]
+    async fn store_webhook_v3() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreWebhook {
+                    collection_id: 1,
+                    id: 2,
+                    url: "https://example.com/hooks/rex".into(),
+                    secret: "secret".into(),
+                    events: vec![],
+                    enabled: false,
+                    failures: 5,
+                    principal_id: 0,
+                    created_at: chrono::Utc::now(),
+                }
+            ]
+        );
+
+        let content: WebhookV3 = test_request!(PUT "/api/v3/collection/00000000000000000000000000000001/webhook/00000000000000000000000000000002", serde_json::json!({
+            "url": "https://example.com/hooks/rex-v2",
+            "events": ["member.added"],
+            "enabled": true
+        }) => OK with content | state = state);
+
+        assert_eq!(content.url, "https://example.com/hooks/rex-v2");
+        assert_eq!(content.events, vec!["member.added"]);
+        assert_eq!(content.enabled, Some(true));
+        assert_eq!(content.failures, Some(0));
+
+        let webhook = state
+            .store
+            .send(GetWebhook {
+                collection_id: 1,
+                id: 2,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the webhook should exist");
+        assert_eq!(webhook.secret, "secret");
+    }
+
+    #[actix_rt::test]
+    async fn store_webhook_v3_not_found() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreRoleAssignment {
+                collection_id: 1,
+                principal_id: 0,
+                role: Role::Owner,
+            }]
+        );
+
+        test_request!(PUT "/api/v3/collection/00000000000000000000000000000001/webhook/00000000000000000000000000000002", serde_json::json!({
+            "url": "https://example.com/hooks/rex",
+            "secret": "secret"
+        }) => NOT_FOUND | state = state);
+    }
+}
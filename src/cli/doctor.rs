@@ -0,0 +1,116 @@
+use crate::{config::Config, models::*, telemetry::TraceMessageExt};
+use actix_rt::time::timeout;
+use std::time::Duration;
+
+/// How long each dependency has to respond before the check fails.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The outcome of a single check, where `None` indicates that it was skipped.
+type CheckResult = Option<Result<String, String>>;
+
+/// Checks that the store, OpenID Connect issuer and UI backend are reachable with the
+/// provided configuration, printing the result of each check and returning whether
+/// they all passed.
+pub async fn run(config: &Config, state: &GlobalState) -> bool {
+    let checks = [
+        ("configuration", Some(Ok("loaded and valid".to_string()))),
+        ("store", check_store(state).await),
+        ("auth", check_auth(config).await),
+        ("ui", check_ui(config).await),
+    ];
+
+    let mut ok = true;
+    for (name, result) in checks {
+        match result {
+            Some(Ok(detail)) => println!("[ok]      {name}: {detail}"),
+            Some(Err(detail)) => {
+                ok = false;
+                println!("[failed]  {name}: {detail}");
+            }
+            None => println!("[skipped] {name}: not configured"),
+        }
+    }
+
+    ok
+}
+
+async fn check_store(state: &GlobalState) -> CheckResult {
+    Some(
+        match timeout(CHECK_TIMEOUT, state.store.send(GetHealth {}.trace())).await {
+            Ok(Ok(Ok(health))) if health.ok => Ok("reachable".into()),
+            Ok(Ok(Ok(_))) => Err("the store could not be reached".into()),
+            Ok(Ok(Err(err))) => Err(err.to_string()),
+            Ok(Err(err)) => Err(err.to_string()),
+            Err(_) => Err("the store did not respond in time".into()),
+        },
+    )
+}
+
+async fn check_auth(config: &Config) -> CheckResult {
+    let url = config.auth.discovery_url();
+    let response = reqwest::Client::new()
+        .get(&url)
+        .timeout(CHECK_TIMEOUT)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status());
+
+    let body = match response {
+        Ok(res) => res.text().await,
+        Err(err) => Err(err),
+    };
+
+    let document: serde_json::Value = match body.map(|body| serde_json::from_str(&body)) {
+        Ok(Ok(document)) => document,
+        Ok(Err(err)) => return Some(Err(format!("{url} is not a discovery document: {err}"))),
+        Err(err) => return Some(Err(format!("{url} could not be retrieved: {err}"))),
+    };
+
+    Some(match document["jwks_uri"].as_str() {
+        Some(_) => Ok(format!("discovered {url}")),
+        None => Err(format!("{url} does not include a jwks_uri")),
+    })
+}
+
+async fn check_ui(config: &Config) -> CheckResult {
+    let uri = config.ui.backend_uri.as_ref()?;
+
+    Some(
+        match reqwest::Client::new()
+            .get(uri)
+            .timeout(CHECK_TIMEOUT)
+            .send()
+            .await
+        {
+            Ok(res) if !res.status().is_server_error() => Ok(format!("{uri} is reachable")),
+            Ok(res) => Err(format!("{uri} responded with {}", res.status())),
+            Err(err) => Err(format!("{uri} could not be reached: {err}")),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+
+    #[actix_rt::test]
+    async fn store_check() {
+        test_state!(state = []);
+
+        assert_eq!(check_store(&state).await, Some(Ok("reachable".into())));
+    }
+
+    #[actix_rt::test]
+    async fn ui_check() {
+        assert_eq!(check_ui(&Config::default()).await, None);
+
+        let config = Config {
+            ui: crate::ui::UiConfig {
+                backend_uri: Some("http://127.0.0.1:1".into()),
+            },
+            ..Default::default()
+        };
+        assert!(matches!(check_ui(&config).await, Some(Err(_))));
+    }
+}
@@ -0,0 +1,131 @@
+use super::CliError;
+use crate::{models::*, telemetry::TraceMessageExt};
+use chrono::{DateTime, Utc};
+
+/// A collection, along with its ideas and role assignments, in the same representation
+/// which is used by the v3 API.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionExport {
+    pub collection: CollectionV3,
+    pub ideas: Vec<IdeaExport>,
+    #[serde(rename = "roleAssignments")]
+    pub role_assignments: Vec<RoleAssignmentV3>,
+}
+
+/// An idea in the v3 representation, along with the times at which it was created and
+/// completed so that they are preserved when it is imported.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdeaExport {
+    #[serde(flatten)]
+    pub idea: IdeaV3,
+    #[serde(rename = "createdAt", default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(
+        rename = "completedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<Idea> for IdeaExport {
+    fn from(idea: Idea) -> Self {
+        Self {
+            created_at: idea.created_at,
+            completed_at: idea.completed_at,
+            idea: idea.into(),
+        }
+    }
+}
+
+pub async fn export(
+    state: &GlobalState,
+    collection_id: u128,
+) -> Result<CollectionExport, CliError> {
+    let collection = state
+        .store
+        .send(GetAllCollections {}.trace())
+        .await??
+        .into_iter()
+        .find(|c| c.collection_id == collection_id)
+        .ok_or_else(|| {
+            CliError(format!(
+                "The collection {collection_id:0>32x} could not be found."
+            ))
+        })?;
+
+    let ideas = state
+        .store
+        .send(
+            GetIdeas {
+                collection: collection_id,
+                tag: None,
+                is_completed: None,
+            }
+            .trace(),
+        )
+        .await?
+        .or_else(|err| {
+            if err.code == 404 {
+                Ok(vec![])
+            } else {
+                Err(err)
+            }
+        })?;
+
+    let role_assignments = state
+        .store
+        .send(GetRoleAssignments { collection_id }.trace())
+        .await??;
+
+    Ok(CollectionExport {
+        collection: collection.into(),
+        ideas: ideas.into_iter().map(|idea| idea.into()).collect(),
+        role_assignments: role_assignments.into_iter().map(|ra| ra.into()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+
+    #[actix_rt::test]
+    async fn export_collection() {
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 2,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 2,
+                    role: Role::Owner,
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 3,
+                    name: "Go hiking".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let export = export(&state, 1)
+            .await
+            .expect("the collection should be exported");
+        assert_eq!(export.collection.name, "Test Collection");
+        assert_eq!(export.ideas.len(), 1);
+        assert_eq!(export.ideas[0].idea.name, "Go hiking");
+        assert!(export.ideas[0].created_at.is_some());
+        assert_eq!(export.role_assignments.len(), 1);
+        assert_eq!(export.role_assignments[0].role, "Owner");
+
+        let err = super::export(&state, 4)
+            .await
+            .expect_err("the collection does not exist");
+        assert!(err.0.contains("could not be found"), "{err}");
+    }
+}
@@ -0,0 +1,209 @@
+use super::{CliError, export::CollectionExport};
+use crate::{models::*, telemetry::TraceMessageExt};
+
+/// Stores an exported collection, adding or updating each of its ideas and role assignments
+/// and listing it for every member who holds a role on it, then returns a summary of what
+/// was imported. Ideas and role assignments which are not part of the export are left in
+/// place, and ideas keep the times at which they were created and completed.
+pub async fn import(state: &GlobalState, export: CollectionExport) -> Result<String, CliError> {
+    let collection: Collection = export.collection.into();
+    if collection.user_id == 0 {
+        return Err(CliError(
+            "The collection being imported must include its userId.".into(),
+        ));
+    }
+
+    state
+        .store
+        .send(
+            StoreCollection {
+                collection_id: collection.collection_id,
+                principal_id: collection.user_id,
+                name: collection.name.clone(),
+            }
+            .trace(),
+        )
+        .await??;
+
+    // Exports made before timestamps were included are treated as if their ideas were
+    // created now, as they would be if they were stored through the API.
+    let now = chrono::Utc::now();
+    let ideas = export.ideas.len();
+    for exported in export.ideas {
+        let mut idea: Idea = exported.idea.into();
+        idea.collection_id = collection.collection_id;
+        idea.created_at = exported.created_at.or(Some(now));
+        idea.completed_at = if idea.completed {
+            exported.completed_at.or(Some(now))
+        } else {
+            None
+        };
+
+        state.store.send(RestoreIdea { idea }.trace()).await??;
+    }
+
+    let role_assignments = export.role_assignments.len();
+    for role_assignment in export.role_assignments {
+        let role_assignment: RoleAssignment = role_assignment.into();
+        state
+            .store
+            .send(
+                StoreRoleAssignment {
+                    collection_id: collection.collection_id,
+                    principal_id: role_assignment.user_id,
+                    role: role_assignment.role,
+                }
+                .trace(),
+            )
+            .await??;
+
+        // Members keep the name they have given the collection if it is still listed for
+        // them, and otherwise see it under the exported name.
+        match state
+            .store
+            .send(
+                GetCollection {
+                    id: collection.collection_id,
+                    principal_id: role_assignment.user_id,
+                }
+                .trace(),
+            )
+            .await?
+        {
+            Ok(_) => {}
+            Err(err) if err.code == 404 => {
+                state
+                    .store
+                    .send(
+                        StoreCollection {
+                            collection_id: collection.collection_id,
+                            principal_id: role_assignment.user_id,
+                            name: collection.name.clone(),
+                        }
+                        .trace(),
+                    )
+                    .await??;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(format!(
+        "Imported '{}' ({:0>32x}) with {} ideas and {} role assignments.",
+        collection.name, collection.collection_id, ideas, role_assignments
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::export::export;
+    use crate::test_state;
+
+    #[actix_rt::test]
+    async fn round_trip() {
+        test_state!(
+            source = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 2,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 2,
+                    role: Role::Owner,
+                },
+                StoreRoleAssignment {
+                    collection_id: 1,
+                    principal_id: 5,
+                    role: Role::Viewer,
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 3,
+                    name: "Go hiking".into(),
+                    tags: hashset!("outdoors"),
+                    completed: true,
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let exported = export(&source, 1)
+            .await
+            .expect("the collection should be exported");
+        let json = serde_json::to_string(&exported).expect("the export should serialize");
+
+        test_state!(target = []);
+        let summary = import(
+            &target,
+            serde_json::from_str(&json).expect("the export should parse"),
+        )
+        .await
+        .expect("the collection should be imported");
+        assert_eq!(
+            summary,
+            "Imported 'Test Collection' (00000000000000000000000000000001) with 1 ideas and 2 role assignments."
+        );
+
+        let idea = target
+            .store
+            .send(GetIdea {
+                collection: 1,
+                id: 3,
+            })
+            .await
+            .expect("the actor should respond")
+            .expect("the idea should have been imported");
+        assert_eq!(idea.name, "Go hiking");
+        assert!(idea.completed);
+        assert!(idea.tags.contains("outdoors"));
+
+        let original = source
+            .store
+            .send(GetIdea {
+                collection: 1,
+                id: 3,
+            })
+            .await
+            .expect("the actor should respond")
+            .expect("the idea should still exist");
+        assert!(original.created_at.is_some());
+        assert_eq!(idea.created_at, original.created_at);
+        assert_eq!(idea.completed_at, original.completed_at);
+
+        let collection = target
+            .store
+            .send(GetCollection {
+                id: 1,
+                principal_id: 2,
+            })
+            .await
+            .expect("the actor should respond")
+            .expect("the collection should have been imported");
+        assert_eq!(collection.name, "Test Collection");
+
+        let role = target
+            .store
+            .send(GetRoleAssignment {
+                collection_id: 1,
+                principal_id: 2,
+            })
+            .await
+            .expect("the actor should respond")
+            .expect("the role assignment should have been imported");
+        assert_eq!(role.role, Role::Owner);
+
+        let listing = target
+            .store
+            .send(GetCollection {
+                id: 1,
+                principal_id: 5,
+            })
+            .await
+            .expect("the actor should respond")
+            .expect("the collection should be listed for every member");
+        assert_eq!(listing.name, "Test Collection");
+    }
+}
@@ -0,0 +1,171 @@
+use super::CliError;
+use crate::{
+    config::Config,
+    models::{EventBus, GlobalState},
+    store::{
+        BACKEND, MemoryStore,
+        migration::{MigrationOptions, MigrationReport, MigrationStore, Snapshot, migrate},
+    },
+};
+use actix::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Either end of a migration.
+#[derive(Debug, PartialEq)]
+pub enum Endpoint {
+    /// The store which this build of Rex is configured to use.
+    Store,
+    /// A JSON file containing the contents of a store.
+    Snapshot(PathBuf),
+}
+
+impl Endpoint {
+    pub fn parse(value: &str) -> Result<Self, CliError> {
+        if let Some(path) = value.strip_prefix("snapshot:") {
+            Ok(Self::Snapshot(path.into()))
+        } else if value == BACKEND {
+            Ok(Self::Store)
+        } else {
+            Err(CliError(format!(
+                "'{value}' is not a store which this build of Rex can migrate, use '{BACKEND}' or 'snapshot:<file>'"
+            )))
+        }
+    }
+}
+
+pub async fn run(
+    config: &Config,
+    from: Endpoint,
+    to: Endpoint,
+    options: MigrationOptions,
+) -> Result<MigrationReport, CliError> {
+    let report = match (from, to) {
+        (Endpoint::Store, Endpoint::Store) => {
+            return Err(CliError(
+                "The source and destination of a migration must be different.".into(),
+            ));
+        }
+        (Endpoint::Store, Endpoint::Snapshot(path)) => {
+            let state = GlobalState::new(&config.store);
+            to_snapshot(&state.store, &path, options).await?
+        }
+        (Endpoint::Snapshot(path), Endpoint::Store) => {
+            let state = GlobalState::new(&config.store);
+            let source = load_snapshot(&path).await?;
+            migrate(&source, &state.store, &options).await?
+        }
+        (Endpoint::Snapshot(from), Endpoint::Snapshot(to)) => {
+            let source = load_snapshot(&from).await?;
+            to_snapshot(&source, &to, options).await?
+        }
+    };
+
+    if !report.verified() {
+        return Err(CliError(format!(
+            "{report}\nThe destination does not match the source, re-run the migration to copy the records which differ again."
+        )));
+    }
+
+    Ok(report)
+}
+
+/// Loads a snapshot file into a new in-memory store.
+async fn load_snapshot(path: &Path) -> Result<Addr<MemoryStore>, CliError> {
+    let json = std::fs::read_to_string(path)?;
+    let snapshot: Snapshot = serde_json::from_str(&json).map_err(|err| {
+        CliError(format!(
+            "The snapshot {} is not valid: {err}",
+            path.display()
+        ))
+    })?;
+
+    let store = MemoryStore::new(EventBus::new(), &Default::default()).start();
+    snapshot.write(&store).await?;
+    Ok(store)
+}
+
+/// Migrates into a new in-memory store and then writes its contents to a snapshot file.
+/// The snapshot is only written once it is complete, so there is nothing to resume.
+async fn to_snapshot<S: MigrationStore>(
+    source: &Addr<S>,
+    path: &Path,
+    options: MigrationOptions,
+) -> Result<MigrationReport, CliError> {
+    let destination = MemoryStore::new(EventBus::new(), &Default::default()).start();
+    let options = MigrationOptions {
+        checkpoint: None,
+        ..options
+    };
+
+    let report = migrate(source, &destination, &options).await?;
+    if !options.dry_run && report.verified() {
+        let snapshot = Snapshot::read(&destination).await?;
+        let json = serde_json::to_string_pretty(&snapshot)
+            .map_err(|err| CliError(format!("The snapshot could not be serialized: {err}")))?;
+        std::fs::write(path, json)?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+    use crate::test_state;
+
+    #[test]
+    fn parse_endpoints() {
+        assert_eq!(Endpoint::parse("memory"), Ok(Endpoint::Store));
+        assert_eq!(
+            Endpoint::parse("snapshot:backup.json"),
+            Ok(Endpoint::Snapshot("backup.json".into()))
+        );
+        assert!(Endpoint::parse("postgres").is_err());
+    }
+
+    #[actix_rt::test]
+    async fn snapshot_to_snapshot() {
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 1,
+                    principal_id: 2,
+                    name: "Test Collection".into(),
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 3,
+                    name: "Go hiking".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+
+        let dir = std::env::temp_dir();
+        let first = dir.join(format!("rex-snapshot-a-{}.json", std::process::id()));
+        let second = dir.join(format!("rex-snapshot-b-{}.json", std::process::id()));
+
+        to_snapshot(&state.store, &first, MigrationOptions::default())
+            .await
+            .expect("the store should be written to a snapshot");
+
+        let report = run(
+            &Config::default(),
+            Endpoint::Snapshot(first.clone()),
+            Endpoint::Snapshot(second.clone()),
+            MigrationOptions::default(),
+        )
+        .await
+        .expect("the snapshot should be migrated");
+        assert_eq!(report.ideas.copied, 1);
+
+        let snapshot: Snapshot =
+            serde_json::from_str(&std::fs::read_to_string(&second).unwrap()).unwrap();
+        assert_eq!(snapshot.collections[0].name, "Test Collection");
+        assert_eq!(snapshot.ideas[0].name, "Go hiking");
+
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+}
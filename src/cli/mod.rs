@@ -0,0 +1,399 @@
+//! The operational commands which the `rex` binary provides alongside `serve`, allowing
+//! an instance to be managed without crafting API calls.
+
+mod doctor;
+mod export;
+mod import;
+mod migrate;
+mod users;
+
+use crate::{config::Config, models::GlobalState, telemetry::TraceMessageExt};
+use std::{collections::BTreeMap, fmt::Display, path::PathBuf};
+
+pub const USAGE: &str = "Usage: rex [--config <file>] <command>
+
+Commands:
+  serve                                    Serve the HTTP and gRPC APIs (the default)
+  export --collection <id> [--output <file>]
+                                           Export a collection, its ideas and role assignments as JSON
+  import [--input <file>]                  Import a collection which was previously exported
+  migrate --from <store> --to <store> [--dry-run] [--checkpoint <file>]
+                                           Copy every user, collection, role assignment and idea
+                                           between stores, where a store is the configured backend
+                                           or snapshot:<file>
+  upgrade-schema [--dry-run]               Rewrite stored entities which use an older schema
+  users list                               List the users who have signed in to Rex
+  doctor                                   Check the configuration, store and OpenID Connect issuer
+  help                                     Show this message
+
+Options:
+  --config <file>  The configuration file to load, instead of $REX_CONFIG or rex.toml";
+
+/// Describes why a command could not be completed.
+#[derive(Debug, PartialEq)]
+pub struct CliError(pub String);
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<crate::api::APIError> for CliError {
+    fn from(err: crate::api::APIError) -> Self {
+        CliError(err.to_string())
+    }
+}
+
+impl From<actix::MailboxError> for CliError {
+    fn from(err: actix::MailboxError) -> Self {
+        CliError(format!("The store did not respond: {err}"))
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(err: std::io::Error) -> Self {
+        CliError(err.to_string())
+    }
+}
+
+/// The options which are switched on by their presence, rather than taking a value.
+const FLAGS: [&str; 1] = ["dry-run"];
+
+/// The file in which the progress of a migration is recorded when `--checkpoint` is not given.
+const DEFAULT_CHECKPOINT: &str = "rex-migration.json";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Serve,
+    Export {
+        collection: u128,
+        output: Option<PathBuf>,
+    },
+    Import {
+        input: Option<PathBuf>,
+    },
+    Migrate {
+        from: migrate::Endpoint,
+        to: migrate::Endpoint,
+        dry_run: bool,
+        checkpoint: PathBuf,
+    },
+    UpgradeSchema {
+        dry_run: bool,
+    },
+    UsersList,
+    Doctor,
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    /// The configuration file which was named with `--config`.
+    pub config: Option<PathBuf>,
+    pub command: Command,
+}
+
+impl Cli {
+    /// Parses the command line arguments, excluding the name of the binary.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, CliError> {
+        let mut words = Vec::new();
+        let mut options = BTreeMap::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                words.push("help".to_string());
+            } else if let Some(name) = arg.strip_prefix("--") {
+                let (name, value) = match name.split_once('=') {
+                    Some((name, value)) => (name.to_string(), value.to_string()),
+                    None if FLAGS.contains(&name) => (name.to_string(), "true".to_string()),
+                    None => (
+                        name.to_string(),
+                        args.next()
+                            .ok_or_else(|| CliError(format!("--{name} requires a value")))?,
+                    ),
+                };
+
+                options.insert(name, value);
+            } else {
+                words.push(arg);
+            }
+        }
+
+        let config = options.remove("config").map(PathBuf::from);
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let (command, allowed): (Command, &[&str]) = match words.as_slice() {
+            [] | ["serve"] => (Command::Serve, &[]),
+            ["export"] => (
+                Command::Export {
+                    collection: options
+                        .get("collection")
+                        .ok_or_else(|| CliError("export requires --collection <id>".into()))
+                        .and_then(|id| parse_id(id))?,
+                    output: options.get("output").map(PathBuf::from),
+                },
+                &["collection", "output"],
+            ),
+            ["import"] => (
+                Command::Import {
+                    input: options.get("input").map(PathBuf::from),
+                },
+                &["input"],
+            ),
+            ["migrate"] => (
+                Command::Migrate {
+                    from: options
+                        .get("from")
+                        .ok_or_else(|| CliError("migrate requires --from <store>".into()))
+                        .and_then(|from| migrate::Endpoint::parse(from))?,
+                    to: options
+                        .get("to")
+                        .ok_or_else(|| CliError("migrate requires --to <store>".into()))
+                        .and_then(|to| migrate::Endpoint::parse(to))?,
+                    dry_run: options.get("dry-run").is_some_and(|v| v == "true"),
+                    checkpoint: options
+                        .get("checkpoint")
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| DEFAULT_CHECKPOINT.into()),
+                },
+                &["from", "to", "dry-run", "checkpoint"],
+            ),
+            ["upgrade-schema"] => (
+                Command::UpgradeSchema {
+                    dry_run: options.get("dry-run").is_some_and(|v| v == "true"),
+                },
+                &["dry-run"],
+            ),
+            ["users", "list"] => (Command::UsersList, &[]),
+            ["doctor"] => (Command::Doctor, &[]),
+            ["help", ..] => (Command::Help, &[]),
+            _ => return Err(CliError(format!("Unknown command '{}'", words.join(" ")))),
+        };
+
+        if let Some(name) = options
+            .keys()
+            .find(|name| !allowed.contains(&name.as_str()))
+        {
+            return Err(CliError(format!("Unknown option --{name}")));
+        }
+
+        Ok(Self { config, command })
+    }
+}
+
+/// Runs one of the operational commands against the configured store.
+pub async fn run(command: Command, config: &Config) -> Result<(), CliError> {
+    let state = GlobalState::new(&config.store);
+
+    match command {
+        Command::Export { collection, output } => {
+            let export = export::export(&state, collection).await?;
+            let json = serde_json::to_string_pretty(&export)
+                .map_err(|err| CliError(format!("The export could not be serialized: {err}")))?;
+
+            match output {
+                Some(path) => std::fs::write(path, json)?,
+                None => println!("{json}"),
+            }
+        }
+        Command::Import { input } => {
+            let json = match input {
+                Some(path) => std::fs::read_to_string(path)?,
+                None => std::io::read_to_string(std::io::stdin())?,
+            };
+
+            let export = serde_json::from_str(&json)
+                .map_err(|err| CliError(format!("The import is not valid: {err}")))?;
+
+            let summary = import::import(&state, export).await?;
+            println!("{summary}");
+        }
+        Command::Migrate {
+            from,
+            to,
+            dry_run,
+            checkpoint,
+        } => {
+            let options = crate::store::migration::MigrationOptions {
+                dry_run,
+                checkpoint: Some(checkpoint),
+            };
+
+            print!("{}", migrate::run(config, from, to, options).await?);
+        }
+        Command::UpgradeSchema { dry_run } => {
+            let tables = state
+                .store
+                .send(crate::models::UpgradeSchema { dry_run }.trace())
+                .await??;
+
+            print!("{}", upgrade_summary(&tables, dry_run));
+        }
+        Command::UsersList => print!("{}", users::list(&state).await?),
+        Command::Doctor => {
+            if !doctor::run(config, &state).await {
+                return Err(CliError("Some checks failed.".into()));
+            }
+        }
+        Command::Serve | Command::Help => {}
+    }
+
+    Ok(())
+}
+
+/// Describes the entities which were, or would be with `dry_run`, upgraded in each table.
+fn upgrade_summary(tables: &[crate::models::SchemaUpgrade], dry_run: bool) -> String {
+    if tables.iter().all(|t| t.upgraded == 0) {
+        return "Every entity is already stored with the current schema.\n".into();
+    }
+
+    let verb = if dry_run { "Would upgrade" } else { "Upgraded" };
+    tables
+        .iter()
+        .filter(|t| t.upgraded > 0)
+        .map(|t| {
+            format!(
+                "{verb} {} of the {} entities in {}.\n",
+                t.upgraded, t.scanned, t.table
+            )
+        })
+        .collect()
+}
+
+/// Parses an ID in either its hyphenated or 32 character hexadecimal form.
+fn parse_id(id: &str) -> Result<u128, CliError> {
+    u128::from_str_radix(&id.replace('-', ""), 16)
+        .map_err(|_| CliError(format!("'{id}' is not a valid ID")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, CliError> {
+        Cli::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            parse(&[]),
+            Ok(Cli {
+                config: None,
+                command: Command::Serve
+            })
+        );
+        assert_eq!(
+            parse(&["--config", "rex.toml", "serve"]),
+            Ok(Cli {
+                config: Some("rex.toml".into()),
+                command: Command::Serve
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "export",
+                "--collection",
+                "00000000-0000-0000-0000-000000000001",
+                "--output=export.json"
+            ])
+            .map(|cli| cli.command),
+            Ok(Command::Export {
+                collection: 1,
+                output: Some("export.json".into())
+            })
+        );
+        assert_eq!(
+            parse(&["import"]).map(|cli| cli.command),
+            Ok(Command::Import { input: None })
+        );
+        assert_eq!(
+            parse(&[
+                "migrate",
+                "--from",
+                "snapshot:backup.json",
+                "--to=memory",
+                "--dry-run"
+            ])
+            .map(|cli| cli.command),
+            Ok(Command::Migrate {
+                from: migrate::Endpoint::Snapshot("backup.json".into()),
+                to: migrate::Endpoint::Store,
+                dry_run: true,
+                checkpoint: "rex-migration.json".into(),
+            })
+        );
+        assert_eq!(
+            parse(&["upgrade-schema", "--dry-run"]).map(|cli| cli.command),
+            Ok(Command::UpgradeSchema { dry_run: true })
+        );
+        assert_eq!(
+            parse(&["users", "list"]).map(|cli| cli.command),
+            Ok(Command::UsersList)
+        );
+        assert_eq!(
+            parse(&["doctor"]).map(|cli| cli.command),
+            Ok(Command::Doctor)
+        );
+        assert_eq!(parse(&["--help"]).map(|cli| cli.command), Ok(Command::Help));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse(&["launch"]),
+            Err(CliError("Unknown command 'launch'".into()))
+        );
+        assert_eq!(
+            parse(&["export"]),
+            Err(CliError("export requires --collection <id>".into()))
+        );
+        assert_eq!(
+            parse(&["export", "--collection", "nope"]),
+            Err(CliError("'nope' is not a valid ID".into()))
+        );
+        assert_eq!(
+            parse(&["doctor", "--collection", "1"]),
+            Err(CliError("Unknown option --collection".into()))
+        );
+        assert_eq!(
+            parse(&["migrate", "--to", "memory"]),
+            Err(CliError("migrate requires --from <store>".into()))
+        );
+        assert_eq!(
+            parse(&["serve", "--config"]),
+            Err(CliError("--config requires a value".into()))
+        );
+    }
+
+    #[test]
+    fn summarize_upgrade() {
+        use crate::models::SchemaUpgrade;
+
+        let tables = vec![
+            SchemaUpgrade {
+                table: "ideas".into(),
+                scanned: 10,
+                upgraded: 4,
+            },
+            SchemaUpgrade {
+                table: "users".into(),
+                scanned: 2,
+                upgraded: 0,
+            },
+        ];
+
+        assert_eq!(
+            upgrade_summary(&tables, true),
+            "Would upgrade 4 of the 10 entities in ideas.\n"
+        );
+        assert_eq!(
+            upgrade_summary(&tables[1..], false),
+            "Every entity is already stored with the current schema.\n"
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use super::CliError;
+use crate::{models::*, telemetry::TraceMessageExt};
+use std::fmt::Write;
+
+/// Lists the users who have signed in to Rex as a tab separated table.
+pub async fn list(state: &GlobalState) -> Result<String, CliError> {
+    let mut users = state.store.send(GetUsers {}.trace()).await??;
+    users.sort_by(|a, b| a.first_name.cmp(&b.first_name));
+
+    let mut out = String::from("PRINCIPAL ID\tEMAIL HASH\tFIRST NAME\n");
+    for user in users {
+        let _ = writeln!(
+            out,
+            "{:0>32x}\t{:0>32x}\t{}",
+            user.principal_id, user.email_hash, user.first_name
+        );
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+
+    #[actix_rt::test]
+    async fn list_users() {
+        test_state!(
+            state = [
+                StoreUser {
+                    email_hash: 2,
+                    principal_id: 1,
+                    first_name: "Zoe".into(),
+                },
+                StoreUser {
+                    email_hash: 4,
+                    principal_id: 3,
+                    first_name: "Alex".into(),
+                }
+            ]
+        );
+
+        assert_eq!(
+            list(&state).await.expect("the users should be listed"),
+            "PRINCIPAL ID\tEMAIL HASH\tFIRST NAME\n\
+             00000000000000000000000000000003\t00000000000000000000000000000004\tAlex\n\
+             00000000000000000000000000000001\t00000000000000000000000000000002\tZoe\n"
+        );
+    }
+}
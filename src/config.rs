@@ -0,0 +1,609 @@
+//! The configuration for a Rex instance, which is loaded from an optional TOML file and
+//! then overridden by `REX_*` environment variables.
+
+use crate::{
+    api::{AuthConfig, CorsConfig, InviteLinkConfig, SlackConfig},
+    store::StoreConfig,
+    telemetry::TelemetryConfig,
+    ui::UiConfig,
+    webhooks::WebhookConfig,
+};
+use std::{collections::BTreeMap, fmt::Display, path::Path, str::FromStr};
+
+/// The file which configuration is loaded from, if it exists, when `REX_CONFIG` is not set.
+const DEFAULT_CONFIG_FILE: &str = "rex.toml";
+
+/// The complete configuration for a Rex instance.
+///
+/// Every setting may be provided in a TOML file (`rex.toml` by default, or the file named
+/// by the `REX_CONFIG` environment variable), and then overridden by an environment
+/// variable named after its section and key, such as `REX_SERVER_PORT` for `server.port`.
+/// The environment variables which Rex used before it supported configuration files are
+/// still honoured, but the `REX_*` variables take precedence over them.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub store: StoreConfig,
+    pub auth: AuthConfig,
+    pub cors: CorsConfig,
+    pub slack: SlackConfig,
+    pub invite_links: InviteLinkConfig,
+    pub webhooks: WebhookConfig,
+    pub telemetry: TelemetryConfig,
+    pub ui: UiConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// The port on which the HTTP API and UI are served.
+    pub port: u16,
+    /// The port on which the gRPC API is served.
+    pub grpc_port: u16,
+    /// The number of seconds which in-flight requests are given to complete once the
+    /// server has been asked to shut down.
+    pub shutdown_timeout: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: 8000,
+            grpc_port: 8001,
+            shutdown_timeout: 30,
+        }
+    }
+}
+
+/// Describes why the configuration could not be loaded.
+#[derive(Debug, PartialEq)]
+pub struct ConfigError(String);
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid configuration: {}", self.0)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Loads the configuration from the provided file, falling back to the file named by
+    /// `REX_CONFIG` (or `rex.toml`) when no file is provided.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        Self::load_from(path, |name| std::env::var(name).ok())
+    }
+
+    fn load_from<F: Fn(&str) -> Option<String>>(
+        path: Option<&Path>,
+        var: F,
+    ) -> Result<Self, ConfigError> {
+        let path = path.map(Path::to_path_buf).or_else(|| {
+            var("REX_CONFIG")
+                .filter(|path| !path.is_empty())
+                .map(Into::into)
+        });
+
+        let mut config = match path {
+            Some(path) => Self::from_file(&path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Self::from_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => Self::default(),
+        };
+
+        config.apply_env(&var)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|err| {
+            ConfigError(format!(
+                "the configuration file '{}' could not be read: {}",
+                path.display(),
+                err
+            ))
+        })?;
+
+        Self::parse(&content)
+            .map_err(|ConfigError(err)| ConfigError(format!("{} (in '{}')", err, path.display())))
+    }
+
+    fn parse(content: &str) -> Result<Self, ConfigError> {
+        toml::from_str(content).map_err(|err| ConfigError(err.message().to_string()))
+    }
+
+    fn apply_env<F: Fn(&str) -> Option<String>>(&mut self, var: &F) -> Result<(), ConfigError> {
+        override_with(
+            var,
+            &["FUNCTIONS_CUSTOMHANDLER_PORT", "REX_SERVER_PORT"],
+            &mut self.server.port,
+        )?;
+        override_with(
+            var,
+            &["GRPC_PORT", "REX_SERVER_GRPC_PORT"],
+            &mut self.server.grpc_port,
+        )?;
+        override_with(
+            var,
+            &["REX_SERVER_SHUTDOWN_TIMEOUT"],
+            &mut self.server.shutdown_timeout,
+        )?;
+
+        override_optional(
+            var,
+            &[
+                "TABLE_STORAGE_CONNECTION_STRING",
+                "REX_STORE_TABLE_STORAGE_CONNECTION_STRING",
+            ],
+            &mut self.store.table_storage_connection_string,
+        );
+        override_with(
+            var,
+            &["REX_STORE_UPGRADE_SCHEMA_ON_START"],
+            &mut self.store.upgrade_schema_on_start,
+        )?;
+
+        override_with(var, &["REX_AUTH_ISSUER"], &mut self.auth.issuer)?;
+        override_with(var, &["REX_AUTH_CLIENT_ID"], &mut self.auth.client_id)?;
+        override_with(
+            var,
+            &["REX_AUTH_TRUST_ISSUER_EMAILS"],
+            &mut self.auth.trust_issuer_emails,
+        )?;
+
+        self.cors = self
+            .cors
+            .clone()
+            .with_vars(|name| var(&format!("REX_{name}")).or_else(|| var(name)))
+            .map_err(ConfigError)?;
+
+        override_optional(
+            var,
+            &["SLACK_SIGNING_SECRET", "REX_SLACK_SIGNING_SECRET"],
+            &mut self.slack.signing_secret,
+        );
+        override_optional(
+            var,
+            &["INVITE_LINK_SECRET", "REX_INVITE_LINKS_SECRET"],
+            &mut self.invite_links.secret,
+        );
+
+        override_with(
+            var,
+            &["REX_WEBHOOKS_MAX_ATTEMPTS"],
+            &mut self.webhooks.max_attempts,
+        )?;
+        override_with(
+            var,
+            &["REX_WEBHOOKS_INITIAL_BACKOFF"],
+            &mut self.webhooks.initial_backoff,
+        )?;
+        override_with(var, &["REX_WEBHOOKS_TIMEOUT"], &mut self.webhooks.timeout)?;
+        override_with(
+            var,
+            &["REX_WEBHOOKS_DISABLE_AFTER"],
+            &mut self.webhooks.disable_after,
+        )?;
+        override_with(
+            var,
+            &["REX_WEBHOOKS_ALLOW_PRIVATE_ADDRESSES"],
+            &mut self.webhooks.allow_private_addresses,
+        )?;
+
+        override_with(var, &["REX_TELEMETRY_EXPORT"], &mut self.telemetry.export)?;
+        override_optional(
+            var,
+            &["REX_TELEMETRY_SENTRY_DSN"],
+            &mut self.telemetry.sentry_dsn,
+        );
+        override_optional(
+            var,
+            &[
+                "OTEL_EXPORTER_OTLP_ENDPOINT",
+                "REX_TELEMETRY_OPENTELEMETRY_ENDPOINT",
+            ],
+            &mut self.telemetry.opentelemetry_endpoint,
+        );
+        for name in [
+            "OTEL_EXPORTER_OTLP_HEADERS",
+            "REX_TELEMETRY_OPENTELEMETRY_HEADERS",
+        ] {
+            if let Some(value) = var(name) {
+                self.telemetry.opentelemetry_headers = parse_headers(name, &value)?;
+            }
+        }
+        override_optional(
+            var,
+            &["HONEYCOMB_KEY", "REX_TELEMETRY_HONEYCOMB_KEY"],
+            &mut self.telemetry.honeycomb_key,
+        );
+        override_optional(
+            var,
+            &["REX_TELEMETRY_MEDAMA_URL"],
+            &mut self.telemetry.medama_url,
+        );
+        override_with(
+            var,
+            &["REX_TELEMETRY_LOG_FORMAT"],
+            &mut self.telemetry.log_format,
+        )?;
+        override_optional(
+            var,
+            &["REX_TELEMETRY_METRICS_ADDRESS"],
+            &mut self.telemetry.metrics_address,
+        );
+
+        override_optional(
+            var,
+            &["INTERFACE_BACKEND_URI", "REX_UI_BACKEND_URI"],
+            &mut self.ui.backend_uri,
+        );
+
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.server.port == self.server.grpc_port {
+            return Err(ConfigError(format!(
+                "`server.port` and `server.grpc_port` must be different, but both are {}",
+                self.server.port
+            )));
+        }
+
+        if cfg!(feature = "table_storage") && self.store.table_storage_connection_string.is_none() {
+            return Err(ConfigError(
+                "`store.table_storage_connection_string` must be set to the connection string for your Azure Storage account (or set REX_STORE_TABLE_STORAGE_CONNECTION_STRING)".into(),
+            ));
+        }
+
+        require_url("auth.issuer", Some(&self.auth.issuer))?;
+        if self.auth.client_id.is_empty() {
+            return Err(ConfigError("`auth.client_id` must not be empty".into()));
+        }
+
+        for origin in self.cors.allowed_origins.iter().filter(|o| *o != "*") {
+            require_url("cors.allowed_origins", Some(origin))?;
+        }
+        // Allowing any origin with credentials would let every site make authenticated
+        // requests on behalf of a signed in user, so the origins must be listed instead.
+        if self.cors.allow_credentials && self.cors.allowed_origins.iter().any(|o| o == "*") {
+            return Err(ConfigError(
+                "`cors.allow_credentials` cannot be enabled when `cors.allowed_origins` contains \"*\", list the origins which may send credentials instead".into(),
+            ));
+        }
+
+        if self.webhooks.max_attempts == 0 {
+            return Err(ConfigError(
+                "`webhooks.max_attempts` must be at least 1".into(),
+            ));
+        }
+        if self.webhooks.timeout == 0 {
+            return Err(ConfigError("`webhooks.timeout` must be at least 1".into()));
+        }
+
+        require_url("telemetry.sentry_dsn", self.telemetry.sentry_dsn.as_ref())?;
+        require_url(
+            "telemetry.opentelemetry_endpoint",
+            self.telemetry.opentelemetry_endpoint.as_ref(),
+        )?;
+        require_url("telemetry.medama_url", self.telemetry.medama_url.as_ref())?;
+        if let Some(address) = &self.telemetry.metrics_address
+            && address.parse::<std::net::SocketAddr>().is_err()
+        {
+            return Err(ConfigError(format!(
+                "`telemetry.metrics_address` must be an address to listen on, such as 127.0.0.1:9090, not '{address}'"
+            )));
+        }
+        require_url("ui.backend_uri", self.ui.backend_uri.as_ref())?;
+
+        Ok(())
+    }
+}
+
+/// Overrides a setting with the value of the last of the named environment variables
+/// which has been set.
+fn override_with<F, T>(var: &F, names: &[&str], setting: &mut T) -> Result<(), ConfigError>
+where
+    F: Fn(&str) -> Option<String>,
+    T: FromStr,
+    T::Err: Display,
+{
+    for name in names {
+        if let Some(value) = var(name).filter(|v| !v.is_empty()) {
+            *setting = value.trim().parse().map_err(|err| {
+                ConfigError(format!(
+                    "the {name} environment variable ('{value}') is not valid: {err}"
+                ))
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Overrides an optional setting with the value of the last of the named environment
+/// variables which has been set, allowing an empty variable to unset it.
+fn override_optional<F>(var: &F, names: &[&str], setting: &mut Option<String>)
+where
+    F: Fn(&str) -> Option<String>,
+{
+    for name in names {
+        if let Some(value) = var(name) {
+            *setting = Some(value).filter(|v| !v.is_empty());
+        }
+    }
+}
+
+/// Parses headers in the `name=value,name=value` format used by `OTEL_EXPORTER_OTLP_HEADERS`.
+fn parse_headers(name: &str, value: &str) -> Result<BTreeMap<String, String>, ConfigError> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|header| !header.is_empty())
+        .map(|header| match header.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(ConfigError(format!(
+                "the {name} environment variable is not valid: '{header}' should be in the form 'name=value'"
+            ))),
+        })
+        .collect()
+}
+
+fn require_url(key: &str, value: Option<&String>) -> Result<(), ConfigError> {
+    match value.map(|v| reqwest::Url::parse(v)) {
+        Some(Ok(url)) if url.has_host() => Ok(()),
+        Some(Ok(_)) => Err(ConfigError(format!(
+            "`{key}` must be an absolute URL, but was '{}'",
+            value.unwrap()
+        ))),
+        Some(Err(err)) => Err(ConfigError(format!(
+            "`{key}` must be a valid URL, but '{}' is not: {err}",
+            value.unwrap()
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::LogFormat;
+    use std::collections::HashMap;
+
+    fn vars(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn defaults() {
+        let config = Config::load_from(None, vars(&[])).expect("the defaults should be valid");
+
+        assert_eq!(config.server, ServerConfig::default());
+        assert_eq!(config.auth, AuthConfig::default());
+        assert_eq!(config.slack.signing_secret, None);
+        assert_eq!(config.ui.backend_uri, None);
+    }
+
+    #[test]
+    fn parse_file() {
+        let config = Config::parse(
+            r#"
+            [server]
+            port = 9000
+
+            [auth]
+            issuer = "https://auth.example.com/"
+            client_id = "rex"
+
+            [cors]
+            allowed_origins = ["https://app.example.com"]
+            allowed_methods = ["GET"]
+
+            [webhooks]
+            max_attempts = 3
+            allow_private_addresses = true
+
+            [ui]
+            backend_uri = "http://localhost:3000"
+            "#,
+        )
+        .expect("the configuration should parse");
+
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.server.grpc_port, 8001);
+        assert_eq!(config.auth.issuer, "https://auth.example.com/");
+        assert_eq!(config.auth.client_id, "rex");
+        assert_eq!(config.cors.allowed_origins, vec!["https://app.example.com"]);
+        assert_eq!(
+            config.cors.allowed_methods,
+            vec![actix_web::http::Method::GET]
+        );
+        assert_eq!(config.webhooks.max_attempts, 3);
+        assert_eq!(config.webhooks.timeout, WebhookConfig::default().timeout);
+        assert!(config.webhooks.allow_private_addresses);
+        assert_eq!(
+            config.ui.backend_uri,
+            Some("http://localhost:3000".to_string())
+        );
+        config
+            .validate()
+            .expect("the configuration should be valid");
+    }
+
+    #[test]
+    fn parse_file_errors() {
+        let err = Config::parse("[server]\nport = \"eighty\"").expect_err("the port is invalid");
+        assert!(err.to_string().contains("invalid type"), "{err}");
+
+        let err = Config::parse("[server]\nhost = \"localhost\"").expect_err("the key is unknown");
+        assert!(err.to_string().contains("unknown field `host`"), "{err}");
+
+        let err = Config::parse("[cors]\nallowed_methods = [\"GET\", \"FETCH\"]")
+            .expect_err("the method is unknown");
+        assert!(err.to_string().contains("'FETCH'"), "{err}");
+    }
+
+    #[test]
+    fn environment_overrides() {
+        let config = Config::load_from(
+            None,
+            vars(&[
+                ("FUNCTIONS_CUSTOMHANDLER_PORT", "7000"),
+                ("REX_SERVER_PORT", "9000"),
+                ("GRPC_PORT", "9001"),
+                ("REX_SERVER_SHUTDOWN_TIMEOUT", "5"),
+                ("REX_STORE_UPGRADE_SCHEMA_ON_START", "true"),
+                ("REX_AUTH_TRUST_ISSUER_EMAILS", "false"),
+                ("REX_WEBHOOKS_TIMEOUT", "30"),
+                ("REX_WEBHOOKS_ALLOW_PRIVATE_ADDRESSES", "true"),
+                ("SLACK_SIGNING_SECRET", "slack-secret"),
+                ("REX_INVITE_LINKS_SECRET", "invite-secret"),
+                ("REX_CORS_ALLOWED_ORIGINS", "https://app.example.com"),
+                ("INTERFACE_BACKEND_URI", "http://localhost:3000"),
+                ("REX_TELEMETRY_SENTRY_DSN", ""),
+                ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4318"),
+                (
+                    "REX_TELEMETRY_OPENTELEMETRY_HEADERS",
+                    "authorization=Bearer token, x-tenant=rex",
+                ),
+                ("REX_TELEMETRY_LOG_FORMAT", "json"),
+            ]),
+        )
+        .expect("the configuration should be valid");
+
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.server.grpc_port, 9001);
+        assert_eq!(config.server.shutdown_timeout, 5);
+        assert!(config.store.upgrade_schema_on_start);
+        assert!(!config.auth.trust_issuer_emails);
+        assert_eq!(config.webhooks.timeout, 30);
+        assert!(config.webhooks.allow_private_addresses);
+        assert_eq!(config.slack.signing_secret, Some("slack-secret".into()));
+        assert_eq!(config.invite_links.secret, Some("invite-secret".into()));
+        assert_eq!(config.cors.allowed_origins, vec!["https://app.example.com"]);
+        assert_eq!(
+            config.ui.backend_uri,
+            Some("http://localhost:3000".to_string())
+        );
+        assert_eq!(config.telemetry.sentry_dsn, None);
+        assert_eq!(
+            config.telemetry.opentelemetry_endpoint,
+            Some("http://localhost:4318".into())
+        );
+        assert_eq!(
+            config.telemetry.opentelemetry_headers,
+            BTreeMap::from([
+                ("authorization".to_string(), "Bearer token".to_string()),
+                ("x-tenant".to_string(), "rex".to_string()),
+            ])
+        );
+        assert_eq!(config.telemetry.log_format, LogFormat::Json);
+    }
+
+    #[test]
+    fn telemetry_opt_out() {
+        let config = Config::parse(
+            r#"
+            [telemetry]
+            export = false
+            log_format = "json"
+            "#,
+        )
+        .expect("the configuration should parse");
+        assert!(!config.telemetry.export);
+        assert_eq!(config.telemetry.log_format, LogFormat::Json);
+
+        let config = Config::parse(
+            r#"
+            [telemetry]
+            sentry_dsn = ""
+            medama_url = ""
+            "#,
+        )
+        .expect("the configuration should parse");
+        config
+            .validate()
+            .expect("the configuration should be valid");
+        assert!(config.telemetry.export);
+        assert_eq!(config.telemetry.sentry_dsn, None);
+        assert_eq!(config.telemetry.medama_url, None);
+        assert_eq!(
+            config.telemetry.opentelemetry_endpoint,
+            TelemetryConfig::default().opentelemetry_endpoint
+        );
+
+        let config = Config::load_from(None, vars(&[("REX_TELEMETRY_EXPORT", "false")]))
+            .expect("the configuration should be valid");
+        assert!(!config.telemetry.export);
+        assert_eq!(config.telemetry.metrics_address, None);
+
+        let config = Config::load_from(
+            None,
+            vars(&[("REX_TELEMETRY_METRICS_ADDRESS", "127.0.0.1:9090")]),
+        )
+        .expect("the configuration should be valid");
+        assert_eq!(
+            config.telemetry.metrics_address,
+            Some("127.0.0.1:9090".into())
+        );
+
+        let err = Config::load_from(None, vars(&[("REX_TELEMETRY_METRICS_ADDRESS", "9090")]))
+            .expect_err("the address has no host");
+        assert!(err.to_string().contains("metrics_address"), "{err}");
+
+        let err = Config::load_from(None, vars(&[("OTEL_EXPORTER_OTLP_HEADERS", "api-key")]))
+            .expect_err("the header has no value");
+        assert!(
+            err.to_string().contains("OTEL_EXPORTER_OTLP_HEADERS"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn validation() {
+        let err = Config::load_from(None, vars(&[("REX_SERVER_PORT", "http")]))
+            .expect_err("the port is not a number");
+        assert!(err.to_string().contains("REX_SERVER_PORT"), "{err}");
+
+        let err = Config::load_from(None, vars(&[("REX_SERVER_GRPC_PORT", "8000")]))
+            .expect_err("the ports conflict");
+        assert!(err.to_string().contains("server.grpc_port"), "{err}");
+
+        let err = Config::load_from(None, vars(&[("REX_AUTH_ISSUER", "not a url")]))
+            .expect_err("the issuer is not a URL");
+        assert!(err.to_string().contains("auth.issuer"), "{err}");
+
+        let err = Config::load_from(None, vars(&[("REX_UI_BACKEND_URI", "/ui")]))
+            .expect_err("the UI backend is not a URL");
+        assert!(err.to_string().contains("ui.backend_uri"), "{err}");
+
+        let err = Config::load_from(None, vars(&[("REX_CORS_ALLOW_CREDENTIALS", "yes")]))
+            .expect_err("the credentials setting is not a boolean");
+        assert!(err.to_string().contains("CORS_ALLOW_CREDENTIALS"), "{err}");
+
+        let err = Config::load_from(
+            None,
+            vars(&[
+                ("REX_CORS_ALLOWED_ORIGINS", "*"),
+                ("REX_CORS_ALLOW_CREDENTIALS", "true"),
+            ]),
+        )
+        .expect_err("any origin cannot be allowed with credentials");
+        assert!(err.to_string().contains("cors.allow_credentials"), "{err}");
+
+        let err = Config::load_from(None, vars(&[("REX_CONFIG", "/does/not/exist.toml")]))
+            .expect_err("the file does not exist");
+        assert!(err.to_string().contains("/does/not/exist.toml"), "{err}");
+    }
+}
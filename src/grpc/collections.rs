@@ -0,0 +1,348 @@
+use super::{authenticate, pb};
+use crate::api::{
+    APIError, AuthToken, OidcActor, authorize, authorize_collection_entry, ensure_user_collection,
+};
+use crate::{models::*, parse_uuid, require_role, require_scope, telemetry::TraceMessageExt};
+use actix::Addr;
+use tonic::{Request, Response, Status};
+
+pub struct CollectionsService {
+    state: GlobalState,
+    oidc: Addr<OidcActor>,
+}
+
+impl CollectionsService {
+    pub fn new(state: GlobalState, oidc: Addr<OidcActor>) -> Self {
+        Self { state, oidc }
+    }
+}
+
+#[tonic::async_trait]
+impl pb::collections_server::Collections for CollectionsService {
+    #[instrument("rex.v1.Collections/ListCollections", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn list_collections(
+        &self,
+        request: Request<pb::ListCollectionsRequest>,
+    ) -> Result<Response<pb::ListCollectionsResponse>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(list_collections(&self.state, &token).await?))
+    }
+
+    #[instrument("rex.v1.Collections/GetCollection", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn get_collection(
+        &self,
+        request: Request<pb::GetCollectionRequest>,
+    ) -> Result<Response<pb::Collection>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            get_collection(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Collections/NewCollection", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn new_collection(
+        &self,
+        request: Request<pb::NewCollectionRequest>,
+    ) -> Result<Response<pb::Collection>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            new_collection(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Collections/StoreCollection", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn store_collection(
+        &self,
+        request: Request<pb::StoreCollectionRequest>,
+    ) -> Result<Response<pb::Collection>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            store_collection(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Collections/RemoveCollection", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn remove_collection(
+        &self,
+        request: Request<pb::RemoveCollectionRequest>,
+    ) -> Result<Response<pb::RemoveCollectionResponse>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            remove_collection(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+}
+
+async fn list_collections(
+    state: &GlobalState,
+    token: &AuthToken,
+) -> Result<pb::ListCollectionsResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Read");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    ensure_user_collection(state, token).await?;
+
+    state
+        .store
+        .send(GetCollections { principal_id: uid }.trace())
+        .await?
+        .map(|collections| pb::ListCollectionsResponse {
+            collections: collections.into_iter().map(|c| c.into()).collect(),
+        })
+}
+
+async fn get_collection(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::GetCollectionRequest,
+) -> Result<pb::Collection, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Read");
+
+    let cid = parse_uuid!(request.id, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(state, cid, uid, Permission::CollectionRead).await?;
+
+    state
+        .store
+        .send(
+            GetCollection {
+                id: cid,
+                principal_id: uid,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|collection| collection.into())
+}
+
+async fn new_collection(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::NewCollectionRequest,
+) -> Result<pb::Collection, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    validate_name(&request.name)?;
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    let collection = state
+        .store
+        .send(
+            StoreCollection {
+                principal_id: uid,
+                collection_id: new_id(),
+                name: request.name,
+            }
+            .trace(),
+        )
+        .await??;
+
+    state
+        .store
+        .send(
+            StoreRoleAssignment {
+                principal_id: uid,
+                collection_id: collection.collection_id,
+                role: Role::Owner,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(collection.into())
+}
+
+async fn store_collection(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::StoreCollectionRequest,
+) -> Result<pb::Collection, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    validate_name(&request.name)?;
+
+    let cid = parse_uuid!(request.id, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    // Storing a collection only changes the caller's own entry in their list, so any
+    // member may rename it, while a new collection is created with the caller as its owner.
+    let is_new = authorize_collection_entry(state, cid, uid).await?;
+
+    let collection = state
+        .store
+        .send(
+            StoreCollection {
+                principal_id: uid,
+                collection_id: cid,
+                name: request.name,
+            }
+            .trace(),
+        )
+        .await??;
+
+    if is_new {
+        state
+            .store
+            .send(
+                StoreRoleAssignment {
+                    principal_id: uid,
+                    collection_id: cid,
+                    role: Role::Owner,
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    Ok(collection.into())
+}
+
+async fn remove_collection(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::RemoveCollectionRequest,
+) -> Result<pb::RemoveCollectionResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Collections.Write");
+
+    let cid = parse_uuid!(request.id, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    // Removing a collection only removes it from the caller's own list, so any member
+    // who can see it may do so.
+    authorize(state, cid, uid, Permission::CollectionRead).await?;
+
+    state
+        .store
+        .send(
+            RemoveCollection {
+                id: cid,
+                principal_id: uid,
+            }
+            .trace(),
+        )
+        .await??;
+
+    state
+        .store
+        .send(
+            RemoveRoleAssignment {
+                collection_id: cid,
+                principal_id: uid,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(pb::RemoveCollectionResponse {})
+}
+
+/// Applies the same validation rules as the REST API to a collection's name.
+fn validate_name(name: &str) -> Result<(), APIError> {
+    CollectionV3 {
+        id: None,
+        user_id: None,
+        name: name.to_string(),
+    }
+    .validate()
+}
+
+impl From<Collection> for pb::Collection {
+    fn from(collection: Collection) -> Self {
+        Self {
+            id: format!("{:0>32x}", collection.collection_id),
+            user_id: format!("{:0>32x}", collection.user_id),
+            name: collection.name,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test::*;
+    use super::*;
+    use pb::collections_server::Collections;
+
+    #[actix_rt::test]
+    async fn new_collection() {
+        test_log_init();
+
+        test_state!(state = []);
+        let service = CollectionsService::new(state.clone(), test_oidc());
+
+        let collection = service
+            .new_collection(authenticated(pb::NewCollectionRequest {
+                name: "Test Collection".into(),
+            }))
+            .await
+            .expect("the collection should be created")
+            .into_inner();
+
+        assert_eq!(collection.name, "Test Collection");
+        assert_eq!(collection.user_id, "00000000000000000000000000000000");
+
+        let role = state
+            .store
+            .send(GetRoleAssignment {
+                collection_id: u128::from_str_radix(&collection.id, 16).unwrap(),
+                principal_id: 0,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the role assignment should exist");
+
+        assert_eq!(role.role, Role::Owner);
+    }
+
+    #[actix_rt::test]
+    async fn list_collections() {
+        test_log_init();
+
+        test_state!(
+            state = [StoreCollection {
+                collection_id: 7,
+                principal_id: 0,
+                name: "Test Collection".into(),
+            }]
+        );
+        let service = CollectionsService::new(state, test_oidc());
+
+        let collections = service
+            .list_collections(authenticated(pb::ListCollectionsRequest {}))
+            .await
+            .expect("the collections should be listed")
+            .into_inner()
+            .collections;
+
+        assert_eq!(collections.len(), 2);
+        assert!(
+            collections
+                .iter()
+                .any(|c| c.id == "00000000000000000000000000000007")
+        );
+    }
+
+    #[actix_rt::test]
+    async fn get_collection_not_member() {
+        test_log_init();
+
+        test_state!(state = []);
+        let service = CollectionsService::new(state, test_oidc());
+
+        let err = service
+            .get_collection(authenticated(pb::GetCollectionRequest {
+                id: "00000000000000000000000000000007".into(),
+            }))
+            .await
+            .expect_err("the collection should not be visible to someone who is not a member");
+
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+}
@@ -0,0 +1,452 @@
+use super::{authenticate, parse_id_or, pb};
+use crate::api::{
+    APIError, AuthToken, OidcActor, authorize, ensure_user_collection, store_idea_permission,
+};
+use crate::{models::*, parse_uuid, require_role, require_scope, telemetry::TraceMessageExt};
+use actix::Addr;
+use tonic::{Request, Response, Status};
+
+pub struct IdeasService {
+    state: GlobalState,
+    oidc: Addr<OidcActor>,
+}
+
+impl IdeasService {
+    pub fn new(state: GlobalState, oidc: Addr<OidcActor>) -> Self {
+        Self { state, oidc }
+    }
+}
+
+#[tonic::async_trait]
+impl pb::ideas_server::Ideas for IdeasService {
+    #[instrument("rex.v1.Ideas/ListIdeas", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn list_ideas(
+        &self,
+        request: Request<pb::ListIdeasRequest>,
+    ) -> Result<Response<pb::ListIdeasResponse>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            list_ideas(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Ideas/GetIdea", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn get_idea(
+        &self,
+        request: Request<pb::GetIdeaRequest>,
+    ) -> Result<Response<pb::Idea>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            get_idea(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Ideas/GetRandomIdea", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn get_random_idea(
+        &self,
+        request: Request<pb::GetRandomIdeaRequest>,
+    ) -> Result<Response<pb::Idea>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            get_random_idea(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Ideas/NewIdea", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn new_idea(
+        &self,
+        request: Request<pb::NewIdeaRequest>,
+    ) -> Result<Response<pb::Idea>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            new_idea(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Ideas/StoreIdea", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn store_idea(
+        &self,
+        request: Request<pb::StoreIdeaRequest>,
+    ) -> Result<Response<pb::Idea>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            store_idea(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.Ideas/RemoveIdea", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn remove_idea(
+        &self,
+        request: Request<pb::RemoveIdeaRequest>,
+    ) -> Result<Response<pb::RemoveIdeaResponse>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            remove_idea(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+}
+
+async fn list_ideas(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::ListIdeasRequest,
+) -> Result<pb::ListIdeasResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Read");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let cid = parse_id_or(&request.collection, uid, "collection ID")?;
+
+    ensure_user_collection(state, token).await?;
+    authorize(state, cid, uid, Permission::IdeaRead).await?;
+
+    state
+        .store
+        .send(
+            GetIdeas {
+                collection: cid,
+                is_completed: request.completed,
+                tag: request.tag,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|ideas| pb::ListIdeasResponse {
+            ideas: ideas.into_iter().map(|i| i.into()).collect(),
+        })
+}
+
+async fn get_idea(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::GetIdeaRequest,
+) -> Result<pb::Idea, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Read");
+
+    let id = parse_uuid!(request.id, "idea ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let cid = parse_id_or(&request.collection, uid, "collection ID")?;
+
+    ensure_user_collection(state, token).await?;
+    authorize(state, cid, uid, Permission::IdeaRead).await?;
+
+    state
+        .store
+        .send(
+            GetIdea {
+                collection: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|idea| idea.into())
+}
+
+async fn get_random_idea(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::GetRandomIdeaRequest,
+) -> Result<pb::Idea, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Read");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let cid = parse_id_or(&request.collection, uid, "collection ID")?;
+
+    ensure_user_collection(state, token).await?;
+    authorize(state, cid, uid, Permission::IdeaRead).await?;
+
+    state
+        .store
+        .send(
+            GetRandomIdea {
+                collection: cid,
+                is_completed: request.completed,
+                tag: request.tag,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|idea| idea.into())
+}
+
+async fn new_idea(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::NewIdeaRequest,
+) -> Result<pb::Idea, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let cid = parse_id_or(&request.collection, uid, "collection ID")?;
+
+    let idea = Idea {
+        id: new_id(),
+        collection_id: cid,
+        name: request.name,
+        description: request.description,
+        tags: request.tags.into_iter().collect(),
+        completed: request.completed,
+        created_at: None,
+        completed_at: None,
+    };
+    idea.validate()?;
+
+    ensure_user_collection(state, token).await?;
+    authorize(state, cid, uid, Permission::IdeaCreate).await?;
+
+    state
+        .store
+        .send(
+            StoreIdea {
+                id: idea.id,
+                collection: cid,
+                name: idea.name,
+                description: idea.description,
+                tags: idea.tags,
+                completed: idea.completed,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|idea| idea.into())
+}
+
+async fn store_idea(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::StoreIdeaRequest,
+) -> Result<pb::Idea, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let id = parse_uuid!(request.id, "idea ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let cid = parse_id_or(&request.collection, uid, "collection ID")?;
+
+    let idea = Idea {
+        id,
+        collection_id: cid,
+        name: request.name,
+        description: request.description,
+        tags: request.tags.into_iter().collect(),
+        completed: request.completed,
+        created_at: None,
+        completed_at: None,
+    };
+    idea.validate()?;
+
+    ensure_user_collection(state, token).await?;
+
+    let permission = store_idea_permission(state, cid, id, &idea).await?;
+    authorize(state, cid, uid, permission).await?;
+
+    state
+        .store
+        .send(
+            StoreIdea {
+                id,
+                collection: cid,
+                name: idea.name,
+                description: idea.description,
+                tags: idea.tags,
+                completed: idea.completed,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|idea| idea.into())
+}
+
+async fn remove_idea(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::RemoveIdeaRequest,
+) -> Result<pb::RemoveIdeaResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "Ideas.Write");
+
+    let id = parse_uuid!(request.id, "idea ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let cid = parse_id_or(&request.collection, uid, "collection ID")?;
+
+    ensure_user_collection(state, token).await?;
+    authorize(state, cid, uid, Permission::IdeaRemove).await?;
+
+    state
+        .store
+        .send(
+            RemoveIdea {
+                collection: cid,
+                id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(pb::RemoveIdeaResponse {})
+}
+
+impl From<Idea> for pb::Idea {
+    fn from(idea: Idea) -> Self {
+        let mut tags: Vec<String> = idea.tags.into_iter().collect();
+        tags.sort();
+
+        Self {
+            id: format!("{:0>32x}", idea.id),
+            collection: format!("{:0>32x}", idea.collection_id),
+            name: idea.name,
+            description: idea.description,
+            tags,
+            completed: idea.completed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test::*;
+    use super::*;
+    use pb::ideas_server::Ideas;
+
+    #[actix_rt::test]
+    async fn new_idea() {
+        test_log_init();
+
+        test_state!(state = []);
+        let service = IdeasService::new(state.clone(), test_oidc());
+
+        let idea = service
+            .new_idea(authenticated(pb::NewIdeaRequest {
+                collection: String::new(),
+                name: "Test Idea".into(),
+                description: "This is a test idea".into(),
+                tags: vec!["test".into()],
+                completed: false,
+            }))
+            .await
+            .expect("the idea should be created")
+            .into_inner();
+
+        assert_eq!(idea.collection, "00000000000000000000000000000000");
+        assert_eq!(idea.name, "Test Idea");
+        assert_eq!(idea.tags, vec!["test".to_string()]);
+
+        state
+            .store
+            .send(GetIdea {
+                collection: 0,
+                id: u128::from_str_radix(&idea.id, 16).unwrap(),
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("The idea should exist in the store");
+    }
+
+    #[actix_rt::test]
+    async fn new_idea_invalid() {
+        test_log_init();
+
+        test_state!(state = []);
+        let service = IdeasService::new(state, test_oidc());
+
+        let err = service
+            .new_idea(authenticated(pb::NewIdeaRequest {
+                name: "".into(),
+                ..Default::default()
+            }))
+            .await
+            .expect_err("the idea should be rejected");
+
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[actix_rt::test]
+    async fn get_random_idea() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 0,
+                    role: Role::Viewer,
+                },
+                StoreIdea {
+                    id: 1,
+                    collection: 7,
+                    name: "Test Idea".into(),
+                    description: "This is a test idea".into(),
+                    tags: hashset!("test"),
+                    completed: false,
+                }
+            ]
+        );
+        let service = IdeasService::new(state, test_oidc());
+
+        let idea = service
+            .get_random_idea(authenticated(pb::GetRandomIdeaRequest {
+                collection: "00000000000000000000000000000007".into(),
+                completed: Some(false),
+                tag: Some("test".into()),
+            }))
+            .await
+            .expect("an idea should be returned")
+            .into_inner();
+
+        assert_eq!(idea.id, "00000000000000000000000000000001");
+        assert_eq!(idea.collection, "00000000000000000000000000000007");
+    }
+
+    #[actix_rt::test]
+    async fn remove_idea_viewer() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 0,
+                    role: Role::Viewer,
+                },
+                StoreIdea {
+                    id: 1,
+                    collection: 7,
+                    name: "Test Idea".into(),
+                    description: "This is a test idea".into(),
+                    tags: hashset!("test"),
+                    completed: false,
+                }
+            ]
+        );
+        let service = IdeasService::new(state, test_oidc());
+
+        let err = service
+            .remove_idea(authenticated(pb::RemoveIdeaRequest {
+                collection: "00000000000000000000000000000007".into(),
+                id: "00000000000000000000000000000001".into(),
+            }))
+            .await
+            .expect_err("a viewer should not be able to remove ideas");
+
+        assert_eq!(err.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[actix_rt::test]
+    async fn list_ideas_unauthenticated() {
+        test_log_init();
+
+        test_state!(state = []);
+        let service = IdeasService::new(state, test_oidc());
+
+        let err = service
+            .list_ideas(Request::new(pb::ListIdeasRequest::default()))
+            .await
+            .expect_err("the request should be rejected");
+
+        assert_eq!(err.code(), tonic::Code::Unauthenticated);
+    }
+}
@@ -0,0 +1,83 @@
+mod collections;
+mod ideas;
+mod role_assignments;
+
+#[cfg(test)]
+mod test;
+
+use crate::api::{APIError, AuthToken, ErrorKind, OidcActor};
+use crate::models::GlobalState;
+use crate::parse_uuid;
+use actix::Addr;
+use std::{future::Future, net::SocketAddr};
+use tonic::{Request, Status, transport::Server};
+
+pub mod pb {
+    tonic::include_proto!("rex.v1");
+}
+
+/// Serves the `rex.v1` gRPC API on the provided address, backed by the same store and
+/// OpenID Connect verifier as the REST API, until the `shutdown` future completes.
+pub async fn serve<F: Future<Output = ()>>(
+    addr: SocketAddr,
+    state: GlobalState,
+    oidc: Addr<OidcActor>,
+    shutdown: F,
+) -> Result<(), tonic::transport::Error> {
+    Server::builder()
+        .add_service(pb::ideas_server::IdeasServer::new(
+            ideas::IdeasService::new(state.clone(), oidc.clone()),
+        ))
+        .add_service(pb::collections_server::CollectionsServer::new(
+            collections::CollectionsService::new(state.clone(), oidc.clone()),
+        ))
+        .add_service(pb::role_assignments_server::RoleAssignmentsServer::new(
+            role_assignments::RoleAssignmentsService::new(state, oidc),
+        ))
+        .serve_with_shutdown(addr, shutdown)
+        .await
+}
+
+/// Verifies the bearer token provided in the `authorization` metadata of a request.
+async fn authenticate<T>(
+    oidc: &Addr<OidcActor>,
+    request: &Request<T>,
+) -> Result<AuthToken, Status> {
+    let authorization = request
+        .metadata()
+        .get("authorization")
+        .map(|value| value.to_str().map_err(|_| APIError::unauthorized()))
+        .transpose()?;
+
+    Ok(AuthToken::from_authorization(oidc, authorization).await?)
+}
+
+/// Parses an identifier from a request, falling back to the provided default when it
+/// has been left empty.
+fn parse_id_or(id: &str, default: u128, desc: &str) -> Result<u128, APIError> {
+    if id.is_empty() {
+        Ok(default)
+    } else {
+        Ok(parse_uuid!(id, desc))
+    }
+}
+
+impl From<APIError> for Status {
+    fn from(err: APIError) -> Self {
+        let code = match err.kind() {
+            Some(ErrorKind::Unauthorized) => tonic::Code::Unauthenticated,
+            Some(ErrorKind::ForbiddenRole) | Some(ErrorKind::ForbiddenScope) => {
+                tonic::Code::PermissionDenied
+            }
+            Some(ErrorKind::NotFound) => tonic::Code::NotFound,
+            Some(ErrorKind::Validation) => tonic::Code::InvalidArgument,
+            Some(ErrorKind::Conflict) => tonic::Code::AlreadyExists,
+            Some(ErrorKind::UpstreamUnavailable) => tonic::Code::Unavailable,
+            None if err.code == 410 => tonic::Code::FailedPrecondition,
+            None if err.code == 503 => tonic::Code::Unavailable,
+            None => tonic::Code::Internal,
+        };
+
+        Status::new(code, err.message)
+    }
+}
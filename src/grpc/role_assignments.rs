@@ -0,0 +1,371 @@
+use super::{authenticate, pb};
+use crate::api::{APIError, AuthToken, ErrorKind, OidcActor, authorize};
+use crate::{models::*, parse_uuid, require_role, require_scope, telemetry::TraceMessageExt};
+use actix::Addr;
+use tonic::{Request, Response, Status};
+
+pub struct RoleAssignmentsService {
+    state: GlobalState,
+    oidc: Addr<OidcActor>,
+}
+
+impl RoleAssignmentsService {
+    pub fn new(state: GlobalState, oidc: Addr<OidcActor>) -> Self {
+        Self { state, oidc }
+    }
+}
+
+#[tonic::async_trait]
+impl pb::role_assignments_server::RoleAssignments for RoleAssignmentsService {
+    #[instrument("rex.v1.RoleAssignments/ListRoleAssignments", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn list_role_assignments(
+        &self,
+        request: Request<pb::ListRoleAssignmentsRequest>,
+    ) -> Result<Response<pb::ListRoleAssignmentsResponse>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            list_role_assignments(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.RoleAssignments/GetRoleAssignment", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn get_role_assignment(
+        &self,
+        request: Request<pb::GetRoleAssignmentRequest>,
+    ) -> Result<Response<pb::RoleAssignment>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            get_role_assignment(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.RoleAssignments/StoreRoleAssignment", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn store_role_assignment(
+        &self,
+        request: Request<pb::StoreRoleAssignmentRequest>,
+    ) -> Result<Response<pb::RoleAssignment>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            store_role_assignment(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+
+    #[instrument("rex.v1.RoleAssignments/RemoveRoleAssignment", err, skip(self, request), fields(otel.kind = "server"))]
+    async fn remove_role_assignment(
+        &self,
+        request: Request<pb::RemoveRoleAssignmentRequest>,
+    ) -> Result<Response<pb::RemoveRoleAssignmentResponse>, Status> {
+        let token = authenticate(&self.oidc, &request).await?;
+        Ok(Response::new(
+            remove_role_assignment(&self.state, &token, request.into_inner()).await?,
+        ))
+    }
+}
+
+async fn list_role_assignments(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::ListRoleAssignmentsRequest,
+) -> Result<pb::ListRoleAssignmentsResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(request.collection_id, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+
+    authorize(state, cid, uid, Permission::MemberList).await?;
+
+    state
+        .store
+        .send(GetRoleAssignments { collection_id: cid }.trace())
+        .await?
+        .map(|roles| pb::ListRoleAssignmentsResponse {
+            role_assignments: roles.into_iter().map(|r| r.into()).collect(),
+        })
+}
+
+async fn get_role_assignment(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::GetRoleAssignmentRequest,
+) -> Result<pb::RoleAssignment, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(request.collection_id, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let tuid = parse_uuid!(request.user_id, "user ID");
+
+    if uid != tuid {
+        authorize(state, cid, uid, Permission::MemberList).await?;
+    }
+
+    state
+        .store
+        .send(
+            GetRoleAssignment {
+                collection_id: cid,
+                principal_id: tuid,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|role| role.into())
+}
+
+async fn store_role_assignment(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::StoreRoleAssignmentRequest,
+) -> Result<pb::RoleAssignment, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let role: Role = request.role().into();
+    if role == Role::Invalid {
+        return Err(APIError::new(
+            422,
+            "Unprocessable Entity",
+            "The role assignment you provided is not valid. The role must be one of Owner, Contributor or Viewer.",
+        )
+        .with_kind(ErrorKind::Validation)
+        .with_field("role", "The role must be one of Owner, Contributor or Viewer."));
+    }
+
+    let cid = parse_uuid!(request.collection_id, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let tuid = parse_uuid!(request.user_id, "user ID");
+
+    let original_collection = state
+        .store
+        .send(
+            GetCollection {
+                id: cid,
+                principal_id: uid,
+            }
+            .trace(),
+        )
+        .await??;
+
+    if tuid == uid {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "You cannot modify your own role assignment. Please request that another collection owner performs this task for you.",
+        ));
+    }
+
+    authorize(state, cid, uid, Permission::MemberInvite).await?;
+
+    match state
+        .store
+        .send(
+            GetCollection {
+                principal_id: tuid,
+                id: cid,
+            }
+            .trace(),
+        )
+        .await?
+    {
+        Ok(_) => {}
+        Err(err) if err.code == 404 => {
+            state
+                .store
+                .send(
+                    StoreCollection {
+                        principal_id: tuid,
+                        collection_id: cid,
+                        name: original_collection.name,
+                    }
+                    .trace(),
+                )
+                .await??;
+        }
+        Err(err) => return Err(err),
+    }
+
+    state
+        .store
+        .send(
+            StoreRoleAssignment {
+                principal_id: tuid,
+                collection_id: cid,
+                role,
+            }
+            .trace(),
+        )
+        .await?
+        .map(|role| role.into())
+}
+
+async fn remove_role_assignment(
+    state: &GlobalState,
+    token: &AuthToken,
+    request: pb::RemoveRoleAssignmentRequest,
+) -> Result<pb::RemoveRoleAssignmentResponse, APIError> {
+    require_role!(token, "Administrator", "User");
+    require_scope!(token, "RoleAssignments.Write");
+
+    let cid = parse_uuid!(request.collection_id, "collection ID");
+    let uid = parse_uuid!(token.oid(), "auth token oid");
+    let tuid = parse_uuid!(request.user_id, "user ID");
+
+    if tuid == uid {
+        return Err(APIError::new(
+            400,
+            "Bad Request",
+            "You cannot remove yourself from a collection. Please request that another collection owner performs this for you.",
+        ));
+    }
+
+    authorize(state, cid, uid, Permission::MemberRemove).await?;
+
+    state
+        .store
+        .send(
+            RemoveRoleAssignment {
+                collection_id: cid,
+                principal_id: tuid,
+            }
+            .trace(),
+        )
+        .await??;
+
+    Ok(pb::RemoveRoleAssignmentResponse {})
+}
+
+impl From<RoleAssignment> for pb::RoleAssignment {
+    fn from(assignment: RoleAssignment) -> Self {
+        let mut role = Self {
+            collection_id: format!("{:0>32x}", assignment.collection_id),
+            user_id: format!("{:0>32x}", assignment.user_id),
+            ..Default::default()
+        };
+        role.set_role(assignment.role.into());
+        role
+    }
+}
+
+impl From<Role> for pb::Role {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Owner => pb::Role::Owner,
+            Role::Contributor => pb::Role::Contributor,
+            Role::Viewer => pb::Role::Viewer,
+            Role::Invalid => pb::Role::Unspecified,
+        }
+    }
+}
+
+impl From<pb::Role> for Role {
+    fn from(role: pb::Role) -> Self {
+        match role {
+            pb::Role::Owner => Role::Owner,
+            pb::Role::Contributor => Role::Contributor,
+            pb::Role::Viewer => Role::Viewer,
+            pb::Role::Unspecified => Role::Invalid,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test::*;
+    use super::*;
+    use pb::role_assignments_server::RoleAssignments;
+
+    #[actix_rt::test]
+    async fn store_role_assignment() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreCollection {
+                    collection_id: 7,
+                    principal_id: 0,
+                    name: "Test Collection".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 0,
+                    role: Role::Owner,
+                }
+            ]
+        );
+        let service = RoleAssignmentsService::new(state.clone(), test_oidc());
+
+        let role = service
+            .store_role_assignment(authenticated(pb::StoreRoleAssignmentRequest {
+                collection_id: "00000000000000000000000000000007".into(),
+                user_id: "00000000000000000000000000000001".into(),
+                role: pb::Role::Contributor.into(),
+            }))
+            .await
+            .expect("the role should be assigned")
+            .into_inner();
+
+        assert_eq!(role.role(), pb::Role::Contributor);
+
+        state
+            .store
+            .send(GetCollection {
+                id: 7,
+                principal_id: 1,
+            })
+            .await
+            .expect("the actor should have run")
+            .expect("the collection should be shared with the user");
+    }
+
+    #[actix_rt::test]
+    async fn store_role_assignment_unspecified() {
+        test_log_init();
+
+        test_state!(state = []);
+        let service = RoleAssignmentsService::new(state, test_oidc());
+
+        let err = service
+            .store_role_assignment(authenticated(pb::StoreRoleAssignmentRequest {
+                collection_id: "00000000000000000000000000000007".into(),
+                user_id: "00000000000000000000000000000001".into(),
+                role: pb::Role::Unspecified.into(),
+            }))
+            .await
+            .expect_err("the role should be rejected");
+
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[actix_rt::test]
+    async fn list_role_assignments() {
+        test_log_init();
+
+        test_state!(
+            state = [
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 0,
+                    role: Role::Owner,
+                },
+                StoreRoleAssignment {
+                    collection_id: 7,
+                    principal_id: 1,
+                    role: Role::Viewer,
+                }
+            ]
+        );
+        let service = RoleAssignmentsService::new(state, test_oidc());
+
+        let roles = service
+            .list_role_assignments(authenticated(pb::ListRoleAssignmentsRequest {
+                collection_id: "00000000000000000000000000000007".into(),
+            }))
+            .await
+            .expect("the role assignments should be listed")
+            .into_inner()
+            .role_assignments;
+
+        assert_eq!(roles.len(), 2);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::api::OidcActor;
+use actix::{Actor, Addr};
+use tonic::Request;
+
+pub use crate::api::test::test_log_init;
+pub use crate::test_state;
+
+pub fn test_oidc() -> Addr<OidcActor> {
+    OidcActor::new(Default::default()).start()
+}
+
+/// Wraps a message in a request which carries the test user's bearer token in its
+/// `authorization` metadata.
+pub fn authenticated<T>(message: T) -> Request<T> {
+    let mut request = Request::new(message);
+    request.metadata_mut().insert(
+        "authorization",
+        crate::api::test::auth_token()
+            .parse()
+            .expect("the auth token should be valid metadata"),
+    );
+    request
+}
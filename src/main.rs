@@ -12,45 +12,164 @@ extern crate tracing;
 mod macros;
 
 mod api;
+mod cli;
+mod config;
+mod grpc;
 mod models;
+mod shutdown;
 mod store;
 mod telemetry;
 mod ui;
+mod webhooks;
 
 use actix_web::{App, HttpServer};
+use std::time::Duration;
 use telemetry::TracingLogger;
 
-fn get_listening_port() -> u16 {
-    std::env::var("FUNCTIONS_CUSTOMHANDLER_PORT")
-        .map(|v| v.parse().unwrap_or(8000))
-        .unwrap_or(8000)
-}
-
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
-    let session = telemetry::setup();
-
-    let state = models::GlobalState::new();
-    let oidc = actix_web::web::Data::new(actix::Actor::start(api::OidcActor::new()));
-
-    info!("Starting server on :{}", get_listening_port());
-    HttpServer::new(move || {
-        App::new()
-            .app_data(actix_web::web::Data::new(state.clone()))
-            .app_data(oidc.clone())
-            .wrap(TracingLogger)
-            // TODO: CORS needs to be updated for new actix-web
-            // .wrap(Cors::default().allow_any_origin().send_wildcard())
-            .configure(api::configure)
-            .configure(ui::configure)
-    })
-    .bind(format!("0.0.0.0:{}", get_listening_port()))?
-    .run()
-    .await
-    .map_err(|err| {
+    let cli = match cli::Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let config = match config::Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) if cli.command == cli::Command::Doctor => {
+            println!("[failed]  configuration: {err}");
+            std::process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+
+    match cli.command {
+        cli::Command::Serve => serve(config).await,
+        cli::Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        command => {
+            if let Err(err) = cli::run(command, &config).await {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+
+            Ok(())
+        }
+    }
+}
+
+async fn serve(config: config::Config) -> std::io::Result<()> {
+    let session = telemetry::setup(&config.telemetry);
+
+    let state = models::GlobalState::new(&config.store);
+    actix::Actor::start(webhooks::WebhookDispatcher::new(
+        state.clone(),
+        (&config.webhooks).into(),
+    ));
+    if config.store.upgrade_schema_on_start {
+        actix_rt::spawn(store::upgrade_schema(state.clone()));
+    }
+
+    let oidc = actix::Actor::start(api::OidcActor::new(config.auth.clone()));
+    let cors = config.cors.clone();
+
+    let shutdown_timeout = Duration::from_secs(config.server.shutdown_timeout);
+    let (stop_grpc, grpc_stopped) = tokio::sync::oneshot::channel::<()>();
+
+    info!("Starting gRPC server on :{}", config.server.grpc_port);
+    let grpc = grpc::serve(
+        ([0, 0, 0, 0], config.server.grpc_port).into(),
+        state.clone(),
+        oidc.clone(),
+        async {
+            let _ = grpc_stopped.await;
+        },
+    );
+    let grpc = actix_rt::spawn(async move {
+        if let Err(err) = grpc.await {
+            error!("The gRPC server exited unexpectedly: {}", err);
+        }
+    });
+
+    let oidc = actix_web::web::Data::new(oidc);
+    let metrics_address = config.telemetry.metrics_address.clone();
+
+    let port = config.server.port;
+    info!("Starting server on :{}", port);
+    let server = {
+        let state = state.clone();
+
+        HttpServer::new(move || {
+            App::new()
+                .app_data(actix_web::web::Data::new(state.clone()))
+                .app_data(oidc.clone())
+                .wrap(api::ProblemDetails)
+                .wrap(TracingLogger)
+                .wrap(cors.build())
+                .configure(api::configure(&config))
+                .configure(ui::configure(&config.ui))
+        })
+        .bind(format!("0.0.0.0:{}", port))?
+        .shutdown_timeout(shutdown_timeout.as_secs())
+        .disable_signals()
+        .run()
+    };
+
+    let metrics_server = match metrics_address {
+        Some(address) => {
+            info!("Serving metrics on {}", address);
+            let state = state.clone();
+            let server = HttpServer::new(move || {
+                App::new()
+                    .app_data(actix_web::web::Data::new(state.clone()))
+                    .configure(api::configure_metrics)
+            })
+            .bind(&address)?
+            .workers(1)
+            .disable_signals()
+            .run();
+            let handle = server.handle();
+            actix_rt::spawn(server);
+            Some(handle)
+        }
+        None => None,
+    };
+
+    // Stop accepting new connections once we are asked to shut down, giving the requests
+    // which are already in progress until the shutdown timeout to complete.
+    let handle = server.handle();
+    let events = state.events.clone();
+    actix_rt::spawn(async move {
+        shutdown::signal().await;
+        let _ = stop_grpc.send(());
+        // Event streams never finish on their own, so they are ended to let the server
+        // stop without waiting for the shutdown timeout.
+        events.close();
+        if let Some(metrics_server) = metrics_server {
+            metrics_server.stop(false).await;
+        }
+        handle.stop(true).await;
+    });
+
+    let result = server.await.map_err(|err| {
         error!("The server exited unexpectedly: {}", err);
         session.record_error(&err);
 
         err
-    })
+    });
+
+    shutdown::drain("gRPC server", shutdown_timeout, grpc).await;
+    shutdown::flush_store(&state, shutdown_timeout).await;
+
+    info!("Shutdown complete.");
+    session.shutdown();
+
+    result
 }
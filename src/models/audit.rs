@@ -0,0 +1,41 @@
+use crate::api::APIError;
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: u128,
+    pub principal_id: u128,
+    pub timestamp: DateTime<Utc>,
+    pub action: String,
+    pub target: String,
+}
+
+actor_message!(GetAuditEntries() -> Vec<AuditEntry>);
+
+actor_message!(StoreAuditEntry(id: u128, principal_id: u128, timestamp: DateTime<Utc>, action: String, target: String) -> AuditEntry);
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntryV3 {
+    pub id: String,
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub action: String,
+    pub target: String,
+}
+
+json_responder!(AuditEntryV3);
+
+impl From<AuditEntry> for AuditEntryV3 {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            id: format!("{:0>32x}", entry.id),
+            user_id: format!("{:0>32x}", entry.principal_id),
+            timestamp: entry.timestamp,
+            action: entry.action,
+            target: entry.target,
+        }
+    }
+}
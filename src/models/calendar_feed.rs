@@ -0,0 +1,45 @@
+use crate::api::APIError;
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// A revocable token which allows calendar clients to retrieve the ideas a user has
+/// scheduled without signing in as them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CalendarFeed {
+    pub id: u128,
+    pub principal_id: u128,
+    pub created_at: DateTime<Utc>,
+}
+
+actor_message!(GetCalendarFeed(principal_id: u128, id: u128) -> CalendarFeed);
+
+actor_message!(GetCalendarFeeds(principal_id: u128) -> Vec<CalendarFeed>);
+
+actor_message!(StoreCalendarFeed(principal_id: u128, id: u128, created_at: DateTime<Utc>) -> CalendarFeed);
+
+actor_message!(RemoveCalendarFeed(principal_id: u128, id: u128) -> ());
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CalendarFeedV3 {
+    pub id: Option<String>,
+    #[serde(rename = "userId")]
+    pub user_id: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+json_responder!(CalendarFeedV3 => (req, model) -> req.url_for("get_calendar_v3", vec![
+    model.user_id.clone().expect("a user id"),
+    model.id.clone().expect("a calendar feed id")
+]));
+
+impl From<CalendarFeed> for CalendarFeedV3 {
+    fn from(feed: CalendarFeed) -> Self {
+        Self {
+            id: Some(format!("{:0>32x}", feed.id)),
+            user_id: Some(format!("{:0>32x}", feed.principal_id)),
+            created_at: Some(feed.created_at),
+        }
+    }
+}
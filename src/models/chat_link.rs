@@ -0,0 +1,50 @@
+use crate::api::APIError;
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// Associates a user of a chat workspace with the Rex principal they act as when
+/// using chat commands.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ChatLink {
+    pub team_id: String,
+    pub user_id: String,
+    pub principal_id: u128,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Calculates the key used to identify a chat user, who is only unique within their workspace.
+pub fn chat_user_hash(team_id: &str, user_id: &str) -> u128 {
+    u128::from_be_bytes(md5::compute(format!("{team_id}:{user_id}").as_bytes()).into())
+}
+
+actor_message!(GetChatLink(chat_user_hash: u128) -> ChatLink);
+
+actor_message!(StoreChatLink(team_id: String, user_id: String, principal_id: u128, created_at: DateTime<Utc>) -> ChatLink);
+
+actor_message!(RemoveChatLink(chat_user_hash: u128) -> ());
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChatLinkV3 {
+    #[serde(rename = "teamId")]
+    pub team_id: String,
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    #[serde(rename = "principalId")]
+    pub principal_id: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: DateTime<Utc>,
+}
+
+json_responder!(ChatLinkV3);
+
+impl From<ChatLink> for ChatLinkV3 {
+    fn from(link: ChatLink) -> Self {
+        Self {
+            team_id: link.team_id,
+            user_id: link.user_id,
+            principal_id: format!("{:0>32x}", link.principal_id),
+            created_at: link.created_at,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use super::new_id;
 use crate::api::APIError;
 use actix::prelude::*;
+use utoipa::ToSchema;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Collection {
@@ -13,11 +14,13 @@ actor_message!(GetCollection(id: u128, principal_id: u128) -> Collection);
 
 actor_message!(GetCollections(principal_id: u128) -> Vec<Collection>);
 
+actor_message!(GetAllCollections() -> Vec<Collection>);
+
 actor_message!(StoreCollection(collection_id: u128, principal_id: u128, name: String) -> Collection);
 
 actor_message!(RemoveCollection(id: u128, principal_id: u128) -> ());
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct CollectionV3 {
     pub id: Option<String>,
     #[serde(rename = "userId")]
@@ -54,3 +57,13 @@ impl From<CollectionV3> for Collection {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CollectionSummaryV3 {
+    pub id: String,
+    pub name: String,
+    pub owners: Vec<String>,
+    pub ideas: usize,
+}
+
+json_responder!(CollectionSummaryV3);
@@ -0,0 +1,236 @@
+use super::{Idea, IdeaV3, RoleAssignment, RoleAssignmentV3};
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, watch};
+use utoipa::ToSchema;
+
+/// The number of recent events which are retained so that clients can resume a
+/// feed using the `Last-Event-ID` header after reconnecting.
+const EVENT_HISTORY_LENGTH: usize = 1000;
+
+#[derive(Clone, Debug)]
+pub struct CollectionEvent {
+    pub id: u64,
+    pub collection_id: u128,
+    pub timestamp: DateTime<Utc>,
+    pub kind: CollectionEventKind,
+}
+
+#[derive(Clone, Debug)]
+pub enum CollectionEventKind {
+    IdeaCreated(Idea),
+    IdeaUpdated(Idea),
+    IdeaCompleted(Idea),
+    IdeaRemoved(u128),
+    MemberAdded(RoleAssignment),
+    MemberRemoved(u128),
+}
+
+impl CollectionEventKind {
+    /// The names of every type of event which may be published for a collection.
+    pub const NAMES: [&'static str; 6] = [
+        "idea.created",
+        "idea.updated",
+        "idea.completed",
+        "idea.removed",
+        "member.added",
+        "member.removed",
+    ];
+
+    /// Determines which event describes storing `idea`, given the idea it replaced.
+    pub fn stored_idea(previous: Option<&Idea>, idea: Idea) -> Self {
+        match previous {
+            None => CollectionEventKind::IdeaCreated(idea),
+            Some(previous) if !previous.completed && idea.completed => {
+                CollectionEventKind::IdeaCompleted(idea)
+            }
+            Some(_) => CollectionEventKind::IdeaUpdated(idea),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CollectionEventKind::IdeaCreated(_) => "idea.created",
+            CollectionEventKind::IdeaUpdated(_) => "idea.updated",
+            CollectionEventKind::IdeaCompleted(_) => "idea.completed",
+            CollectionEventKind::IdeaRemoved(_) => "idea.removed",
+            CollectionEventKind::MemberAdded(_) => "member.added",
+            CollectionEventKind::MemberRemoved(_) => "member.removed",
+        }
+    }
+}
+
+/// Distributes the changes made to collections to anyone who is interested in them.
+///
+/// Events are published by the store once a mutation has succeeded and are only
+/// visible to subscribers within the same process.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<CollectionEvent>,
+    history: Arc<Mutex<EventHistory>>,
+    closed: Arc<watch::Sender<bool>>,
+}
+
+struct EventHistory {
+    last_id: u64,
+    events: VecDeque<CollectionEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_HISTORY_LENGTH);
+
+        Self {
+            sender,
+            history: Arc::new(Mutex::new(EventHistory {
+                // Starting from the current time keeps event IDs increasing across restarts,
+                // so that clients resuming with an ID from a previous run are not left behind.
+                last_id: Utc::now().timestamp_micros().max(0) as u64,
+                events: VecDeque::with_capacity(EVENT_HISTORY_LENGTH),
+            })),
+            closed: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Tells the clients which are following the feed that it has ended, so that their
+    /// connections do not hold up a graceful shutdown.
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+    }
+
+    /// Watches for the feed being closed by [`EventBus::close`].
+    pub fn closed(&self) -> watch::Receiver<bool> {
+        self.closed.subscribe()
+    }
+
+    pub fn publish(&self, collection_id: u128, kind: CollectionEventKind) {
+        let Ok(mut history) = self.history.lock() else {
+            error!("The event history lock has been poisoned, dropping event.");
+            return;
+        };
+
+        history.last_id += 1;
+        let event = CollectionEvent {
+            id: history.last_id,
+            collection_id,
+            timestamp: Utc::now(),
+            kind,
+        };
+
+        if history.events.len() == EVENT_HISTORY_LENGTH {
+            history.events.pop_front();
+        }
+        history.events.push_back(event.clone());
+
+        // Sending while holding the history lock guarantees that subscribers see
+        // every event exactly once, either in their replayed history or live.
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to all future events, returning the retained events for the collection
+    /// which were published after `last_event_id` alongside the live receiver.
+    pub fn subscribe(
+        &self,
+        collection_id: u128,
+        last_event_id: Option<u64>,
+    ) -> (Vec<CollectionEvent>, broadcast::Receiver<CollectionEvent>) {
+        let history = self
+            .history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let receiver = self.sender.subscribe();
+        let replay = last_event_id
+            .map(|last_id| Self::filter(&history, collection_id, last_id))
+            .unwrap_or_default();
+
+        (replay, receiver)
+    }
+
+    /// Subscribes to all future events, regardless of the collection they were published for.
+    pub fn receiver(&self) -> broadcast::Receiver<CollectionEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Retrieves the retained events for a collection which were published after `last_event_id`.
+    pub fn since(&self, collection_id: u128, last_event_id: u64) -> Vec<CollectionEvent> {
+        let history = self
+            .history
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        Self::filter(&history, collection_id, last_event_id)
+    }
+
+    fn filter(
+        history: &EventHistory,
+        collection_id: u128,
+        last_event_id: u64,
+    ) -> Vec<CollectionEvent> {
+        history
+            .events
+            .iter()
+            .filter(|e| e.collection_id == collection_id && e.id > last_event_id)
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CollectionEventV3 {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub collection: String,
+    pub timestamp: DateTime<Utc>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idea: Option<IdeaV3>,
+    #[serde(rename = "ideaId", default, skip_serializing_if = "Option::is_none")]
+    pub idea_id: Option<String>,
+    #[serde(
+        rename = "roleAssignment",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub role_assignment: Option<RoleAssignmentV3>,
+    #[serde(rename = "userId", default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+impl From<CollectionEvent> for CollectionEventV3 {
+    fn from(event: CollectionEvent) -> Self {
+        let mut model = Self {
+            id: event.id.to_string(),
+            event_type: event.kind.name().to_string(),
+            collection: format!("{:0>32x}", event.collection_id),
+            timestamp: event.timestamp,
+            idea: None,
+            idea_id: None,
+            role_assignment: None,
+            user_id: None,
+        };
+
+        match event.kind {
+            CollectionEventKind::IdeaCreated(idea)
+            | CollectionEventKind::IdeaUpdated(idea)
+            | CollectionEventKind::IdeaCompleted(idea) => {
+                model.idea_id = Some(format!("{:0>32x}", idea.id));
+                model.idea = Some(idea.into());
+            }
+            CollectionEventKind::IdeaRemoved(id) => {
+                model.idea_id = Some(format!("{id:0>32x}"));
+            }
+            CollectionEventKind::MemberAdded(role) => {
+                model.user_id = Some(format!("{:0>32x}", role.user_id));
+                model.role_assignment = Some(role.into());
+            }
+            CollectionEventKind::MemberRemoved(user_id) => {
+                model.user_id = Some(format!("{user_id:0>32x}"));
+            }
+        }
+
+        model
+    }
+}
@@ -1,5 +1,7 @@
 use crate::api::APIError;
 use actix::prelude::*;
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
 
 #[derive(Clone, Copy)]
 pub struct Health {
@@ -9,7 +11,33 @@ pub struct Health {
 
 actor_message!(GetHealth() -> Health);
 
-#[derive(Serialize, Deserialize)]
+// Waits for the store to finish any operations which are still in progress, which is
+// used to avoid losing writes when the service shuts down.
+actor_message!(FlushStore() -> ());
+
+/// The number of entities in a table which were stored with an older version of its schema.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaUpgrade {
+    pub table: String,
+    pub scanned: usize,
+    pub upgraded: usize,
+}
+
+/// Rewrites any entities which were stored with an older version of their schema, so that
+/// they no longer need to be upgraded each time they are read.
+#[derive(Debug, Default)]
+pub struct UpgradeSchema {
+    /// Counts the entities which would be upgraded without rewriting them. Only the Table
+    /// Storage backend holds entities which outlive the process, so nothing else reads it.
+    #[cfg_attr(any(test, not(feature = "table_storage")), allow(dead_code))]
+    pub dry_run: bool,
+}
+
+impl Message for UpgradeSchema {
+    type Result = Result<Vec<SchemaUpgrade>, APIError>;
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct HealthV1 {
     pub ok: bool,
 }
@@ -31,7 +59,7 @@ impl From<HealthV1> for Health {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct HealthV2 {
     pub ok: bool,
     pub started_at: chrono::DateTime<chrono::Utc>,
@@ -56,3 +84,45 @@ impl From<HealthV2> for Health {
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct HealthV3 {
+    pub ok: bool,
+    #[serde(rename = "startedAt")]
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub components: BTreeMap<String, ComponentHealthV3>,
+}
+
+impl actix_web::Responder for HealthV3 {
+    type Body = actix_web::body::BoxBody;
+
+    #[tracing::instrument(target="response.render", fields(http.content_type = "application/json"), skip(self, _req))]
+    fn respond_to(self, _req: &actix_web::HttpRequest) -> actix_web::HttpResponse<Self::Body> {
+        if self.ok {
+            actix_web::HttpResponse::Ok()
+        } else {
+            actix_web::HttpResponse::ServiceUnavailable()
+        }
+        .content_type("application/json")
+        .json(&self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentStatus {
+    Healthy,
+    Unhealthy,
+    Disabled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ComponentHealthV3 {
+    pub status: ComponentStatus,
+    /// Whether Rex is unable to serve requests while this component is unhealthy.
+    pub critical: bool,
+    #[serde(rename = "latencyMs", skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
@@ -1,9 +1,11 @@
 use super::new_id;
 use crate::api::APIError;
 use actix::prelude::*;
+use chrono::{DateTime, Utc};
 use std::collections::HashSet;
+use utoipa::ToSchema;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Idea {
     pub id: u128,
     pub collection_id: u128,
@@ -11,19 +13,43 @@ pub struct Idea {
     pub description: String,
     pub tags: HashSet<String>,
     pub completed: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl Idea {
+    /// Records when this idea was first stored and when it was completed, carrying the
+    /// times forward from the previously stored version of the idea where they apply.
+    pub fn timestamped(mut self, previous: Option<&Idea>, now: DateTime<Utc>) -> Self {
+        self.created_at = previous.and_then(|p| p.created_at).or(Some(now));
+        self.completed_at = match previous {
+            _ if !self.completed => None,
+            Some(p) if p.completed => p.completed_at,
+            _ => Some(now),
+        };
+
+        self
+    }
 }
 
 actor_message!(GetIdea(id: u128, collection: u128) -> Idea);
 
 actor_message!(GetIdeas(collection: u128, tag: Option<String>, is_completed: Option<bool>) -> Vec<Idea>);
 
+// Counts the ideas in a collection, which is zero when the collection has none.
+actor_message!(CountIdeas(collection: u128) -> usize);
+
 actor_message!(GetRandomIdea(collection: u128, tag: Option<String>, is_completed: Option<bool>) -> Idea);
 
 actor_message!(StoreIdea(id: u128, collection: u128, name: String, description: String, tags: HashSet<String>, completed: bool) -> Idea);
 
 actor_message!(RemoveIdea(id: u128, collection: u128) -> ());
 
-#[derive(Debug, Serialize, Deserialize)]
+// Writes an idea exactly as it is provided, including its timestamps and without publishing
+// an event, which is used to copy ideas from another store.
+actor_message!(RestoreIdea(idea: Idea) -> Idea);
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct IdeaV1 {
     pub id: Option<String>,
     pub name: String,
@@ -55,11 +81,13 @@ impl From<IdeaV1> for Idea {
             description: val.description,
             tags: HashSet::new(),
             completed: false,
+            created_at: None,
+            completed_at: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct IdeaV2 {
     pub id: Option<String>,
     pub name: String,
@@ -99,11 +127,13 @@ impl From<IdeaV2> for Idea {
             description: val.description.clone(),
             tags: val.tags.clone().unwrap_or_default(),
             completed: val.completed.unwrap_or(false),
+            created_at: None,
+            completed_at: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct IdeaV3 {
     pub collection: Option<String>,
     pub id: Option<String>,
@@ -156,6 +186,49 @@ impl From<IdeaV3> for Idea {
             description: val.description.clone(),
             tags: val.tags.clone().unwrap_or_default(),
             completed: val.completed.unwrap_or(false),
+            created_at: None,
+            completed_at: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn idea(completed: bool) -> Idea {
+        Idea {
+            id: 1,
+            collection_id: 1,
+            name: "Go hiking".into(),
+            description: String::new(),
+            tags: HashSet::new(),
+            completed,
+            created_at: None,
+            completed_at: None,
         }
     }
+
+    #[test]
+    fn timestamped() {
+        let created = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let completed = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+
+        let new = idea(false).timestamped(None, created);
+        assert_eq!(new.created_at, Some(created));
+        assert_eq!(new.completed_at, None);
+
+        let done = idea(true).timestamped(Some(&new), completed);
+        assert_eq!(done.created_at, Some(created));
+        assert_eq!(done.completed_at, Some(completed));
+
+        let updated = idea(true).timestamped(Some(&done), now);
+        assert_eq!(updated.completed_at, Some(completed));
+
+        let reopened = idea(false).timestamped(Some(&updated), now);
+        assert_eq!(reopened.created_at, Some(created));
+        assert_eq!(reopened.completed_at, None);
+    }
 }
@@ -0,0 +1,51 @@
+use super::Role;
+use crate::api::APIError;
+use actix::prelude::*;
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Invitation {
+    pub collection_id: u128,
+    pub email_hash: u128,
+    pub role: Role,
+    pub invited_by: u128,
+}
+
+actor_message!(GetInvitation(collection_id: u128, email_hash: u128) -> Invitation);
+
+actor_message!(GetInvitations(collection_id: u128) -> Vec<Invitation>);
+
+actor_message!(GetUserInvitations(email_hash: u128) -> Vec<Invitation>);
+
+actor_message!(StoreInvitation(collection_id: u128, email_hash: u128, role: Role, principal_id: u128) -> Invitation);
+
+actor_message!(RemoveInvitation(collection_id: u128, email_hash: u128) -> ());
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvitationV3 {
+    #[serde(rename = "collectionId")]
+    pub collection_id: Option<String>,
+    pub email: Option<String>,
+    #[serde(rename = "emailHash")]
+    pub email_hash: Option<String>,
+    pub role: String,
+    #[serde(rename = "invitedBy")]
+    pub invited_by: Option<String>,
+}
+
+json_responder!(InvitationV3 => (req, model) -> req.url_for("get_invitation_v3", vec![
+    model.collection_id.clone().expect("a collection id"),
+    model.email_hash.clone().expect("an email hash")
+]));
+
+impl From<Invitation> for InvitationV3 {
+    fn from(invitation: Invitation) -> Self {
+        Self {
+            collection_id: Some(format!("{:0>32x}", invitation.collection_id)),
+            email: None,
+            email_hash: Some(format!("{:0>32x}", invitation.email_hash)),
+            role: invitation.role.into(),
+            invited_by: Some(format!("{:0>32x}", invitation.invited_by)),
+        }
+    }
+}
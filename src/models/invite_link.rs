@@ -0,0 +1,84 @@
+use super::Role;
+use crate::api::APIError;
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InviteLink {
+    pub id: u128,
+    pub collection_id: u128,
+    pub role: Role,
+    pub max_uses: u32,
+    pub uses: u32,
+    pub expires_at: DateTime<Utc>,
+    pub created_by: u128,
+}
+
+impl InviteLink {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.uses >= self.max_uses
+    }
+
+    /// Records a use of the link, failing if it has expired or been used up.
+    pub fn redeem(&mut self) -> Result<(), APIError> {
+        if self.is_expired() || self.is_exhausted() {
+            return Err(APIError::new(
+                410,
+                "Gone",
+                "This invite link has expired. Please ask the collection owner for a new one.",
+            ));
+        }
+
+        self.uses += 1;
+        Ok(())
+    }
+}
+
+actor_message!(GetInviteLink(collection_id: u128, id: u128) -> InviteLink);
+
+actor_message!(GetInviteLinks(collection_id: u128) -> Vec<InviteLink>);
+
+actor_message!(StoreInviteLink(collection_id: u128, id: u128, role: Role, max_uses: u32, uses: u32, expires_at: DateTime<Utc>, principal_id: u128) -> InviteLink);
+
+actor_message!(RemoveInviteLink(collection_id: u128, id: u128) -> ());
+
+// Records a use of an invite link as a single operation in the store, so that concurrent
+// redemptions cannot use it more times than it allows.
+actor_message!(RedeemInviteLink(collection_id: u128, id: u128) -> InviteLink);
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InviteLinkV3 {
+    pub id: Option<String>,
+    #[serde(rename = "collectionId")]
+    pub collection_id: Option<String>,
+    pub role: String,
+    #[serde(rename = "maxUses")]
+    pub max_uses: Option<u32>,
+    pub uses: Option<u32>,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+    pub token: Option<String>,
+}
+
+json_responder!(InviteLinkV3 => (req, model) -> req.url_for("get_invite_link_v3", vec![
+    model.token.clone().expect("a token")
+]));
+
+impl From<InviteLink> for InviteLinkV3 {
+    fn from(link: InviteLink) -> Self {
+        Self {
+            id: Some(format!("{:0>32x}", link.id)),
+            collection_id: Some(format!("{:0>32x}", link.collection_id)),
+            role: link.role.into(),
+            max_uses: Some(link.max_uses),
+            uses: Some(link.uses),
+            expires_at: Some(link.expires_at),
+            token: None,
+        }
+    }
+}
@@ -1,19 +1,41 @@
 #[macro_use]
 mod macros;
 
+mod audit;
+mod calendar_feed;
+mod chat_link;
 mod collection;
+mod event;
 mod health;
 mod idea;
+mod invitation;
+mod invite_link;
+mod policy;
 mod role_assignment;
+mod schedule;
+mod share_link;
 mod user;
+mod validation;
+mod webhook;
 
 use actix::prelude::*;
 
+pub use audit::*;
+pub use calendar_feed::*;
+pub use chat_link::*;
 pub use collection::*;
+pub use event::*;
 pub use health::*;
 pub use idea::*;
+pub use invitation::*;
+pub use invite_link::*;
+pub use policy::*;
 pub use role_assignment::*;
+pub use schedule::*;
+pub use share_link::*;
 pub use user::*;
+pub use validation::*;
+pub use webhook::*;
 
 pub fn new_id() -> u128 {
     let id = uuid::Uuid::new_v4();
@@ -23,12 +45,16 @@ pub fn new_id() -> u128 {
 #[derive(Clone)]
 pub struct GlobalState {
     pub store: Addr<crate::store::Store>,
+    pub events: EventBus,
 }
 
 impl GlobalState {
-    pub fn new() -> Self {
+    pub fn new(config: &crate::store::StoreConfig) -> Self {
+        let events = EventBus::new();
+
         Self {
-            store: crate::store::Store::new().start(),
+            store: crate::store::Store::new(events.clone(), config).start(),
+            events,
         }
     }
 }
@@ -0,0 +1,152 @@
+use super::Role;
+use std::fmt;
+
+/// An action which a principal may attempt to perform against a collection.
+///
+/// Handlers should ask the policy whether a principal's [`Role`] grants the
+/// relevant permission rather than matching on roles directly, so that the
+/// mapping between roles and actions lives in a single place.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Permission {
+    CollectionRead,
+    CollectionUpdate,
+
+    IdeaRead,
+    IdeaCreate,
+    IdeaUpdate,
+    IdeaComplete,
+    IdeaRemove,
+
+    MemberList,
+    MemberInvite,
+    MemberRemove,
+
+    ShareManage,
+    WebhookManage,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 12] = [
+        Permission::CollectionRead,
+        Permission::CollectionUpdate,
+        Permission::IdeaRead,
+        Permission::IdeaCreate,
+        Permission::IdeaUpdate,
+        Permission::IdeaComplete,
+        Permission::IdeaRemove,
+        Permission::MemberList,
+        Permission::MemberInvite,
+        Permission::MemberRemove,
+        Permission::ShareManage,
+        Permission::WebhookManage,
+    ];
+
+    /// A human readable description of the action, suitable for completing the
+    /// sentence "You do not have permission to ...".
+    pub fn description(&self) -> &'static str {
+        match self {
+            Permission::CollectionRead => "view this collection",
+            Permission::CollectionUpdate => "modify this collection",
+            Permission::IdeaRead => "view the ideas within this collection",
+            Permission::IdeaCreate => "add an idea to this collection",
+            Permission::IdeaUpdate => "modify an idea within this collection",
+            Permission::IdeaComplete => "mark an idea within this collection as completed",
+            Permission::IdeaRemove => "remove an idea from this collection",
+            Permission::MemberList | Permission::MemberInvite | Permission::MemberRemove => {
+                "view or manage the list of users for this collection"
+            }
+            Permission::ShareManage => "view or manage the public share links for this collection",
+            Permission::WebhookManage => "view or manage the webhooks for this collection",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Permission::CollectionRead => "Collection.Read",
+            Permission::CollectionUpdate => "Collection.Update",
+            Permission::IdeaRead => "Idea.Read",
+            Permission::IdeaCreate => "Idea.Create",
+            Permission::IdeaUpdate => "Idea.Update",
+            Permission::IdeaComplete => "Idea.Complete",
+            Permission::IdeaRemove => "Idea.Remove",
+            Permission::MemberList => "Member.List",
+            Permission::MemberInvite => "Member.Invite",
+            Permission::MemberRemove => "Member.Remove",
+            Permission::ShareManage => "Share.Manage",
+            Permission::WebhookManage => "Webhook.Manage",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl Role {
+    /// Determines whether this role grants the provided permission on the
+    /// collection it was assigned for.
+    pub fn can(&self, permission: Permission) -> bool {
+        use Permission::*;
+
+        match self {
+            Role::Owner => true,
+            Role::Contributor => matches!(
+                permission,
+                CollectionRead | IdeaRead | IdeaCreate | IdeaUpdate | IdeaComplete | IdeaRemove
+            ),
+            Role::Viewer => matches!(permission, CollectionRead | IdeaRead),
+            Role::Invalid => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROLES: [Role; 4] = [Role::Owner, Role::Contributor, Role::Viewer, Role::Invalid];
+
+    #[test]
+    fn permission_matrix() {
+        use Permission::*;
+
+        #[rustfmt::skip]
+        let matrix: Vec<(Permission, [bool; 4])> = vec![
+            //                    Owner  Contrib Viewer Invalid
+            (CollectionRead,     [true,  true,   true,  false]),
+            (CollectionUpdate,   [true,  false,  false, false]),
+            (IdeaRead,           [true,  true,   true,  false]),
+            (IdeaCreate,         [true,  true,   false, false]),
+            (IdeaUpdate,         [true,  true,   false, false]),
+            (IdeaComplete,       [true,  true,   false, false]),
+            (IdeaRemove,         [true,  true,   false, false]),
+            (MemberList,         [true,  false,  false, false]),
+            (MemberInvite,       [true,  false,  false, false]),
+            (MemberRemove,       [true,  false,  false, false]),
+            (ShareManage,        [true,  false,  false, false]),
+            (WebhookManage,      [true,  false,  false, false]),
+        ];
+
+        assert_eq!(
+            matrix.len(),
+            Permission::ALL.len(),
+            "every permission should appear in the policy matrix"
+        );
+
+        for permission in Permission::ALL {
+            let (_, expected) = matrix
+                .iter()
+                .find(|(p, _)| *p == permission)
+                .unwrap_or_else(|| panic!("{permission} is missing from the policy matrix"));
+
+            for (role, allowed) in ROLES.iter().zip(expected.iter()) {
+                assert_eq!(
+                    role.can(permission),
+                    *allowed,
+                    "{role:?} should {}be granted {permission}",
+                    if *allowed { "" } else { "not " }
+                );
+            }
+        }
+    }
+}
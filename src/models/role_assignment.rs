@@ -1,5 +1,6 @@
 use crate::api::APIError;
 use actix::prelude::*;
+use utoipa::ToSchema;
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum Role {
@@ -43,11 +44,14 @@ actor_message!(GetRoleAssignment(collection_id: u128, principal_id: u128) -> Rol
 
 actor_message!(GetRoleAssignments(collection_id: u128) -> Vec<RoleAssignment>);
 
+// Lists the role assignments which a principal holds across every collection.
+actor_message!(GetPrincipalRoleAssignments(principal_id: u128) -> Vec<RoleAssignment>);
+
 actor_message!(StoreRoleAssignment(collection_id: u128, principal_id: u128, role: Role) -> RoleAssignment);
 
 actor_message!(RemoveRoleAssignment(collection_id: u128, principal_id: u128) -> ());
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct RoleAssignmentV3 {
     #[serde(rename = "collectionId")]
     pub collection_id: Option<String>,
@@ -0,0 +1,48 @@
+use crate::api::APIError;
+use actix::prelude::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use utoipa::ToSchema;
+
+/// Records the date on which the members of a collection plan to do one of its ideas.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IdeaSchedule {
+    pub collection_id: u128,
+    pub idea_id: u128,
+    pub date: NaiveDate,
+    pub scheduled_by: u128,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+actor_message!(GetIdeaSchedule(collection_id: u128, idea_id: u128) -> IdeaSchedule);
+
+actor_message!(GetIdeaSchedules(collection_id: u128) -> Vec<IdeaSchedule>);
+
+actor_message!(StoreIdeaSchedule(collection_id: u128, idea_id: u128, date: NaiveDate, principal_id: u128, scheduled_at: DateTime<Utc>) -> IdeaSchedule);
+
+actor_message!(RemoveIdeaSchedule(collection_id: u128, idea_id: u128) -> ());
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct IdeaScheduleV3 {
+    pub collection: Option<String>,
+    pub idea: Option<String>,
+    /// The date on which the idea will be done, which defaults to today when an idea has just been picked.
+    pub date: Option<NaiveDate>,
+    #[serde(rename = "scheduledBy")]
+    pub scheduled_by: Option<String>,
+    #[serde(rename = "scheduledAt")]
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+json_responder!(IdeaScheduleV3);
+
+impl From<IdeaSchedule> for IdeaScheduleV3 {
+    fn from(schedule: IdeaSchedule) -> Self {
+        Self {
+            collection: Some(format!("{:0>32x}", schedule.collection_id)),
+            idea: Some(format!("{:0>32x}", schedule.idea_id)),
+            date: Some(schedule.date),
+            scheduled_by: Some(format!("{:0>32x}", schedule.scheduled_by)),
+            scheduled_at: Some(schedule.scheduled_at),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::api::APIError;
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ShareLink {
+    pub id: u128,
+    pub collection_id: u128,
+    pub created_by: u128,
+    pub created_at: DateTime<Utc>,
+}
+
+actor_message!(GetShareLink(collection_id: u128, id: u128) -> ShareLink);
+
+actor_message!(GetShareLinks(collection_id: u128) -> Vec<ShareLink>);
+
+actor_message!(StoreShareLink(collection_id: u128, id: u128, principal_id: u128, created_at: DateTime<Utc>) -> ShareLink);
+
+actor_message!(RemoveShareLink(collection_id: u128, id: u128) -> ());
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ShareLinkV3 {
+    pub id: Option<String>,
+    #[serde(rename = "collectionId")]
+    pub collection_id: Option<String>,
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+json_responder!(ShareLinkV3 => (req, model) -> req.url_for("get_shared_ideas_v3", vec![
+    model.collection_id.clone().expect("a collection id"),
+    model.id.clone().expect("a share link id")
+]));
+
+impl From<ShareLink> for ShareLinkV3 {
+    fn from(link: ShareLink) -> Self {
+        Self {
+            id: Some(format!("{:0>32x}", link.id)),
+            collection_id: Some(format!("{:0>32x}", link.collection_id)),
+            created_by: Some(format!("{:0>32x}", link.created_by)),
+            created_at: Some(link.created_at),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::api::APIError;
 use actix::prelude::*;
+use utoipa::ToSchema;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct User {
@@ -8,11 +9,20 @@ pub struct User {
     pub first_name: String,
 }
 
+/// Calculates the hash used to identify a user by their email address without storing it.
+pub fn email_hash(email: &str) -> u128 {
+    u128::from_be_bytes(md5::compute(email.to_lowercase().trim().as_bytes()).into())
+}
+
 actor_message!(GetUser(email_hash: u128) -> User);
 
+actor_message!(GetUsers() -> Vec<User>);
+
 actor_message!(StoreUser(email_hash: u128, principal_id: u128, first_name: String) -> User);
 
-#[derive(Debug, Serialize, Deserialize)]
+actor_message!(RemoveUser(email_hash: u128) -> ());
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserV3 {
     pub id: String,
 
@@ -0,0 +1,347 @@
+use super::{CollectionEventKind, CollectionV3, Idea, WebhookV3, is_public_address};
+use crate::api::{APIError, ErrorKind};
+
+pub const MAX_NAME_LENGTH: usize = 100;
+pub const MAX_DESCRIPTION_LENGTH: usize = 2000;
+pub const MAX_TAGS: usize = 20;
+pub const MAX_TAG_LENGTH: usize = 32;
+pub const MAX_URL_LENGTH: usize = 2000;
+
+/// Checks that a request body is acceptable before it is written to the store.
+///
+/// Validation is applied to the internal models which every API version converts
+/// into, ensuring that the same limits are enforced regardless of the version a
+/// client is using.
+pub trait Validate {
+    fn validate(&self) -> Result<(), APIError>;
+}
+
+impl Validate for Idea {
+    fn validate(&self) -> Result<(), APIError> {
+        let mut errors = Validator::default();
+
+        errors.name("name", &self.name);
+
+        if self.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            errors.add(
+                "description",
+                &format!(
+                    "The description must not be longer than {MAX_DESCRIPTION_LENGTH} characters."
+                ),
+            );
+        }
+
+        if self.tags.len() > MAX_TAGS {
+            errors.add(
+                "tags",
+                &format!("An idea must not have more than {MAX_TAGS} tags."),
+            );
+        }
+
+        if let Some(tag) = self.tags.iter().find(|t| !is_valid_tag(t)) {
+            errors.add(
+                "tags",
+                &format!(
+                    "The tag '{tag}' is invalid. Tags must be between 1 and {MAX_TAG_LENGTH} characters long and may only contain letters, numbers, spaces, dashes and underscores."
+                ),
+            );
+        }
+
+        errors.finish("idea")
+    }
+}
+
+impl Validate for CollectionV3 {
+    fn validate(&self) -> Result<(), APIError> {
+        let mut errors = Validator::default();
+
+        errors.name("name", &self.name);
+
+        errors.finish("collection")
+    }
+}
+
+impl Validate for WebhookV3 {
+    fn validate(&self) -> Result<(), APIError> {
+        let mut errors = Validator::default();
+
+        match reqwest::Url::parse(&self.url) {
+            _ if self.url.len() > MAX_URL_LENGTH => errors.add(
+                "url",
+                &format!("The URL must not be longer than {MAX_URL_LENGTH} characters."),
+            ),
+            Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => {}
+            _ => errors.add("url", "The URL must be an absolute http or https URL."),
+        }
+
+        if self.secret.as_deref().unwrap_or_default().is_empty() {
+            errors.add(
+                "secret",
+                "A secret must be provided to sign deliveries with.",
+            );
+        }
+
+        if let Some(event) = self
+            .events
+            .iter()
+            .find(|e| !CollectionEventKind::NAMES.contains(&e.as_str()))
+        {
+            errors.add(
+                "events",
+                &format!(
+                    "The event type '{event}' is not recognized. Events must be one of {}.",
+                    CollectionEventKind::NAMES.join(", ")
+                ),
+            );
+        }
+
+        errors.finish("webhook")
+    }
+}
+
+const PRIVATE_URL: &str =
+    "The URL must not refer to a loopback, link-local or private network address.";
+
+/// Checks that a webhook's URL does not refer to, or resolve to, an address which is not
+/// public, unless `allow_private_addresses` is set. This is not part of [`Validate`] since
+/// it depends on the instance's configuration and needs a DNS lookup. Hosts which cannot be
+/// resolved are accepted, since the address a delivery is sent to is checked again when it
+/// is made.
+pub async fn validate_webhook_target(
+    url: &str,
+    allow_private_addresses: bool,
+) -> Result<(), APIError> {
+    if allow_private_addresses {
+        return Ok(());
+    }
+
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return Ok(());
+    };
+
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Ok(());
+    };
+
+    let private = match literal_address(host) {
+        Some(ip) => !is_public_address(ip),
+        None => {
+            let name = host.trim_end_matches('.').to_ascii_lowercase();
+            name == "localhost"
+                || name.ends_with(".localhost")
+                || tokio::net::lookup_host((host, port))
+                    .await
+                    .is_ok_and(|mut addrs| addrs.any(|addr| !is_public_address(addr.ip())))
+        }
+    };
+
+    let mut errors = Validator::default();
+    if private {
+        errors.add("url", PRIVATE_URL);
+    }
+
+    errors.finish("webhook")
+}
+
+/// Parses the host of a URL when it is an IP address rather than a domain name, in which
+/// case it is used without being resolved.
+pub fn literal_address(host: &str) -> Option<std::net::IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+fn is_valid_tag(tag: &str) -> bool {
+    let length = tag.chars().count();
+
+    (1..=MAX_TAG_LENGTH).contains(&length)
+        && tag.trim() == tag
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+#[derive(Default)]
+struct Validator {
+    fields: Vec<(String, String)>,
+}
+
+impl Validator {
+    fn add(&mut self, field: &str, message: &str) {
+        self.fields.push((field.to_string(), message.to_string()));
+    }
+
+    fn name(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, "The name must not be empty.");
+        } else if value.chars().count() > MAX_NAME_LENGTH {
+            self.add(
+                field,
+                &format!("The name must not be longer than {MAX_NAME_LENGTH} characters."),
+            );
+        }
+    }
+
+    fn finish(self, kind: &str) -> Result<(), APIError> {
+        if self.fields.is_empty() {
+            return Ok(());
+        }
+
+        let summary = self
+            .fields
+            .iter()
+            .map(|(_, message)| message.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Err(self.fields.iter().fold(
+            APIError::new(
+                422,
+                "Unprocessable Entity",
+                &format!("The {kind} you provided is not valid. {summary}"),
+            )
+            .with_kind(ErrorKind::Validation),
+            |err, (field, message)| err.with_field(field, message),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn idea(name: &str, description: &str, tags: &[&str]) -> Idea {
+        Idea {
+            id: 1,
+            collection_id: 1,
+            name: name.into(),
+            description: description.into(),
+            tags: tags.iter().map(|t| t.to_string()).collect::<HashSet<_>>(),
+            completed: false,
+            created_at: None,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn valid_idea() {
+        idea(
+            "Go for a walk",
+            "Somewhere nice",
+            &["outdoors", "low-cost", "date night"],
+        )
+        .validate()
+        .expect("the idea should be valid");
+    }
+
+    #[test]
+    fn invalid_ideas() {
+        let long_name = "x".repeat(MAX_NAME_LENGTH + 1);
+        let long_description = "x".repeat(MAX_DESCRIPTION_LENGTH + 1);
+        let long_tag = "x".repeat(MAX_TAG_LENGTH + 1);
+        let many_tags: Vec<String> = (0..=MAX_TAGS).map(|i| format!("tag{i}")).collect();
+        let many_tags: Vec<&str> = many_tags.iter().map(|t| t.as_str()).collect();
+
+        let cases: Vec<(Idea, &str)> = vec![
+            (idea("", "", &[]), "name"),
+            (idea("   ", "", &[]), "name"),
+            (idea(&long_name, "", &[]), "name"),
+            (idea("Test", &long_description, &[]), "description"),
+            (idea("Test", "", &many_tags), "tags"),
+            (idea("Test", "", &["a,b"]), "tags"),
+            (idea("Test", "", &[""]), "tags"),
+            (idea("Test", "", &[&long_tag]), "tags"),
+        ];
+
+        for (idea, field) in cases {
+            let err = idea.validate().expect_err("the idea should be invalid");
+            assert_eq!(err.code, 422);
+            assert!(
+                err.fields.iter().any(|f| f.field == field),
+                "expected an error for the {field} field, got {:?}",
+                err.fields
+            );
+        }
+    }
+
+    fn webhook(url: &str, secret: Option<&str>, events: &[&str]) -> WebhookV3 {
+        WebhookV3 {
+            id: None,
+            collection_id: None,
+            url: url.into(),
+            secret: secret.map(|s| s.into()),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            enabled: None,
+            failures: None,
+            created_by: None,
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn valid_webhook() {
+        webhook(
+            "https://example.com/hooks/rex",
+            Some("secret"),
+            &["idea.created", "member.added"],
+        )
+        .validate()
+        .expect("the webhook should be valid");
+    }
+
+    #[test]
+    fn invalid_webhooks() {
+        let cases: Vec<(WebhookV3, &str)> = vec![
+            (webhook("", Some("secret"), &[]), "url"),
+            (webhook("/hooks/rex", Some("secret"), &[]), "url"),
+            (webhook("ftp://example.com/rex", Some("secret"), &[]), "url"),
+            (webhook("https://example.com", None, &[]), "secret"),
+            (webhook("https://example.com", Some(""), &[]), "secret"),
+            (
+                webhook("https://example.com", Some("secret"), &["idea.exploded"]),
+                "events",
+            ),
+        ];
+
+        for (webhook, field) in cases {
+            let err = webhook
+                .validate()
+                .expect_err("the webhook should be invalid");
+            assert_eq!(err.code, 422);
+            assert!(
+                err.fields.iter().any(|f| f.field == field),
+                "expected an error for the {field} field, got {:?}",
+                err.fields
+            );
+        }
+    }
+
+    #[actix_rt::test]
+    async fn webhook_targets() {
+        for url in [
+            "http://127.0.0.1:8080/rex",
+            "http://[::1]/rex",
+            "http://169.254.169.254/latest",
+            "http://10.0.0.5/rex",
+            "http://[::ffff:192.168.1.1]/rex",
+            "http://localhost:8000/rex",
+            "http://localhost/hooks/rex",
+        ] {
+            let err = validate_webhook_target(url, false)
+                .await
+                .expect_err("a private address should be rejected");
+            assert_eq!(err.code, 422, "{url}");
+            assert!(err.fields.iter().any(|f| f.field == "url"), "{url}");
+
+            validate_webhook_target(url, true)
+                .await
+                .expect("private addresses should be accepted when they are allowed");
+        }
+
+        validate_webhook_target("http://8.8.8.8/hooks/rex", false)
+            .await
+            .expect("a public address should be accepted");
+    }
+}
@@ -0,0 +1,225 @@
+use crate::api::APIError;
+use actix::prelude::*;
+use chrono::{DateTime, Utc};
+use std::net::{IpAddr, Ipv4Addr};
+use utoipa::ToSchema;
+
+/// The number of deliveries which are retained for each webhook.
+pub const WEBHOOK_DELIVERY_HISTORY: usize = 100;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: u128,
+    pub collection_id: u128,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub failures: u32,
+    pub created_by: u128,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    /// Determines whether this webhook should be notified of an event, an empty
+    /// list of events subscribing it to every event for the collection.
+    pub fn wants(&self, event_type: &str) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.iter().any(|e| e == event_type))
+    }
+}
+
+/// Determines whether an address is on the public internet. Webhooks may only be delivered
+/// to public addresses, preventing them from being used to reach the host Rex runs on or
+/// the private network around it.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_ipv4(ip);
+            }
+
+            let segments = ip.segments();
+            // The NAT64 prefix embeds an IPv4 address in its last 32 bits.
+            if segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                let [.., high, low] = segments;
+                return is_public_ipv4(Ipv4Addr::from(((high as u32) << 16) | low as u32));
+            }
+
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()
+                || segments[..2] == [0x2001, 0xdb8])
+        }
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || a >= 240
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (18..20).contains(&b)))
+}
+
+actor_message!(GetWebhook(collection_id: u128, id: u128) -> Webhook);
+
+actor_message!(GetWebhooks(collection_id: u128) -> Vec<Webhook>);
+
+actor_message!(StoreWebhook(collection_id: u128, id: u128, url: String, secret: String, events: Vec<String>, enabled: bool, failures: u32, principal_id: u128, created_at: DateTime<Utc>) -> Webhook);
+
+actor_message!(RemoveWebhook(collection_id: u128, id: u128) -> ());
+
+impl From<Webhook> for StoreWebhook {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            collection_id: webhook.collection_id,
+            id: webhook.id,
+            url: webhook.url,
+            secret: webhook.secret,
+            events: webhook.events,
+            enabled: webhook.enabled,
+            failures: webhook.failures,
+            principal_id: webhook.created_by,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: u128,
+    pub webhook_id: u128,
+    pub event_id: u64,
+    pub event_type: String,
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+actor_message!(GetWebhookDeliveries(webhook_id: u128) -> Vec<WebhookDelivery>);
+
+actor_message!(StoreWebhookDelivery(webhook_id: u128, id: u128, event_id: u64, event_type: String, attempt: u32, status_code: Option<u16>, error: Option<String>, succeeded: bool, timestamp: DateTime<Utc>) -> WebhookDelivery);
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookV3 {
+    pub id: Option<String>,
+    #[serde(rename = "collectionId")]
+    pub collection_id: Option<String>,
+    pub url: String,
+    /// The secret used to sign each delivery. It is never returned once it has been set.
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+    /// The types of event which should be delivered, or every event if this is empty.
+    #[serde(default)]
+    pub events: Vec<String>,
+    pub enabled: Option<bool>,
+    pub failures: Option<u32>,
+    #[serde(rename = "createdBy")]
+    pub created_by: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+json_responder!(WebhookV3 => (req, model) -> req.url_for("get_webhook_v3", vec![
+    model.collection_id.clone().expect("a collection id"),
+    model.id.clone().expect("a webhook id")
+]));
+
+impl From<Webhook> for WebhookV3 {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: Some(format!("{:0>32x}", webhook.id)),
+            collection_id: Some(format!("{:0>32x}", webhook.collection_id)),
+            url: webhook.url,
+            secret: None,
+            events: webhook.events,
+            enabled: Some(webhook.enabled),
+            failures: Some(webhook.failures),
+            created_by: Some(format!("{:0>32x}", webhook.created_by)),
+            created_at: Some(webhook.created_at),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WebhookDeliveryV3 {
+    pub id: String,
+    #[serde(rename = "webhookId")]
+    pub webhook_id: String,
+    #[serde(rename = "eventId")]
+    pub event_id: String,
+    #[serde(rename = "eventType")]
+    pub event_type: String,
+    pub attempt: u32,
+    #[serde(rename = "statusCode")]
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub succeeded: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryV3 {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            id: format!("{:0>32x}", delivery.id),
+            webhook_id: format!("{:0>32x}", delivery.webhook_id),
+            event_id: delivery.event_id.to_string(),
+            event_type: delivery.event_type,
+            attempt: delivery.attempt,
+            status_code: delivery.status_code,
+            error: delivery.error,
+            succeeded: delivery.succeeded,
+            timestamp: delivery.timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_addresses() {
+        for ip in ["8.8.8.8", "2606:4700:4700::1111", "::ffff:1.1.1.1"] {
+            assert!(
+                is_public_address(ip.parse().unwrap()),
+                "{ip} should be public"
+            );
+        }
+
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(
+                !is_public_address(ip.parse().unwrap()),
+                "{ip} should not be public"
+            );
+        }
+    }
+}
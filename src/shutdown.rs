@@ -0,0 +1,79 @@
+//! Coordinates the graceful shutdown of the service, ensuring that in-flight requests and
+//! store operations are allowed to complete before the process exits.
+
+use crate::{models::*, telemetry::TraceMessageExt};
+use std::{future::Future, time::Duration};
+
+/// Completes once the process has been asked to stop, either by `SIGTERM` (which is what
+/// container platforms send) or by `SIGINT` (Ctrl+C).
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let mut terminate =
+            signal(SignalKind::terminate()).expect("We should be able to listen for SIGTERM.");
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("Received SIGINT, shutting down."),
+            _ = terminate.recv() => info!("Received SIGTERM, shutting down."),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Received Ctrl+C, shutting down.");
+    }
+}
+
+/// Waits for `work` to complete, giving up once the `timeout` has elapsed.
+pub async fn drain<F: Future>(name: &str, timeout: Duration, work: F) -> Option<F::Output> {
+    match actix_rt::time::timeout(timeout, work).await {
+        Ok(output) => Some(output),
+        Err(_) => {
+            warn!(
+                "The {} did not finish within {}s and will be stopped.",
+                name,
+                timeout.as_secs()
+            );
+            None
+        }
+    }
+}
+
+/// Waits for the store to finish the operations which are still in progress.
+pub async fn flush_store(state: &GlobalState, timeout: Duration) {
+    match drain("store", timeout, state.store.send(FlushStore {}.trace())).await {
+        Some(Ok(Ok(()))) => info!("The store has been flushed."),
+        Some(Ok(Err(err))) => error!("The store could not be flushed: {}", err),
+        Some(Err(err)) => error!("The store could not be flushed: {}", err),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_rt::test]
+    async fn drain_completes() {
+        assert_eq!(
+            drain("test", Duration::from_secs(1), async { 42 }).await,
+            Some(42)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn drain_times_out() {
+        assert_eq!(
+            drain(
+                "test",
+                Duration::from_millis(10),
+                actix_rt::time::sleep(Duration::from_secs(10))
+            )
+            .await,
+            None
+        );
+    }
+}
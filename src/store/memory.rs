@@ -1,3 +1,4 @@
+use super::StoreConfig;
 use crate::api::APIError;
 use crate::{models::*, trace_handler};
 use actix::prelude::*;
@@ -11,16 +12,36 @@ pub struct MemoryStore {
     collections: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, Collection>>>>,
     role_assignments: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, RoleAssignment>>>>,
     users: Arc<RwLock<BTreeMap<u128, User>>>,
+    chat_links: Arc<RwLock<BTreeMap<u128, ChatLink>>>,
+    invitations: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, Invitation>>>>,
+    invite_links: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, InviteLink>>>>,
+    share_links: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, ShareLink>>>>,
+    idea_schedules: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, IdeaSchedule>>>>,
+    calendar_feeds: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, CalendarFeed>>>>,
+    webhooks: Arc<RwLock<BTreeMap<u128, BTreeMap<u128, Webhook>>>>,
+    webhook_deliveries: Arc<RwLock<BTreeMap<u128, Vec<WebhookDelivery>>>>,
+    audit_log: Arc<RwLock<Vec<AuditEntry>>>,
+    events: EventBus,
 }
 
 impl MemoryStore {
-    pub fn new() -> Self {
+    pub fn new(events: EventBus, _config: &StoreConfig) -> Self {
         Self {
             started_at: chrono::Utc::now(),
             ideas: Arc::new(RwLock::new(BTreeMap::new())),
             collections: Arc::new(RwLock::new(BTreeMap::new())),
             role_assignments: Arc::new(RwLock::new(BTreeMap::new())),
             users: Arc::new(RwLock::new(BTreeMap::new())),
+            chat_links: Arc::new(RwLock::new(BTreeMap::new())),
+            invitations: Arc::new(RwLock::new(BTreeMap::new())),
+            invite_links: Arc::new(RwLock::new(BTreeMap::new())),
+            share_links: Arc::new(RwLock::new(BTreeMap::new())),
+            idea_schedules: Arc::new(RwLock::new(BTreeMap::new())),
+            calendar_feeds: Arc::new(RwLock::new(BTreeMap::new())),
+            webhooks: Arc::new(RwLock::new(BTreeMap::new())),
+            webhook_deliveries: Arc::new(RwLock::new(BTreeMap::new())),
+            audit_log: Arc::new(RwLock::new(Vec::new())),
+            events,
         }
     }
 }
@@ -35,13 +56,45 @@ impl Handler<GetHealth> for MemoryStore {
     type Result = Result<Health, APIError>;
 
     fn handle(&mut self, _: GetHealth, _: &mut Self::Context) -> Self::Result {
+        // A poisoned lock means that a writer panicked and every later request for that
+        // data will fail, so the store can no longer be relied upon.
         Ok(Health {
-            ok: true,
+            ok: !self.ideas.is_poisoned()
+                && !self.collections.is_poisoned()
+                && !self.role_assignments.is_poisoned()
+                && !self.users.is_poisoned(),
             started_at: self.started_at,
         })
     }
 }
 
+trace_handler!(MemoryStore, FlushStore, Result<(), APIError>);
+
+impl Handler<FlushStore> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, _: FlushStore, _: &mut Self::Context) -> Self::Result {
+        // Every operation completes before the next message is handled, so there is
+        // never anything left to wait for.
+        Ok(())
+    }
+}
+
+trace_handler!(
+    MemoryStore,
+    UpgradeSchema,
+    Result<Vec<SchemaUpgrade>, APIError>
+);
+
+impl Handler<UpgradeSchema> for MemoryStore {
+    type Result = Result<Vec<SchemaUpgrade>, APIError>;
+
+    fn handle(&mut self, _: UpgradeSchema, _: &mut Self::Context) -> Self::Result {
+        // Nothing outlives the process, so everything is always stored with the current schema.
+        Ok(vec![])
+    }
+}
+
 trace_handler!(MemoryStore, GetIdea, Result<Idea, APIError>);
 
 impl Handler<GetIdea> for MemoryStore {
@@ -96,6 +149,27 @@ impl Handler<GetIdeas> for MemoryStore {
     }
 }
 
+trace_handler!(MemoryStore, CountIdeas, Result<usize, APIError>);
+
+impl Handler<CountIdeas> for MemoryStore {
+    type Result = Result<usize, APIError>;
+
+    fn handle(&mut self, msg: CountIdeas, _: &mut Self::Context) -> Self::Result {
+        let is = self.ideas.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(is
+            .get(&msg.collection)
+            .map(|items| items.len())
+            .unwrap_or_default())
+    }
+}
+
 trace_handler!(MemoryStore, GetRandomIdea, Result<Idea, APIError>);
 
 impl Handler<GetRandomIdea> for MemoryStore {
@@ -144,6 +218,8 @@ impl Handler<StoreIdea> for MemoryStore {
             )
         })?;
 
+        let ideas = is.entry(msg.collection).or_insert_with(BTreeMap::new);
+
         let idea = Idea {
             id: msg.id,
             collection_id: msg.collection,
@@ -151,16 +227,44 @@ impl Handler<StoreIdea> for MemoryStore {
             description: msg.description.clone(),
             tags: msg.tags.clone(),
             completed: msg.completed,
-        };
+            created_at: None,
+            completed_at: None,
+        }
+        .timestamped(ideas.get(&msg.id), chrono::Utc::now());
 
-        is.entry(msg.collection)
-            .or_insert_with(BTreeMap::new)
-            .insert(idea.id, idea.clone());
+        let previous = ideas.insert(idea.id, idea.clone());
+
+        self.events.publish(
+            msg.collection,
+            CollectionEventKind::stored_idea(previous.as_ref(), idea.clone()),
+        );
 
         Ok(idea)
     }
 }
 
+trace_handler!(MemoryStore, RestoreIdea, Result<Idea, APIError>);
+
+impl Handler<RestoreIdea> for MemoryStore {
+    type Result = Result<Idea, APIError>;
+
+    fn handle(&mut self, msg: RestoreIdea, _: &mut Self::Context) -> Self::Result {
+        let mut is = self.ideas.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        is.entry(msg.idea.collection_id)
+            .or_insert_with(BTreeMap::new)
+            .insert(msg.idea.id, msg.idea.clone());
+
+        Ok(msg.idea)
+    }
+}
+
 trace_handler!(MemoryStore, RemoveIdea, Result<(), APIError>);
 
 impl Handler<RemoveIdea> for MemoryStore {
@@ -180,7 +284,12 @@ impl Handler<RemoveIdea> for MemoryStore {
             .and_then(|c|
                 c.remove(&msg.id)
                 .map(|_| ())
-                .ok_or_else(|| APIError::new(404, "Not Found", "The idea ID you provided could not be found. Please check it and try again.")))
+                .ok_or_else(|| APIError::new(404, "Not Found", "The idea ID you provided could not be found. Please check it and try again.")))?;
+
+        self.events
+            .publish(msg.collection, CollectionEventKind::IdeaRemoved(msg.id));
+
+        Ok(())
     }
 }
 
@@ -230,6 +339,28 @@ impl Handler<GetCollections> for MemoryStore {
     }
 }
 
+trace_handler!(
+    MemoryStore,
+    GetAllCollections,
+    Result<Vec<Collection>, APIError>
+);
+
+impl Handler<GetAllCollections> for MemoryStore {
+    type Result = Result<Vec<Collection>, APIError>;
+
+    fn handle(&mut self, _: GetAllCollections, _: &mut Self::Context) -> Self::Result {
+        let is = self.collections.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(is.values().flat_map(|c| c.values().cloned()).collect())
+    }
+}
+
 trace_handler!(MemoryStore, StoreCollection, Result<Collection, APIError>);
 
 impl Handler<StoreCollection> for MemoryStore {
@@ -345,6 +476,31 @@ impl Handler<GetRoleAssignments> for MemoryStore {
     }
 }
 
+trace_handler!(
+    MemoryStore,
+    GetPrincipalRoleAssignments,
+    Result<Vec<RoleAssignment>, APIError>
+);
+
+impl Handler<GetPrincipalRoleAssignments> for MemoryStore {
+    type Result = Result<Vec<RoleAssignment>, APIError>;
+
+    fn handle(&mut self, msg: GetPrincipalRoleAssignments, _: &mut Self::Context) -> Self::Result {
+        let is = self.role_assignments.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(is
+            .values()
+            .filter_map(|items| items.get(&msg.principal_id).cloned())
+            .collect())
+    }
+}
+
 trace_handler!(MemoryStore, StoreRoleAssignment, Result<RoleAssignment, APIError>);
 
 impl Handler<StoreRoleAssignment> for MemoryStore {
@@ -365,10 +521,18 @@ impl Handler<StoreRoleAssignment> for MemoryStore {
             role: msg.role,
         };
 
-        is.entry(msg.collection_id)
+        let previous = is
+            .entry(msg.collection_id)
             .or_insert_with(BTreeMap::new)
             .insert(role_assignment.user_id, role_assignment.clone());
 
+        if previous.is_none() {
+            self.events.publish(
+                msg.collection_id,
+                CollectionEventKind::MemberAdded(role_assignment.clone()),
+            );
+        }
+
         Ok(role_assignment)
     }
 }
@@ -398,17 +562,24 @@ impl Handler<RemoveRoleAssignment> for MemoryStore {
                 .ok_or_else(|| {
                     debug!("Could not find an entry for the user {} in the collection role assignments table for {}", msg.principal_id, msg.collection_id);
                     APIError::new(404, "Not Found", "The principal ID you provided could not be found. This likely means that you do not yet have any collections.")
-                }))
+                }))?;
+
+        self.events.publish(
+            msg.collection_id,
+            CollectionEventKind::MemberRemoved(msg.principal_id),
+        );
+
+        Ok(())
     }
 }
 
-trace_handler!(MemoryStore, GetUser, Result<User, APIError>);
+trace_handler!(MemoryStore, GetInvitation, Result<Invitation, APIError>);
 
-impl Handler<GetUser> for MemoryStore {
-    type Result = Result<User, APIError>;
+impl Handler<GetInvitation> for MemoryStore {
+    type Result = Result<Invitation, APIError>;
 
-    fn handle(&mut self, msg: GetUser, _: &mut Self::Context) -> Self::Result {
-        let users = self.users.read().map_err(|_| {
+    fn handle(&mut self, msg: GetInvitation, _: &mut Self::Context) -> Self::Result {
+        let is = self.invitations.read().map_err(|_| {
             APIError::new(
                 500,
                 "Internal Server Error",
@@ -416,18 +587,23 @@ impl Handler<GetUser> for MemoryStore {
             )
         })?;
 
-        users.get(&msg.email_hash)
-            .ok_or_else(|| APIError::new(404, "Not Found", "No user could be found with the email hash you provided. Please check it and try again.")).cloned()
+        is.get(&msg.collection_id)
+            .and_then(|c| c.get(&msg.email_hash).cloned())
+            .ok_or_else(|| APIError::new(404, "Not Found", "No pending invitation could be found for the email address you provided. Please check it and try again."))
     }
 }
 
-trace_handler!(MemoryStore, StoreUser, Result<User, APIError>);
+trace_handler!(
+    MemoryStore,
+    GetInvitations,
+    Result<Vec<Invitation>, APIError>
+);
 
-impl Handler<StoreUser> for MemoryStore {
-    type Result = Result<User, APIError>;
+impl Handler<GetInvitations> for MemoryStore {
+    type Result = Result<Vec<Invitation>, APIError>;
 
-    fn handle(&mut self, msg: StoreUser, _: &mut Self::Context) -> Self::Result {
-        let mut users = self.users.write().map_err(|_| {
+    fn handle(&mut self, msg: GetInvitations, _: &mut Self::Context) -> Self::Result {
+        let is = self.invitations.read().map_err(|_| {
             APIError::new(
                 500,
                 "Internal Server Error",
@@ -435,14 +611,878 @@ impl Handler<StoreUser> for MemoryStore {
             )
         })?;
 
-        let user = User {
-            principal_id: msg.principal_id,
+        Ok(is
+            .get(&msg.collection_id)
+            .map(|c| c.values().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+trace_handler!(
+    MemoryStore,
+    GetUserInvitations,
+    Result<Vec<Invitation>, APIError>
+);
+
+impl Handler<GetUserInvitations> for MemoryStore {
+    type Result = Result<Vec<Invitation>, APIError>;
+
+    fn handle(&mut self, msg: GetUserInvitations, _: &mut Self::Context) -> Self::Result {
+        let is = self.invitations.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(is
+            .values()
+            .filter_map(|c| c.get(&msg.email_hash).cloned())
+            .collect())
+    }
+}
+
+trace_handler!(MemoryStore, StoreInvitation, Result<Invitation, APIError>);
+
+impl Handler<StoreInvitation> for MemoryStore {
+    type Result = Result<Invitation, APIError>;
+
+    fn handle(&mut self, msg: StoreInvitation, _: &mut Self::Context) -> Self::Result {
+        let mut is = self.invitations.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let invitation = Invitation {
+            collection_id: msg.collection_id,
             email_hash: msg.email_hash,
-            first_name: msg.first_name.clone(),
+            role: msg.role,
+            invited_by: msg.principal_id,
         };
 
-        users.insert(msg.email_hash, user.clone());
+        is.entry(msg.collection_id)
+            .or_insert_with(BTreeMap::new)
+            .insert(invitation.email_hash, invitation.clone());
 
-        Ok(user)
+        Ok(invitation)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveInvitation, Result<(), APIError>);
+
+impl Handler<RemoveInvitation> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveInvitation, _: &mut Self::Context) -> Self::Result {
+        let mut is = self.invitations.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        is.get_mut(&msg.collection_id)
+            .and_then(|c| c.remove(&msg.email_hash))
+            .map(|_| ())
+            .ok_or_else(|| APIError::new(404, "Not Found", "No pending invitation could be found for the email address you provided. Please check it and try again."))
+    }
+}
+
+trace_handler!(MemoryStore, GetInviteLink, Result<InviteLink, APIError>);
+
+impl Handler<GetInviteLink> for MemoryStore {
+    type Result = Result<InviteLink, APIError>;
+
+    fn handle(&mut self, msg: GetInviteLink, _: &mut Self::Context) -> Self::Result {
+        let is = self.invite_links.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        is.get(&msg.collection_id)
+            .and_then(|c| c.get(&msg.id).cloned())
+            .ok_or_else(|| APIError::new(404, "Not Found", "The invite link you provided could not be found. Please check it and try again."))
+    }
+}
+
+trace_handler!(MemoryStore, RedeemInviteLink, Result<InviteLink, APIError>);
+
+impl Handler<RedeemInviteLink> for MemoryStore {
+    type Result = Result<InviteLink, APIError>;
+
+    fn handle(&mut self, msg: RedeemInviteLink, _: &mut Self::Context) -> Self::Result {
+        let mut is = self.invite_links.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let link = is
+            .get_mut(&msg.collection_id)
+            .and_then(|c| c.get_mut(&msg.id))
+            .ok_or_else(|| APIError::new(404, "Not Found", "The invite link you provided could not be found. Please check it and try again."))?;
+
+        link.redeem()?;
+        Ok(link.clone())
+    }
+}
+
+trace_handler!(
+    MemoryStore,
+    GetInviteLinks,
+    Result<Vec<InviteLink>, APIError>
+);
+
+impl Handler<GetInviteLinks> for MemoryStore {
+    type Result = Result<Vec<InviteLink>, APIError>;
+
+    fn handle(&mut self, msg: GetInviteLinks, _: &mut Self::Context) -> Self::Result {
+        let is = self.invite_links.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(is
+            .get(&msg.collection_id)
+            .map(|c| c.values().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+trace_handler!(MemoryStore, StoreInviteLink, Result<InviteLink, APIError>);
+
+impl Handler<StoreInviteLink> for MemoryStore {
+    type Result = Result<InviteLink, APIError>;
+
+    fn handle(&mut self, msg: StoreInviteLink, _: &mut Self::Context) -> Self::Result {
+        let mut is = self.invite_links.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let link = InviteLink {
+            id: msg.id,
+            collection_id: msg.collection_id,
+            role: msg.role,
+            max_uses: msg.max_uses,
+            uses: msg.uses,
+            expires_at: msg.expires_at,
+            created_by: msg.principal_id,
+        };
+
+        is.entry(msg.collection_id)
+            .or_insert_with(BTreeMap::new)
+            .insert(link.id, link.clone());
+
+        Ok(link)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveInviteLink, Result<(), APIError>);
+
+impl Handler<RemoveInviteLink> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveInviteLink, _: &mut Self::Context) -> Self::Result {
+        let mut is = self.invite_links.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        is.get_mut(&msg.collection_id)
+            .and_then(|c| c.remove(&msg.id))
+            .map(|_| ())
+            .ok_or_else(|| APIError::new(404, "Not Found", "The invite link you provided could not be found. Please check it and try again."))
+    }
+}
+
+trace_handler!(MemoryStore, GetShareLink, Result<ShareLink, APIError>);
+
+impl Handler<GetShareLink> for MemoryStore {
+    type Result = Result<ShareLink, APIError>;
+
+    fn handle(&mut self, msg: GetShareLink, _: &mut Self::Context) -> Self::Result {
+        let ss = self.share_links.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        ss.get(&msg.collection_id)
+            .and_then(|c| c.get(&msg.id).cloned())
+            .ok_or_else(|| APIError::new(404, "Not Found", "The share link you provided could not be found. Please check it and try again."))
+    }
+}
+
+trace_handler!(MemoryStore, GetShareLinks, Result<Vec<ShareLink>, APIError>);
+
+impl Handler<GetShareLinks> for MemoryStore {
+    type Result = Result<Vec<ShareLink>, APIError>;
+
+    fn handle(&mut self, msg: GetShareLinks, _: &mut Self::Context) -> Self::Result {
+        let ss = self.share_links.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(ss
+            .get(&msg.collection_id)
+            .map(|c| c.values().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+trace_handler!(MemoryStore, StoreShareLink, Result<ShareLink, APIError>);
+
+impl Handler<StoreShareLink> for MemoryStore {
+    type Result = Result<ShareLink, APIError>;
+
+    fn handle(&mut self, msg: StoreShareLink, _: &mut Self::Context) -> Self::Result {
+        let mut ss = self.share_links.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let link = ShareLink {
+            id: msg.id,
+            collection_id: msg.collection_id,
+            created_by: msg.principal_id,
+            created_at: msg.created_at,
+        };
+
+        ss.entry(msg.collection_id)
+            .or_insert_with(BTreeMap::new)
+            .insert(link.id, link.clone());
+
+        Ok(link)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveShareLink, Result<(), APIError>);
+
+impl Handler<RemoveShareLink> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveShareLink, _: &mut Self::Context) -> Self::Result {
+        let mut ss = self.share_links.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        ss.get_mut(&msg.collection_id)
+            .and_then(|c| c.remove(&msg.id))
+            .map(|_| ())
+            .ok_or_else(|| APIError::new(404, "Not Found", "The share link you provided could not be found. Please check it and try again."))
+    }
+}
+
+trace_handler!(MemoryStore, GetIdeaSchedule, Result<IdeaSchedule, APIError>);
+
+impl Handler<GetIdeaSchedule> for MemoryStore {
+    type Result = Result<IdeaSchedule, APIError>;
+
+    fn handle(&mut self, msg: GetIdeaSchedule, _: &mut Self::Context) -> Self::Result {
+        let ss = self.idea_schedules.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        ss.get(&msg.collection_id)
+            .and_then(|c| c.get(&msg.idea_id).cloned())
+            .ok_or_else(|| {
+                APIError::new(
+                    404,
+                    "Not Found",
+                    "The schedule you provided could not be found. Please check it and try again.",
+                )
+            })
+    }
+}
+
+trace_handler!(
+    MemoryStore,
+    GetIdeaSchedules,
+    Result<Vec<IdeaSchedule>, APIError>
+);
+
+impl Handler<GetIdeaSchedules> for MemoryStore {
+    type Result = Result<Vec<IdeaSchedule>, APIError>;
+
+    fn handle(&mut self, msg: GetIdeaSchedules, _: &mut Self::Context) -> Self::Result {
+        let ss = self.idea_schedules.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(ss
+            .get(&msg.collection_id)
+            .map(|c| c.values().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+trace_handler!(MemoryStore, StoreIdeaSchedule, Result<IdeaSchedule, APIError>);
+
+impl Handler<StoreIdeaSchedule> for MemoryStore {
+    type Result = Result<IdeaSchedule, APIError>;
+
+    fn handle(&mut self, msg: StoreIdeaSchedule, _: &mut Self::Context) -> Self::Result {
+        let mut ss = self.idea_schedules.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let schedule = IdeaSchedule {
+            collection_id: msg.collection_id,
+            idea_id: msg.idea_id,
+            date: msg.date,
+            scheduled_by: msg.principal_id,
+            scheduled_at: msg.scheduled_at,
+        };
+
+        ss.entry(msg.collection_id)
+            .or_insert_with(BTreeMap::new)
+            .insert(schedule.idea_id, schedule.clone());
+
+        Ok(schedule)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveIdeaSchedule, Result<(), APIError>);
+
+impl Handler<RemoveIdeaSchedule> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveIdeaSchedule, _: &mut Self::Context) -> Self::Result {
+        let mut ss = self.idea_schedules.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        ss.get_mut(&msg.collection_id)
+            .and_then(|c| c.remove(&msg.idea_id))
+            .map(|_| ())
+            .ok_or_else(|| {
+                APIError::new(
+                    404,
+                    "Not Found",
+                    "The schedule you provided could not be found. Please check it and try again.",
+                )
+            })
+    }
+}
+
+trace_handler!(MemoryStore, GetCalendarFeed, Result<CalendarFeed, APIError>);
+
+impl Handler<GetCalendarFeed> for MemoryStore {
+    type Result = Result<CalendarFeed, APIError>;
+
+    fn handle(&mut self, msg: GetCalendarFeed, _: &mut Self::Context) -> Self::Result {
+        let fs = self.calendar_feeds.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        fs.get(&msg.principal_id)
+            .and_then(|u| u.get(&msg.id).cloned())
+            .ok_or_else(|| APIError::new(404, "Not Found", "The calendar feed you provided could not be found. Please check it and try again."))
+    }
+}
+
+trace_handler!(
+    MemoryStore,
+    GetCalendarFeeds,
+    Result<Vec<CalendarFeed>, APIError>
+);
+
+impl Handler<GetCalendarFeeds> for MemoryStore {
+    type Result = Result<Vec<CalendarFeed>, APIError>;
+
+    fn handle(&mut self, msg: GetCalendarFeeds, _: &mut Self::Context) -> Self::Result {
+        let fs = self.calendar_feeds.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(fs
+            .get(&msg.principal_id)
+            .map(|u| u.values().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+trace_handler!(MemoryStore, StoreCalendarFeed, Result<CalendarFeed, APIError>);
+
+impl Handler<StoreCalendarFeed> for MemoryStore {
+    type Result = Result<CalendarFeed, APIError>;
+
+    fn handle(&mut self, msg: StoreCalendarFeed, _: &mut Self::Context) -> Self::Result {
+        let mut fs = self.calendar_feeds.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let feed = CalendarFeed {
+            id: msg.id,
+            principal_id: msg.principal_id,
+            created_at: msg.created_at,
+        };
+
+        fs.entry(msg.principal_id)
+            .or_insert_with(BTreeMap::new)
+            .insert(feed.id, feed.clone());
+
+        Ok(feed)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveCalendarFeed, Result<(), APIError>);
+
+impl Handler<RemoveCalendarFeed> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveCalendarFeed, _: &mut Self::Context) -> Self::Result {
+        let mut fs = self.calendar_feeds.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        fs.get_mut(&msg.principal_id)
+            .and_then(|u| u.remove(&msg.id))
+            .map(|_| ())
+            .ok_or_else(|| APIError::new(404, "Not Found", "The calendar feed you provided could not be found. Please check it and try again."))
+    }
+}
+
+trace_handler!(MemoryStore, GetWebhook, Result<Webhook, APIError>);
+
+impl Handler<GetWebhook> for MemoryStore {
+    type Result = Result<Webhook, APIError>;
+
+    fn handle(&mut self, msg: GetWebhook, _: &mut Self::Context) -> Self::Result {
+        let ws = self.webhooks.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        ws.get(&msg.collection_id)
+            .and_then(|c| c.get(&msg.id).cloned())
+            .ok_or_else(|| {
+                APIError::new(
+                    404,
+                    "Not Found",
+                    "The webhook you provided could not be found. Please check it and try again.",
+                )
+            })
+    }
+}
+
+trace_handler!(MemoryStore, GetWebhooks, Result<Vec<Webhook>, APIError>);
+
+impl Handler<GetWebhooks> for MemoryStore {
+    type Result = Result<Vec<Webhook>, APIError>;
+
+    fn handle(&mut self, msg: GetWebhooks, _: &mut Self::Context) -> Self::Result {
+        let ws = self.webhooks.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(ws
+            .get(&msg.collection_id)
+            .map(|c| c.values().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+trace_handler!(MemoryStore, StoreWebhook, Result<Webhook, APIError>);
+
+impl Handler<StoreWebhook> for MemoryStore {
+    type Result = Result<Webhook, APIError>;
+
+    fn handle(&mut self, msg: StoreWebhook, _: &mut Self::Context) -> Self::Result {
+        let mut ws = self.webhooks.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let webhook = Webhook {
+            id: msg.id,
+            collection_id: msg.collection_id,
+            url: msg.url,
+            secret: msg.secret,
+            events: msg.events,
+            enabled: msg.enabled,
+            failures: msg.failures,
+            created_by: msg.principal_id,
+            created_at: msg.created_at,
+        };
+
+        ws.entry(msg.collection_id)
+            .or_insert_with(BTreeMap::new)
+            .insert(webhook.id, webhook.clone());
+
+        Ok(webhook)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveWebhook, Result<(), APIError>);
+
+impl Handler<RemoveWebhook> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveWebhook, _: &mut Self::Context) -> Self::Result {
+        let mut ws = self.webhooks.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        ws.get_mut(&msg.collection_id)
+            .and_then(|c| c.remove(&msg.id))
+            .ok_or_else(|| {
+                APIError::new(
+                    404,
+                    "Not Found",
+                    "The webhook you provided could not be found. Please check it and try again.",
+                )
+            })?;
+
+        if let Ok(mut ds) = self.webhook_deliveries.write() {
+            ds.remove(&msg.id);
+        }
+
+        Ok(())
+    }
+}
+
+trace_handler!(
+    MemoryStore,
+    GetWebhookDeliveries,
+    Result<Vec<WebhookDelivery>, APIError>
+);
+
+impl Handler<GetWebhookDeliveries> for MemoryStore {
+    type Result = Result<Vec<WebhookDelivery>, APIError>;
+
+    fn handle(&mut self, msg: GetWebhookDeliveries, _: &mut Self::Context) -> Self::Result {
+        let ds = self.webhook_deliveries.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(ds.get(&msg.webhook_id).cloned().unwrap_or_default())
+    }
+}
+
+trace_handler!(MemoryStore, StoreWebhookDelivery, Result<WebhookDelivery, APIError>);
+
+impl Handler<StoreWebhookDelivery> for MemoryStore {
+    type Result = Result<WebhookDelivery, APIError>;
+
+    fn handle(&mut self, msg: StoreWebhookDelivery, _: &mut Self::Context) -> Self::Result {
+        let mut ds = self.webhook_deliveries.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let delivery = WebhookDelivery {
+            id: msg.id,
+            webhook_id: msg.webhook_id,
+            event_id: msg.event_id,
+            event_type: msg.event_type,
+            attempt: msg.attempt,
+            status_code: msg.status_code,
+            error: msg.error,
+            succeeded: msg.succeeded,
+            timestamp: msg.timestamp,
+        };
+
+        let deliveries = ds.entry(msg.webhook_id).or_default();
+        if deliveries.len() >= WEBHOOK_DELIVERY_HISTORY {
+            deliveries.remove(0);
+        }
+        deliveries.push(delivery.clone());
+
+        Ok(delivery)
+    }
+}
+
+trace_handler!(MemoryStore, GetChatLink, Result<ChatLink, APIError>);
+
+impl Handler<GetChatLink> for MemoryStore {
+    type Result = Result<ChatLink, APIError>;
+
+    fn handle(&mut self, msg: GetChatLink, _: &mut Self::Context) -> Self::Result {
+        let cs = self.chat_links.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        cs.get(&msg.chat_user_hash).cloned().ok_or_else(|| {
+            APIError::new(
+                404,
+                "Not Found",
+                "Your chat account has not been linked to Rex yet. Please link it and try again.",
+            )
+        })
+    }
+}
+
+trace_handler!(MemoryStore, StoreChatLink, Result<ChatLink, APIError>);
+
+impl Handler<StoreChatLink> for MemoryStore {
+    type Result = Result<ChatLink, APIError>;
+
+    fn handle(&mut self, msg: StoreChatLink, _: &mut Self::Context) -> Self::Result {
+        let mut cs = self.chat_links.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let link = ChatLink {
+            team_id: msg.team_id,
+            user_id: msg.user_id,
+            principal_id: msg.principal_id,
+            created_at: msg.created_at,
+        };
+
+        cs.insert(chat_user_hash(&link.team_id, &link.user_id), link.clone());
+
+        Ok(link)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveChatLink, Result<(), APIError>);
+
+impl Handler<RemoveChatLink> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveChatLink, _: &mut Self::Context) -> Self::Result {
+        let mut cs = self.chat_links.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        cs.remove(&msg.chat_user_hash).map(|_| ()).ok_or_else(|| {
+            APIError::new(
+                404,
+                "Not Found",
+                "Your chat account has not been linked to Rex yet. Please link it and try again.",
+            )
+        })
+    }
+}
+
+trace_handler!(MemoryStore, GetUser, Result<User, APIError>);
+
+impl Handler<GetUser> for MemoryStore {
+    type Result = Result<User, APIError>;
+
+    fn handle(&mut self, msg: GetUser, _: &mut Self::Context) -> Self::Result {
+        let users = self.users.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        users.get(&msg.email_hash)
+            .ok_or_else(|| APIError::new(404, "Not Found", "No user could be found with the email hash you provided. Please check it and try again.")).cloned()
+    }
+}
+
+trace_handler!(MemoryStore, GetUsers, Result<Vec<User>, APIError>);
+
+impl Handler<GetUsers> for MemoryStore {
+    type Result = Result<Vec<User>, APIError>;
+
+    fn handle(&mut self, _: GetUsers, _: &mut Self::Context) -> Self::Result {
+        let users = self.users.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(users.values().cloned().collect())
+    }
+}
+
+trace_handler!(MemoryStore, StoreUser, Result<User, APIError>);
+
+impl Handler<StoreUser> for MemoryStore {
+    type Result = Result<User, APIError>;
+
+    fn handle(&mut self, msg: StoreUser, _: &mut Self::Context) -> Self::Result {
+        let mut users = self.users.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let user = User {
+            principal_id: msg.principal_id,
+            email_hash: msg.email_hash,
+            first_name: msg.first_name.clone(),
+        };
+
+        users.insert(msg.email_hash, user.clone());
+
+        Ok(user)
+    }
+}
+
+trace_handler!(MemoryStore, RemoveUser, Result<(), APIError>);
+
+impl Handler<RemoveUser> for MemoryStore {
+    type Result = Result<(), APIError>;
+
+    fn handle(&mut self, msg: RemoveUser, _: &mut Self::Context) -> Self::Result {
+        let mut users = self.users.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        users.remove(&msg.email_hash)
+            .map(|_| ())
+            .ok_or_else(|| APIError::new(404, "Not Found", "No user could be found with the email hash you provided. Please check it and try again."))
+    }
+}
+
+trace_handler!(
+    MemoryStore,
+    GetAuditEntries,
+    Result<Vec<AuditEntry>, APIError>
+);
+
+impl Handler<GetAuditEntries> for MemoryStore {
+    type Result = Result<Vec<AuditEntry>, APIError>;
+
+    fn handle(&mut self, _: GetAuditEntries, _: &mut Self::Context) -> Self::Result {
+        let entries = self.audit_log.read().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        Ok(entries.clone())
+    }
+}
+
+trace_handler!(MemoryStore, StoreAuditEntry, Result<AuditEntry, APIError>);
+
+impl Handler<StoreAuditEntry> for MemoryStore {
+    type Result = Result<AuditEntry, APIError>;
+
+    fn handle(&mut self, msg: StoreAuditEntry, _: &mut Self::Context) -> Self::Result {
+        let mut entries = self.audit_log.write().map_err(|_| {
+            APIError::new(
+                500,
+                "Internal Server Error",
+                "The service is currently unavailable, please try again later.",
+            )
+        })?;
+
+        let entry = AuditEntry {
+            id: msg.id,
+            principal_id: msg.principal_id,
+            timestamp: msg.timestamp,
+            action: msg.action.clone(),
+            target: msg.target.clone(),
+        };
+
+        entries.push(entry.clone());
+
+        Ok(entry)
     }
 }
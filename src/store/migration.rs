@@ -0,0 +1,940 @@
+//! Copies every user, collection, role assignment and idea from one store into another,
+//! so that an instance can move between storage backends without losing data.
+//!
+//! Everything else a store holds is left behind, and is listed in [`NOT_MIGRATED`].
+
+use crate::{
+    api::APIError,
+    models::*,
+    telemetry::{TraceMessage, TraceMessageExt},
+};
+use actix::prelude::*;
+use chrono::{DateTime, SecondsFormat, Utc};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::PathBuf,
+};
+
+/// The records which a migration does not copy, and which must be recreated by hand.
+pub const NOT_MIGRATED: &str = "share links, invite links, invitations, webhooks, idea schedules, calendar feeds and chat links";
+
+/// A store which can be read from and written to by a migration.
+pub trait MigrationStore:
+    Actor<Context = Context<Self>>
+    + Handler<TraceMessage<GetUsers>>
+    + Handler<TraceMessage<GetAllCollections>>
+    + Handler<TraceMessage<GetRoleAssignments>>
+    + Handler<TraceMessage<GetIdeas>>
+    + Handler<TraceMessage<StoreUser>>
+    + Handler<TraceMessage<StoreCollection>>
+    + Handler<TraceMessage<StoreRoleAssignment>>
+    + Handler<TraceMessage<RestoreIdea>>
+{
+}
+
+impl<T> MigrationStore for T where
+    T: Actor<Context = Context<T>>
+        + Handler<TraceMessage<GetUsers>>
+        + Handler<TraceMessage<GetAllCollections>>
+        + Handler<TraceMessage<GetRoleAssignments>>
+        + Handler<TraceMessage<GetIdeas>>
+        + Handler<TraceMessage<StoreUser>>
+        + Handler<TraceMessage<StoreCollection>>
+        + Handler<TraceMessage<StoreRoleAssignment>>
+        + Handler<TraceMessage<RestoreIdea>>
+{
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationOptions {
+    /// Reads the source and reports what would be copied without writing anything.
+    pub dry_run: bool,
+    /// The file in which progress is recorded, allowing an interrupted migration to be
+    /// resumed without copying everything again.
+    pub checkpoint: Option<PathBuf>,
+}
+
+/// The progress of a migration, saved after each collection has been copied. Collections
+/// which fail verification are removed from it, so that re-running the migration copies
+/// them again.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Checkpoint {
+    users: bool,
+    collections: BTreeSet<String>,
+}
+
+impl Checkpoint {
+    fn load(options: &MigrationOptions) -> Result<Self, APIError> {
+        match &options.checkpoint {
+            Some(path) if path.exists() => {
+                let json = std::fs::read_to_string(path).map_err(checkpoint_error)?;
+                serde_json::from_str(&json).map_err(|err| {
+                    APIError::new(
+                        500,
+                        "Internal Server Error",
+                        &format!(
+                            "The migration checkpoint {} is not valid: {err}",
+                            path.display()
+                        ),
+                    )
+                })
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn save(&self, options: &MigrationOptions) -> Result<(), APIError> {
+        if options.dry_run {
+            return Ok(());
+        }
+
+        if let Some(path) = &options.checkpoint {
+            let json = serde_json::to_string(self).map_err(checkpoint_error)?;
+            std::fs::write(path, json).map_err(checkpoint_error)?;
+        }
+
+        Ok(())
+    }
+
+    fn clear(options: &MigrationOptions) -> Result<(), APIError> {
+        match &options.checkpoint {
+            Some(path) if path.exists() => std::fs::remove_file(path).map_err(checkpoint_error),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn checkpoint_error<E: Display>(err: E) -> APIError {
+    APIError::new(
+        500,
+        "Internal Server Error",
+        &format!("The migration checkpoint could not be saved: {err}"),
+    )
+}
+
+/// The number of records of a single type which were found in the source store.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tally {
+    pub found: usize,
+    pub copied: usize,
+    /// The records which were copied by an earlier, interrupted, run.
+    pub resumed: usize,
+}
+
+impl Display for Tally {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} found, {} copied", self.found, self.copied)?;
+        if self.resumed > 0 {
+            write!(f, ", {} already migrated", self.resumed)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The number of records of each type and a checksum over their contents.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Fingerprint {
+    pub users: (usize, String),
+    pub collections: (usize, String),
+    pub role_assignments: (usize, String),
+    pub ideas: (usize, String),
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    pub users: Tally,
+    pub collections: Tally,
+    pub role_assignments: Tally,
+    pub ideas: Tally,
+    /// The fingerprints of the source and destination, once the migration has been
+    /// verified. This is `None` for a dry run.
+    pub verification: Option<(Fingerprint, Fingerprint)>,
+}
+
+impl MigrationReport {
+    pub fn verified(&self) -> bool {
+        match &self.verification {
+            Some((source, destination)) => source == destination,
+            None => self.dry_run,
+        }
+    }
+}
+
+impl Display for MigrationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.dry_run {
+            writeln!(f, "Dry run, nothing was written to the destination.")?;
+        }
+
+        writeln!(f, "Users:            {}", self.users)?;
+        writeln!(f, "Collections:      {}", self.collections)?;
+        writeln!(f, "Role assignments: {}", self.role_assignments)?;
+        writeln!(f, "Ideas:            {}", self.ideas)?;
+        writeln!(f, "Not migrated:     {NOT_MIGRATED}")?;
+
+        if let Some((source, destination)) = &self.verification {
+            let rows = [
+                ("Users", &source.users, &destination.users),
+                ("Collections", &source.collections, &destination.collections),
+                (
+                    "Role assignments",
+                    &source.role_assignments,
+                    &destination.role_assignments,
+                ),
+                ("Ideas", &source.ideas, &destination.ideas),
+            ];
+
+            for (name, source, destination) in rows {
+                let status = if source == destination {
+                    "ok"
+                } else {
+                    "MISMATCH"
+                };
+                writeln!(
+                    f,
+                    "Verified {name}: {status} (source {} sha256:{}, destination {} sha256:{})",
+                    source.0, source.1, destination.0, destination.1
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Copies every user, collection, role assignment and idea from `source` into
+/// `destination`, then reads both stores back to confirm that they hold the same data.
+pub async fn migrate<S, D>(
+    source: &Addr<S>,
+    destination: &Addr<D>,
+    options: &MigrationOptions,
+) -> Result<MigrationReport, APIError>
+where
+    S: MigrationStore,
+    D: MigrationStore,
+{
+    let mut checkpoint = Checkpoint::load(options)?;
+    let mut report = MigrationReport {
+        dry_run: options.dry_run,
+        ..Default::default()
+    };
+
+    let users = source.send(GetUsers {}.trace()).await??;
+    report.users.found = users.len();
+    if checkpoint.users {
+        report.users.resumed = users.len();
+    } else {
+        for user in users {
+            if !options.dry_run {
+                destination
+                    .send(
+                        StoreUser {
+                            email_hash: user.email_hash,
+                            principal_id: user.principal_id,
+                            first_name: user.first_name,
+                        }
+                        .trace(),
+                    )
+                    .await??;
+            }
+
+            report.users.copied += 1;
+        }
+
+        checkpoint.users = true;
+        checkpoint.save(options)?;
+    }
+
+    // Collections are listed once for each of their members, but their role assignments
+    // and ideas are shared by every listing and so are only copied once.
+    let collections = group_collections(source.send(GetAllCollections {}.trace()).await??);
+    for (collection_id, listings) in collections {
+        let role_assignments = get_role_assignments(source, collection_id).await?;
+        let ideas = get_ideas(source, collection_id).await?;
+
+        report.collections.found += listings.len();
+        report.role_assignments.found += role_assignments.len();
+        report.ideas.found += ideas.len();
+
+        let key = collection_key(collection_id);
+        if checkpoint.collections.contains(&key) {
+            report.collections.resumed += listings.len();
+            report.role_assignments.resumed += role_assignments.len();
+            report.ideas.resumed += ideas.len();
+            continue;
+        }
+
+        if !options.dry_run {
+            copy_collection(destination, &listings, &role_assignments, ideas.clone()).await?;
+        }
+
+        report.collections.copied += listings.len();
+        report.role_assignments.copied += role_assignments.len();
+        report.ideas.copied += ideas.len();
+
+        checkpoint.collections.insert(key);
+        checkpoint.save(options)?;
+    }
+
+    if !options.dry_run {
+        let verification = verify(source, destination).await?;
+        if verification.source == verification.destination {
+            Checkpoint::clear(options)?;
+        } else {
+            // Anything which does not match is forgotten, so that it is copied again when
+            // the migration is resumed.
+            checkpoint.users &= verification.source.users == verification.destination.users;
+            for collection_id in verification.mismatched {
+                checkpoint
+                    .collections
+                    .remove(&collection_key(collection_id));
+            }
+            checkpoint.save(options)?;
+        }
+
+        report.verification = Some((verification.source, verification.destination));
+    }
+
+    Ok(report)
+}
+
+/// Copies a collection's listings, role assignments and ideas into `destination`.
+async fn copy_collection<D: MigrationStore>(
+    destination: &Addr<D>,
+    listings: &[Collection],
+    role_assignments: &[RoleAssignment],
+    ideas: Vec<Idea>,
+) -> Result<(), APIError> {
+    for collection in listings {
+        destination
+            .send(
+                StoreCollection {
+                    collection_id: collection.collection_id,
+                    principal_id: collection.user_id,
+                    name: collection.name.clone(),
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    for role_assignment in role_assignments {
+        destination
+            .send(
+                StoreRoleAssignment {
+                    collection_id: role_assignment.collection_id,
+                    principal_id: role_assignment.user_id,
+                    role: role_assignment.role,
+                }
+                .trace(),
+            )
+            .await??;
+    }
+
+    for idea in ideas {
+        destination.send(RestoreIdea { idea }.trace()).await??;
+    }
+
+    Ok(())
+}
+
+/// The fingerprints of both stores, along with the collections whose listings, role
+/// assignments or ideas differ between them.
+struct Verification {
+    source: Fingerprint,
+    destination: Fingerprint,
+    mismatched: BTreeSet<u128>,
+}
+
+/// Reads both stores and fingerprints the records which were migrated, ignoring any
+/// additional records which the destination already held.
+async fn verify<S, D>(source: &Addr<S>, destination: &Addr<D>) -> Result<Verification, APIError>
+where
+    S: MigrationStore,
+    D: MigrationStore,
+{
+    let mut expected = Fingerprinter::default();
+    let mut actual = Fingerprinter::default();
+    let mut mismatched = BTreeSet::new();
+
+    let mut destination_users: BTreeMap<u128, User> = destination
+        .send(GetUsers {}.trace())
+        .await??
+        .into_iter()
+        .map(|user| (user.email_hash, user))
+        .collect();
+
+    for user in source.send(GetUsers {}.trace()).await?? {
+        expected.user(&user);
+        if let Some(user) = destination_users.remove(&user.email_hash) {
+            actual.user(&user);
+        }
+    }
+
+    let mut destination_collections =
+        group_collections(destination.send(GetAllCollections {}.trace()).await??);
+
+    for (collection_id, listings) in
+        group_collections(source.send(GetAllCollections {}.trace()).await??)
+    {
+        let mut collection_expected = Fingerprinter::default();
+        let mut collection_actual = Fingerprinter::default();
+
+        let mut copied_listings: BTreeMap<u128, Collection> = destination_collections
+            .remove(&collection_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|collection| (collection.user_id, collection))
+            .collect();
+        for collection in listings {
+            collection_expected.collection(&collection);
+            if let Some(copy) = copied_listings.remove(&collection.user_id) {
+                collection_actual.collection(&copy);
+            }
+        }
+
+        let mut copied_role_assignments: BTreeMap<u128, RoleAssignment> =
+            get_role_assignments(destination, collection_id)
+                .await?
+                .into_iter()
+                .map(|ra| (ra.user_id, ra))
+                .collect();
+        for role_assignment in get_role_assignments(source, collection_id).await? {
+            collection_expected.role_assignment(&role_assignment);
+            if let Some(copy) = copied_role_assignments.remove(&role_assignment.user_id) {
+                collection_actual.role_assignment(&copy);
+            }
+        }
+
+        let mut copied_ideas: BTreeMap<u128, Idea> = get_ideas(destination, collection_id)
+            .await?
+            .into_iter()
+            .map(|idea| (idea.id, idea))
+            .collect();
+        for idea in get_ideas(source, collection_id).await? {
+            collection_expected.idea(&idea);
+            if let Some(copy) = copied_ideas.remove(&idea.id) {
+                collection_actual.idea(&copy);
+            }
+        }
+
+        if collection_expected != collection_actual {
+            mismatched.insert(collection_id);
+        }
+
+        expected.append(collection_expected);
+        actual.append(collection_actual);
+    }
+
+    Ok(Verification {
+        source: expected.finish(),
+        destination: actual.finish(),
+        mismatched,
+    })
+}
+
+/// Accumulates the records of each type which are counted and checksummed. Records are
+/// hashed in the order they are provided, so both stores must be visited in the same order.
+#[derive(Default, PartialEq)]
+struct Fingerprinter {
+    users: Vec<serde_json::Value>,
+    collections: Vec<serde_json::Value>,
+    role_assignments: Vec<serde_json::Value>,
+    ideas: Vec<serde_json::Value>,
+}
+
+impl Fingerprinter {
+    fn user(&mut self, user: &User) {
+        self.users.push(serde_json::json!([
+            format!("{:0>32x}", user.email_hash),
+            format!("{:0>32x}", user.principal_id),
+            user.first_name,
+        ]));
+    }
+
+    fn collection(&mut self, collection: &Collection) {
+        self.collections.push(serde_json::json!([
+            format!("{:0>32x}", collection.collection_id),
+            format!("{:0>32x}", collection.user_id),
+            collection.name,
+        ]));
+    }
+
+    fn role_assignment(&mut self, role_assignment: &RoleAssignment) {
+        self.role_assignments.push(serde_json::json!([
+            format!("{:0>32x}", role_assignment.collection_id),
+            format!("{:0>32x}", role_assignment.user_id),
+            String::from(role_assignment.role),
+        ]));
+    }
+
+    /// Timestamps are compared to the microsecond, since stores may hold them with
+    /// different precisions.
+    fn idea(&mut self, idea: &Idea) {
+        let tags: BTreeSet<&String> = idea.tags.iter().collect();
+        let timestamp = |time: Option<DateTime<Utc>>| {
+            time.map(|time| time.to_rfc3339_opts(SecondsFormat::Micros, true))
+        };
+        self.ideas.push(serde_json::json!([
+            format!("{:0>32x}", idea.collection_id),
+            format!("{:0>32x}", idea.id),
+            idea.name,
+            idea.description,
+            tags,
+            idea.completed,
+            timestamp(idea.created_at),
+            timestamp(idea.completed_at),
+        ]));
+    }
+
+    /// Adds the records accumulated by another fingerprinter after this one's.
+    fn append(&mut self, other: Fingerprinter) {
+        self.users.extend(other.users);
+        self.collections.extend(other.collections);
+        self.role_assignments.extend(other.role_assignments);
+        self.ideas.extend(other.ideas);
+    }
+
+    fn finish(self) -> Fingerprint {
+        let finish = |records: Vec<serde_json::Value>| {
+            let mut hasher = Sha256::new();
+            for record in records.iter() {
+                hasher.update(record.to_string().as_bytes());
+                hasher.update(b"\n");
+            }
+
+            (records.len(), hex::encode(hasher.finalize()))
+        };
+
+        Fingerprint {
+            users: finish(self.users),
+            collections: finish(self.collections),
+            role_assignments: finish(self.role_assignments),
+            ideas: finish(self.ideas),
+        }
+    }
+}
+
+fn collection_key(collection_id: u128) -> String {
+    format!("{collection_id:0>32x}")
+}
+
+/// Groups the listings of each collection, which are returned once for each member, by
+/// the collection's ID.
+fn group_collections(collections: Vec<Collection>) -> BTreeMap<u128, Vec<Collection>> {
+    let mut groups: BTreeMap<u128, Vec<Collection>> = BTreeMap::new();
+    for collection in collections {
+        groups
+            .entry(collection.collection_id)
+            .or_default()
+            .push(collection);
+    }
+
+    for listings in groups.values_mut() {
+        listings.sort_by_key(|collection| collection.user_id);
+    }
+
+    groups
+}
+
+async fn get_role_assignments<S: MigrationStore>(
+    store: &Addr<S>,
+    collection_id: u128,
+) -> Result<Vec<RoleAssignment>, APIError> {
+    let mut role_assignments = store
+        .send(GetRoleAssignments { collection_id }.trace())
+        .await?
+        .or_else(empty_if_not_found)?;
+    role_assignments.sort_by_key(|ra| ra.user_id);
+    Ok(role_assignments)
+}
+
+async fn get_ideas<S: MigrationStore>(
+    store: &Addr<S>,
+    collection: u128,
+) -> Result<Vec<Idea>, APIError> {
+    let mut ideas = store
+        .send(
+            GetIdeas {
+                collection,
+                tag: None,
+                is_completed: None,
+            }
+            .trace(),
+        )
+        .await?
+        .or_else(empty_if_not_found)?;
+    ideas.sort_by_key(|idea| idea.id);
+    Ok(ideas)
+}
+
+fn empty_if_not_found<T>(err: APIError) -> Result<Vec<T>, APIError> {
+    if err.code == 404 {
+        Ok(vec![])
+    } else {
+        Err(err)
+    }
+}
+
+/// The contents of a store, in a form which can be written to and read from a file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub users: Vec<User>,
+    pub collections: Vec<Collection>,
+    pub role_assignments: Vec<RoleAssignment>,
+    pub ideas: Vec<Idea>,
+}
+
+impl Snapshot {
+    /// Reads every user, collection, role assignment and idea from a store.
+    pub async fn read<S: MigrationStore>(store: &Addr<S>) -> Result<Self, APIError> {
+        let mut snapshot = Snapshot {
+            users: store.send(GetUsers {}.trace()).await??,
+            collections: store.send(GetAllCollections {}.trace()).await??,
+            ..Default::default()
+        };
+
+        let collection_ids: BTreeSet<u128> = snapshot
+            .collections
+            .iter()
+            .map(|collection| collection.collection_id)
+            .collect();
+        for collection_id in collection_ids {
+            snapshot
+                .role_assignments
+                .extend(get_role_assignments(store, collection_id).await?);
+            snapshot
+                .ideas
+                .extend(get_ideas(store, collection_id).await?);
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot into a store.
+    pub async fn write<D: MigrationStore>(self, store: &Addr<D>) -> Result<(), APIError> {
+        for user in self.users {
+            store
+                .send(
+                    StoreUser {
+                        email_hash: user.email_hash,
+                        principal_id: user.principal_id,
+                        first_name: user.first_name,
+                    }
+                    .trace(),
+                )
+                .await??;
+        }
+
+        for (collection_id, listings) in group_collections(self.collections) {
+            let role_assignments: Vec<RoleAssignment> = self
+                .role_assignments
+                .iter()
+                .filter(|ra| ra.collection_id == collection_id)
+                .cloned()
+                .collect();
+            let ideas = self
+                .ideas
+                .iter()
+                .filter(|idea| idea.collection_id == collection_id)
+                .cloned()
+                .collect();
+
+            copy_collection(store, &listings, &role_assignments, ideas).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_state;
+
+    macro_rules! source_state {
+        ($state:ident) => {
+            test_state!(
+                $state = [
+                    StoreUser {
+                        email_hash: 1,
+                        principal_id: 2,
+                        first_name: "Alice".into(),
+                    },
+                    StoreCollection {
+                        collection_id: 10,
+                        principal_id: 2,
+                        name: "Weekend".into(),
+                    },
+                    StoreRoleAssignment {
+                        collection_id: 10,
+                        principal_id: 2,
+                        role: Role::Owner,
+                    },
+                    StoreIdea {
+                        collection: 10,
+                        id: 11,
+                        name: "Go hiking".into(),
+                        tags: hashset!("outdoors", "active"),
+                        completed: true,
+                        ..Default::default()
+                    },
+                    StoreCollection {
+                        collection_id: 20,
+                        principal_id: 2,
+                        name: "Empty".into(),
+                    }
+                ]
+            );
+        };
+    }
+
+    fn checkpoint_path(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("rex-migration-{name}-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[actix_rt::test]
+    async fn migrate_all() {
+        source_state!(source);
+        test_state!(destination = []);
+
+        let checkpoint = checkpoint_path("all");
+        let options = MigrationOptions {
+            dry_run: false,
+            checkpoint: Some(checkpoint.clone()),
+        };
+
+        let report = migrate(&source.store, &destination.store, &options)
+            .await
+            .expect("the migration should succeed");
+
+        assert_eq!(
+            report.users,
+            Tally {
+                found: 1,
+                copied: 1,
+                resumed: 0
+            }
+        );
+        assert_eq!(report.collections.copied, 2);
+        assert_eq!(report.role_assignments.copied, 1);
+        assert_eq!(report.ideas.copied, 1);
+        assert!(report.verified(), "{report}");
+        assert!(
+            !checkpoint.exists(),
+            "the checkpoint should be removed once the migration has been verified"
+        );
+
+        let idea = destination
+            .store
+            .send(GetIdea {
+                collection: 10,
+                id: 11,
+            })
+            .await
+            .expect("the actor should respond")
+            .expect("the idea should have been migrated");
+        assert_eq!(idea.name, "Go hiking");
+        assert!(idea.completed);
+        assert!(idea.tags.contains("outdoors"));
+
+        let original = source
+            .store
+            .send(GetIdea {
+                collection: 10,
+                id: 11,
+            })
+            .await
+            .expect("the actor should respond")
+            .expect("the idea should still exist in the source");
+        assert!(original.created_at.is_some());
+        assert_eq!(idea.created_at, original.created_at);
+        assert_eq!(idea.completed_at, original.completed_at);
+
+        assert!(report.to_string().contains(NOT_MIGRATED));
+    }
+
+    #[actix_rt::test]
+    async fn dry_run() {
+        source_state!(source);
+        test_state!(destination = []);
+
+        let report = migrate(
+            &source.store,
+            &destination.store,
+            &MigrationOptions {
+                dry_run: true,
+                checkpoint: None,
+            },
+        )
+        .await
+        .expect("the dry run should succeed");
+
+        assert_eq!(report.collections.found, 2);
+        assert_eq!(report.ideas.found, 1);
+        assert!(report.verification.is_none());
+        assert!(report.to_string().starts_with("Dry run"), "{report}");
+
+        let users = destination
+            .store
+            .send(GetUsers {})
+            .await
+            .expect("the actor should respond")
+            .expect("the users should be listed");
+        assert!(users.is_empty(), "a dry run should not write anything");
+    }
+
+    #[actix_rt::test]
+    async fn resume() {
+        source_state!(source);
+        test_state!(destination = []);
+
+        let checkpoint = checkpoint_path("resume");
+        std::fs::write(
+            &checkpoint,
+            serde_json::to_string(&Checkpoint {
+                users: true,
+                collections: BTreeSet::from([collection_key(20)]),
+            })
+            .unwrap(),
+        )
+        .unwrap();
+
+        let report = migrate(
+            &source.store,
+            &destination.store,
+            &MigrationOptions {
+                dry_run: false,
+                checkpoint: Some(checkpoint.clone()),
+            },
+        )
+        .await
+        .expect("the migration should succeed");
+
+        assert_eq!(report.users.resumed, 1);
+        assert_eq!(report.collections.resumed, 1);
+        assert_eq!(report.collections.copied, 1);
+        assert_eq!(report.ideas.copied, 1);
+
+        // The work recorded by the checkpoint was never actually done, so verification
+        // should notice that the destination is missing the user and empty collection.
+        assert!(!report.verified(), "{report}");
+        assert!(report.to_string().contains("MISMATCH"), "{report}");
+        assert!(
+            checkpoint.exists(),
+            "the checkpoint should be kept when verification fails"
+        );
+
+        let options = MigrationOptions {
+            dry_run: false,
+            checkpoint: Some(checkpoint.clone()),
+        };
+        let saved = Checkpoint::load(&options).expect("the checkpoint should be readable");
+        assert!(!saved.users, "the mismatched users should be copied again");
+        assert_eq!(
+            saved.collections,
+            BTreeSet::from([collection_key(10)]),
+            "only the verified collection should be skipped when resuming"
+        );
+
+        let report = migrate(&source.store, &destination.store, &options)
+            .await
+            .expect("the resumed migration should succeed");
+        assert_eq!(report.users.copied, 1);
+        assert_eq!(report.collections.copied, 1);
+        assert_eq!(report.collections.resumed, 1);
+        assert!(report.verified(), "{report}");
+        assert!(!checkpoint.exists());
+    }
+
+    #[actix_rt::test]
+    async fn shared_collection() {
+        test_state!(
+            source = [
+                StoreCollection {
+                    collection_id: 30,
+                    principal_id: 2,
+                    name: "Household".into(),
+                },
+                StoreCollection {
+                    collection_id: 30,
+                    principal_id: 3,
+                    name: "Home".into(),
+                },
+                StoreRoleAssignment {
+                    collection_id: 30,
+                    principal_id: 2,
+                    role: Role::Owner,
+                },
+                StoreRoleAssignment {
+                    collection_id: 30,
+                    principal_id: 3,
+                    role: Role::Contributor,
+                },
+                StoreIdea {
+                    collection: 30,
+                    id: 31,
+                    name: "Paint the fence".into(),
+                    ..Default::default()
+                }
+            ]
+        );
+        test_state!(destination = []);
+
+        let report = migrate(&source.store, &destination.store, &Default::default())
+            .await
+            .expect("the migration should succeed");
+
+        assert_eq!(report.collections.found, 2);
+        assert_eq!(report.collections.copied, 2);
+        assert_eq!(report.role_assignments.found, 2);
+        assert_eq!(report.role_assignments.copied, 2);
+        assert_eq!(report.ideas.found, 1);
+        assert_eq!(report.ideas.copied, 1);
+        assert!(report.verified(), "{report}");
+
+        let (source_fingerprint, _) = report.verification.expect("the migration is verified");
+        assert_eq!(source_fingerprint.role_assignments.0, 2);
+        assert_eq!(source_fingerprint.ideas.0, 1);
+
+        let snapshot = Snapshot::read(&destination.store)
+            .await
+            .expect("the snapshot should be read");
+        assert_eq!(snapshot.collections.len(), 2);
+        assert_eq!(snapshot.role_assignments.len(), 2);
+        assert_eq!(snapshot.ideas.len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn snapshot_round_trip() {
+        source_state!(source);
+
+        let snapshot = Snapshot::read(&source.store)
+            .await
+            .expect("the snapshot should be read");
+        let json = serde_json::to_string(&snapshot).expect("the snapshot should serialize");
+        let snapshot: Snapshot = serde_json::from_str(&json).expect("the snapshot should parse");
+        assert_eq!(snapshot.collections.len(), 2);
+        assert_eq!(snapshot.ideas.len(), 1);
+
+        test_state!(destination = []);
+        snapshot
+            .write(&destination.store)
+            .await
+            .expect("the snapshot should be written");
+
+        let verification = verify(&source.store, &destination.store)
+            .await
+            .expect("the stores should be verified");
+        assert_eq!(verification.source, verification.destination);
+        assert!(verification.mismatched.is_empty());
+        assert_eq!(verification.source.ideas.0, 1);
+    }
+}
@@ -1,11 +1,66 @@
-#[cfg(any(test, not(feature = "table_storage")))]
 mod memory;
+pub mod migration;
+
+// The entities are compiled into the tests even though the Table Storage backend is not,
+// so that upgrades of rows written by older versions of Rex can be tested.
+#[cfg(any(test, feature = "table_storage"))]
+#[cfg_attr(test, allow(dead_code))]
+mod schema;
+
+pub use memory::MemoryStore;
 
 #[cfg(any(test, not(feature = "table_storage")))]
 pub type Store = memory::MemoryStore;
 
+/// The name of the backend which [`Store`] uses in this build.
+#[cfg(any(test, not(feature = "table_storage")))]
+pub const BACKEND: &str = "memory";
+
 #[cfg(all(not(test), feature = "table_storage"))]
 mod tablestorage;
 
 #[cfg(all(not(test), feature = "table_storage"))]
 pub type Store = tablestorage::TableStorage;
+
+#[cfg(all(not(test), feature = "table_storage"))]
+pub const BACKEND: &str = "tablestorage";
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StoreConfig {
+    /// The connection string for the Azure Storage account which is used when Rex is
+    /// built with the `table_storage` feature.
+    ///
+    /// Rex does not create the tables it uses, so the account must already contain the
+    /// `ideas`, `ideatags`, `collections`, `roleassignments`, `users`, `chatlinks`,
+    /// `invitations`, `invitelinks`, `sharelinks`, `ideaschedules`, `calendarfeeds`,
+    /// `webhooks`, `webhookdeliveries` and `auditlog` tables.
+    pub table_storage_connection_string: Option<String>,
+
+    /// Rewrites any entities which were stored with an older version of their schema in
+    /// the background once the server has started.
+    pub upgrade_schema_on_start: bool,
+}
+
+/// Rewrites the entities which were stored with an older version of their schema, logging
+/// how many were upgraded in each table.
+pub async fn upgrade_schema(state: crate::models::GlobalState) {
+    use crate::telemetry::TraceMessageExt;
+
+    match state
+        .store
+        .send(crate::models::UpgradeSchema { dry_run: false }.trace())
+        .await
+    {
+        Ok(Ok(tables)) => {
+            for table in tables.iter().filter(|t| t.upgraded > 0) {
+                info!(
+                    "Upgraded {} of the {} entities in the {} table to the current schema.",
+                    table.upgraded, table.scanned, table.table
+                );
+            }
+        }
+        Ok(Err(err)) => error!("The store schema could not be upgraded: {}", err),
+        Err(err) => error!("The store schema could not be upgraded: {}", err),
+    }
+}
@@ -0,0 +1,750 @@
+//! The entities in which models are persisted to Azure Table Storage.
+//!
+//! Every entity records the version of the schema it was written with. Rows which were
+//! written by an older version of Rex are upgraded as they are read, and can be rewritten
+//! in bulk with the [`UpgradeSchema`](crate::models::UpgradeSchema) message.
+
+use crate::models::{self, *};
+use std::collections::{BTreeSet, HashSet};
+
+/// An entity whose stored representation may have changed since it was written.
+pub trait Versioned: Sized {
+    /// The version of the schema which is written by this build of Rex. Rows written
+    /// before entities were versioned have no `SchemaVersion` and are treated as version 0.
+    const SCHEMA_VERSION: u32;
+
+    fn schema_version(&self) -> u32;
+
+    /// The partition and row keys under which the entity is stored.
+    fn keys(&self) -> (&str, &str);
+
+    /// Applies each of the upgrades between the entity's schema version and the current one.
+    fn upgrade(self) -> Self;
+}
+
+/// Implements [`Versioned`] for an entity, optionally with a function which upgrades an
+/// entity written with the given version to the next one.
+macro_rules! versioned {
+    ($entity:ty [$pk:ident, $rk:ident]: $version:literal) => {
+        versioned!($entity [$pk, $rk]: $version => |entity, _version| entity);
+    };
+
+    ($entity:ty [$pk:ident, $rk:ident]: $version:literal => $upgrade:expr) => {
+        impl Versioned for $entity {
+            const SCHEMA_VERSION: u32 = $version;
+
+            fn schema_version(&self) -> u32 {
+                self.schema_version
+            }
+
+            fn keys(&self) -> (&str, &str) {
+                (&self.$pk, &self.$rk)
+            }
+
+            fn upgrade(mut self) -> Self {
+                let upgrade: fn(Self, u32) -> Self = $upgrade;
+                while self.schema_version < Self::SCHEMA_VERSION {
+                    let version = self.schema_version;
+                    self = upgrade(self, version);
+                    self.schema_version = version + 1;
+                }
+
+                self
+            }
+        }
+    };
+}
+
+versioned!(TableStorageIdea[collection_id, id]: 2 => upgrade_idea);
+versioned!(TableStorageIdeaTag[partition_key, idea_id]: 1);
+versioned!(TableStorageCollection[principal_id, collection_id]: 1);
+versioned!(TableStorageRoleAssignment[collection_id, principal_id]: 1);
+versioned!(TableStorageUser[email_hash, row_key]: 1);
+versioned!(TableStorageChatLink[chat_user_hash, row_key]: 1);
+versioned!(TableStorageInvitation[collection_id, email_hash]: 1);
+versioned!(TableStorageInviteLink[collection_id, id]: 1);
+versioned!(TableStorageShareLink[collection_id, id]: 1);
+versioned!(TableStorageIdeaSchedule[collection_id, idea_id]: 1);
+versioned!(TableStorageCalendarFeed[principal_id, id]: 1);
+versioned!(TableStorageWebhook[collection_id, id]: 1);
+versioned!(TableStorageWebhookDelivery[webhook_id, id]: 1);
+versioned!(TableStorageAuditEntry[principal_id, id]: 1);
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageIdea {
+    #[serde(rename = "PartitionKey")]
+    pub collection_id: String,
+    #[serde(rename = "RowKey")]
+    pub id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Description")]
+    pub description: String,
+    /// A JSON array of the idea's tags, which were separated by commas before version 2.
+    #[serde(rename = "Tags")]
+    pub tags: String,
+    #[serde(rename = "Completed")]
+    pub completed: bool,
+    #[serde(rename = "CreatedAt", default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(
+        rename = "CompletedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl TableStorageIdea {
+    pub fn encode_tags(tags: &HashSet<String>) -> String {
+        let tags: BTreeSet<&String> = tags.iter().collect();
+        serde_json::to_string(&tags).unwrap_or_else(|_| "[]".into())
+    }
+
+    pub fn tags(&self) -> HashSet<String> {
+        serde_json::from_str(&self.tags).unwrap_or_default()
+    }
+
+    /// Whether the idea has exactly the tag, and completion state, which a query asked for.
+    pub fn matches(&self, tag: Option<&str>, is_completed: Option<bool>) -> bool {
+        tag.is_none_or(|tag| self.tags().contains(tag))
+            && is_completed.is_none_or(|completed| self.completed == completed)
+    }
+
+    /// The entries which list this idea in the `ideatags` index.
+    pub fn tag_index(&self) -> Vec<TableStorageIdeaTag> {
+        let collection_id = u128::from_str_radix(&self.collection_id, 16).unwrap_or_default();
+
+        self.tags()
+            .iter()
+            .map(|tag| TableStorageIdeaTag {
+                partition_key: TableStorageIdeaTag::partition_key(collection_id, tag),
+                idea_id: self.id.clone(),
+                schema_version: TableStorageIdeaTag::SCHEMA_VERSION,
+                completed: self.completed,
+            })
+            .collect()
+    }
+}
+
+fn upgrade_idea(mut entity: TableStorageIdea, version: u32) -> TableStorageIdea {
+    if version == 1 {
+        // Tags were joined with a leading comma, which is skipped along with any other
+        // empty tags when they are split.
+        let tags: HashSet<String> = entity
+            .tags
+            .split(',')
+            .filter(|t| !t.is_empty())
+            .map(|t| t.to_string())
+            .collect();
+        entity.tags = TableStorageIdea::encode_tags(&tags);
+    }
+
+    entity
+}
+
+impl From<TableStorageIdea> for Idea {
+    fn from(entity: TableStorageIdea) -> Self {
+        Self {
+            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            name: entity.name.clone(),
+            tags: entity.tags(),
+            description: entity.description.clone(),
+            completed: entity.completed,
+            created_at: entity.created_at,
+            completed_at: entity.completed_at,
+        }
+    }
+}
+
+/// An entry in the index of the ideas in each collection which have a given tag, allowing
+/// them to be found without reading every idea in the collection. Ideas stored before
+/// version 2 are added to the index the first time a collection is queried by tag, or
+/// when the schema is upgraded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageIdeaTag {
+    #[serde(rename = "PartitionKey")]
+    pub partition_key: String,
+    #[serde(rename = "RowKey")]
+    pub idea_id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    /// Whether the idea was completed when it was last stored, allowing the index to be
+    /// filtered in the same way as the ideas themselves.
+    #[serde(rename = "Completed")]
+    pub completed: bool,
+}
+
+impl TableStorageIdeaTag {
+    /// Tags are hex encoded, as they may contain characters which are not permitted in keys.
+    pub fn partition_key(collection_id: u128, tag: &str) -> String {
+        format!("{collection_id:0>32x}-{}", hex::encode(tag))
+    }
+
+    /// Records that every idea in a collection has been added to the index. Tags are never
+    /// empty, so this cannot be mistaken for the entry of an idea.
+    pub fn indexed_marker(collection_id: u128) -> Self {
+        Self {
+            partition_key: Self::partition_key(collection_id, ""),
+            idea_id: format!("{:0>32x}", 0),
+            schema_version: Self::SCHEMA_VERSION,
+            completed: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageCollection {
+    #[serde(rename = "PartitionKey")]
+    pub principal_id: String,
+    #[serde(rename = "RowKey")]
+    pub collection_id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "Name")]
+    pub name: String,
+}
+
+impl From<TableStorageCollection> for Collection {
+    fn from(entity: TableStorageCollection) -> Self {
+        Self {
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            user_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
+            name: entity.name.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageRoleAssignment {
+    #[serde(rename = "PartitionKey")]
+    pub collection_id: String,
+    #[serde(rename = "RowKey")]
+    pub principal_id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "Role")]
+    pub role: String,
+}
+
+impl From<TableStorageRoleAssignment> for RoleAssignment {
+    fn from(entity: TableStorageRoleAssignment) -> Self {
+        Self {
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            user_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
+            role: entity.role.as_str().into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageUser {
+    #[serde(rename = "PartitionKey")]
+    pub email_hash: String,
+
+    #[serde(rename = "RowKey")]
+    pub row_key: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "PrincipalId")]
+    pub principal_id: String,
+
+    #[serde(rename = "FirstName")]
+    pub first_name: String,
+}
+
+impl From<TableStorageUser> for models::User {
+    fn from(entity: TableStorageUser) -> Self {
+        Self {
+            email_hash: u128::from_str_radix(&entity.email_hash, 16).unwrap_or_default(),
+            principal_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
+            first_name: entity.first_name.as_str().into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageChatLink {
+    #[serde(rename = "PartitionKey")]
+    pub chat_user_hash: String,
+    #[serde(rename = "RowKey")]
+    pub row_key: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "TeamId")]
+    pub team_id: String,
+    #[serde(rename = "UserId")]
+    pub user_id: String,
+    #[serde(rename = "PrincipalId")]
+    pub principal_id: String,
+    #[serde(rename = "CreatedAt")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TableStorageChatLink> for ChatLink {
+    fn from(entity: TableStorageChatLink) -> Self {
+        Self {
+            team_id: entity.team_id,
+            user_id: entity.user_id,
+            principal_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
+            created_at: entity.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageInvitation {
+    #[serde(rename = "PartitionKey")]
+    pub collection_id: String,
+    #[serde(rename = "RowKey")]
+    pub email_hash: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "Role")]
+    pub role: String,
+    #[serde(rename = "InvitedBy")]
+    pub invited_by: String,
+}
+
+impl From<TableStorageInvitation> for Invitation {
+    fn from(entity: TableStorageInvitation) -> Self {
+        Self {
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            email_hash: u128::from_str_radix(&entity.email_hash, 16).unwrap_or_default(),
+            role: entity.role.as_str().into(),
+            invited_by: u128::from_str_radix(&entity.invited_by, 16).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageInviteLink {
+    #[serde(rename = "PartitionKey")]
+    pub collection_id: String,
+    #[serde(rename = "RowKey")]
+    pub id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "Role")]
+    pub role: String,
+    #[serde(rename = "MaxUses")]
+    pub max_uses: u32,
+    #[serde(rename = "Uses")]
+    pub uses: u32,
+    #[serde(rename = "ExpiresAt")]
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "CreatedBy")]
+    pub created_by: String,
+}
+
+impl From<TableStorageInviteLink> for InviteLink {
+    fn from(entity: TableStorageInviteLink) -> Self {
+        Self {
+            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            role: entity.role.as_str().into(),
+            max_uses: entity.max_uses,
+            uses: entity.uses,
+            expires_at: entity.expires_at,
+            created_by: u128::from_str_radix(&entity.created_by, 16).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageShareLink {
+    #[serde(rename = "PartitionKey")]
+    pub collection_id: String,
+    #[serde(rename = "RowKey")]
+    pub id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "CreatedBy")]
+    pub created_by: String,
+    #[serde(rename = "CreatedAt")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TableStorageShareLink> for ShareLink {
+    fn from(entity: TableStorageShareLink) -> Self {
+        Self {
+            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            created_by: u128::from_str_radix(&entity.created_by, 16).unwrap_or_default(),
+            created_at: entity.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageIdeaSchedule {
+    #[serde(rename = "PartitionKey")]
+    pub collection_id: String,
+    #[serde(rename = "RowKey")]
+    pub idea_id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "Date")]
+    pub date: String,
+    #[serde(rename = "ScheduledBy")]
+    pub scheduled_by: String,
+    #[serde(rename = "ScheduledAt")]
+    pub scheduled_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TableStorageIdeaSchedule> for IdeaSchedule {
+    fn from(entity: TableStorageIdeaSchedule) -> Self {
+        Self {
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            idea_id: u128::from_str_radix(&entity.idea_id, 16).unwrap_or_default(),
+            date: entity.date.parse().unwrap_or_default(),
+            scheduled_by: u128::from_str_radix(&entity.scheduled_by, 16).unwrap_or_default(),
+            scheduled_at: entity.scheduled_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageCalendarFeed {
+    #[serde(rename = "PartitionKey")]
+    pub principal_id: String,
+    #[serde(rename = "RowKey")]
+    pub id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "CreatedAt")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TableStorageCalendarFeed> for CalendarFeed {
+    fn from(entity: TableStorageCalendarFeed) -> Self {
+        Self {
+            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
+            principal_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
+            created_at: entity.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageWebhook {
+    #[serde(rename = "PartitionKey")]
+    pub collection_id: String,
+    #[serde(rename = "RowKey")]
+    pub id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "Url")]
+    pub url: String,
+    #[serde(rename = "Secret")]
+    pub secret: String,
+    #[serde(rename = "Events")]
+    pub events: String,
+    #[serde(rename = "Enabled")]
+    pub enabled: bool,
+    #[serde(rename = "Failures")]
+    pub failures: u32,
+    #[serde(rename = "CreatedBy")]
+    pub created_by: String,
+    #[serde(rename = "CreatedAt")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TableStorageWebhook> for Webhook {
+    fn from(entity: TableStorageWebhook) -> Self {
+        Self {
+            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
+            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
+            url: entity.url,
+            secret: entity.secret,
+            events: entity
+                .events
+                .split(',')
+                .filter(|e| !e.is_empty())
+                .map(|e| e.to_string())
+                .collect(),
+            enabled: entity.enabled,
+            failures: entity.failures,
+            created_by: u128::from_str_radix(&entity.created_by, 16).unwrap_or_default(),
+            created_at: entity.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageWebhookDelivery {
+    #[serde(rename = "PartitionKey")]
+    pub webhook_id: String,
+    #[serde(rename = "RowKey")]
+    pub id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "EventId")]
+    pub event_id: String,
+    #[serde(rename = "EventType")]
+    pub event_type: String,
+    #[serde(rename = "Attempt")]
+    pub attempt: u32,
+    /// The HTTP status code returned by the receiver, or zero if no response was received.
+    #[serde(rename = "StatusCode")]
+    pub status_code: u16,
+    #[serde(rename = "Error")]
+    pub error: String,
+    #[serde(rename = "Succeeded")]
+    pub succeeded: bool,
+    #[serde(rename = "DeliveredAt")]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<TableStorageWebhookDelivery> for WebhookDelivery {
+    fn from(entity: TableStorageWebhookDelivery) -> Self {
+        Self {
+            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
+            webhook_id: u128::from_str_radix(&entity.webhook_id, 16).unwrap_or_default(),
+            event_id: entity.event_id.parse().unwrap_or_default(),
+            event_type: entity.event_type,
+            attempt: entity.attempt,
+            status_code: Some(entity.status_code).filter(|&code| code != 0),
+            error: Some(entity.error).filter(|err| !err.is_empty()),
+            succeeded: entity.succeeded,
+            timestamp: entity.timestamp,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TableStorageAuditEntry {
+    #[serde(rename = "PartitionKey")]
+    pub principal_id: String,
+    #[serde(rename = "RowKey")]
+    pub id: String,
+
+    #[serde(rename = "SchemaVersion", default)]
+    pub schema_version: u32,
+
+    #[serde(rename = "OccurredAt")]
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "Action")]
+    pub action: String,
+    #[serde(rename = "Target")]
+    pub target: String,
+}
+
+impl From<TableStorageAuditEntry> for AuditEntry {
+    fn from(entity: TableStorageAuditEntry) -> Self {
+        Self {
+            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
+            principal_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
+            timestamp: entity.timestamp,
+            action: entity.action,
+            target: entity.target,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_idea() {
+        // Ideas written before entities were versioned had no timestamps and a leading
+        // separator in their tags.
+        let entity: TableStorageIdea = serde_json::from_value(serde_json::json!({
+            "PartitionKey": "0000000000000000000000000000000a",
+            "RowKey": "0000000000000000000000000000000b",
+            "Timestamp": "2020-01-01T00:00:00Z",
+            "Name": "Go hiking",
+            "Description": "Somewhere with a view",
+            "Tags": ",outdoors,active",
+            "Completed": false,
+        }))
+        .expect("a legacy idea should deserialize");
+        assert_eq!(entity.schema_version(), 0);
+
+        let entity = entity.upgrade();
+        assert_eq!(entity.schema_version(), TableStorageIdea::SCHEMA_VERSION);
+        assert_eq!(
+            entity.keys(),
+            (
+                "0000000000000000000000000000000a",
+                "0000000000000000000000000000000b"
+            )
+        );
+
+        assert_eq!(entity.tags, r#"["active","outdoors"]"#);
+
+        let idea: Idea = entity.into();
+        assert_eq!(idea.collection_id, 10);
+        assert_eq!(idea.id, 11);
+        assert_eq!(idea.name, "Go hiking");
+        assert_eq!(idea.tags, hashset!("outdoors", "active"));
+        assert!(!idea.completed);
+        assert!(idea.created_at.is_none());
+    }
+
+    #[test]
+    fn current_idea() {
+        let entity: TableStorageIdea = serde_json::from_value(serde_json::json!({
+            "PartitionKey": "0000000000000000000000000000000a",
+            "RowKey": "0000000000000000000000000000000b",
+            "SchemaVersion": TableStorageIdea::SCHEMA_VERSION,
+            "Name": "Go hiking",
+            "Description": "",
+            "Tags": r#"["outdoors","party games"]"#,
+            "Completed": true,
+            "CreatedAt": "2024-05-01T10:00:00Z",
+            "CompletedAt": "2024-05-02T10:00:00Z",
+        }))
+        .expect("an idea should deserialize");
+
+        let idea: Idea = entity.upgrade().into();
+        assert_eq!(idea.tags, hashset!("outdoors", "party games"));
+        assert!(idea.completed);
+        assert_eq!(
+            idea.completed_at.map(|t| t.to_rfc3339()),
+            Some("2024-05-02T10:00:00+00:00".into())
+        );
+    }
+
+    #[test]
+    fn version_one_idea() {
+        let entity: TableStorageIdea = serde_json::from_value(serde_json::json!({
+            "PartitionKey": "0000000000000000000000000000000a",
+            "RowKey": "0000000000000000000000000000000b",
+            "SchemaVersion": 1,
+            "Name": "Go hiking",
+            "Description": "",
+            "Tags": ",party,art",
+            "Completed": false,
+        }))
+        .expect("a version 1 idea should deserialize");
+
+        let entity = entity.upgrade();
+        assert_eq!(entity.schema_version(), 2);
+        assert_eq!(entity.tags(), hashset!("art", "party"));
+    }
+
+    #[test]
+    fn unindexed_legacy_idea() {
+        // An idea written before the tag index existed has no entries in it, so the
+        // collection must be scanned and matched exactly until it has been backfilled.
+        let entity: TableStorageIdea = serde_json::from_value(serde_json::json!({
+            "PartitionKey": "0000000000000000000000000000000a",
+            "RowKey": "0000000000000000000000000000000b",
+            "SchemaVersion": 1,
+            "Name": "Dance",
+            "Description": "",
+            "Tags": ",party,art",
+            "Completed": false,
+        }))
+        .expect("a version 1 idea should deserialize");
+        let entity = entity.upgrade();
+
+        assert!(entity.matches(Some("art"), None));
+        assert!(entity.matches(Some("party"), Some(false)));
+        assert!(
+            !entity.matches(Some("par"), None),
+            "tags must match exactly"
+        );
+        assert!(!entity.matches(Some("art"), Some(true)));
+        assert!(entity.matches(None, None));
+
+        let mut index: Vec<String> = entity
+            .tag_index()
+            .into_iter()
+            .map(|entry| {
+                assert_eq!(entry.idea_id, "0000000000000000000000000000000b");
+                entry.partition_key
+            })
+            .collect();
+        index.sort();
+        assert_eq!(
+            index,
+            vec![
+                TableStorageIdeaTag::partition_key(10, "art"),
+                TableStorageIdeaTag::partition_key(10, "party"),
+            ]
+        );
+
+        let marker = TableStorageIdeaTag::indexed_marker(10);
+        assert!(!index.contains(&marker.partition_key));
+        assert!(
+            !marker
+                .partition_key
+                .contains(&TableStorageIdeaTag::partition_key(11, ""))
+        );
+    }
+
+    #[test]
+    fn tag_encoding() {
+        let tags = hashset!("party", "art", "board games");
+        let encoded = TableStorageIdea::encode_tags(&tags);
+        assert_eq!(encoded, r#"["art","board games","party"]"#);
+
+        assert_eq!(
+            TableStorageIdea::encode_tags(&HashSet::new()),
+            "[]",
+            "an idea without tags should not be stored with an empty tag"
+        );
+
+        assert_eq!(
+            TableStorageIdeaTag::partition_key(10, "art"),
+            "0000000000000000000000000000000a-617274"
+        );
+        assert_ne!(
+            TableStorageIdeaTag::partition_key(10, "art"),
+            TableStorageIdeaTag::partition_key(10, "party"),
+        );
+    }
+
+    #[test]
+    fn legacy_collection() {
+        let entity: TableStorageCollection = serde_json::from_value(serde_json::json!({
+            "PartitionKey": "00000000000000000000000000000002",
+            "RowKey": "00000000000000000000000000000001",
+            "Name": "Weekend",
+        }))
+        .expect("a legacy collection should deserialize");
+
+        let entity = entity.upgrade();
+        assert_eq!(entity.schema_version, 1);
+
+        let serialized = serde_json::to_value(&entity).expect("the entity should serialize");
+        assert_eq!(serialized["SchemaVersion"], 1);
+
+        let collection: Collection = entity.into();
+        assert_eq!(collection.collection_id, 1);
+        assert_eq!(collection.user_id, 2);
+        assert_eq!(collection.name, "Weekend");
+    }
+}
@@ -1,8 +1,7 @@
+use super::StoreConfig;
+use super::schema::*;
 use crate::api::APIError;
-use crate::{
-    models::{self, *},
-    trace_handler,
-};
+use crate::models::{self, *};
 use actix::prelude::*;
 use azure_data_tables::prelude::*;
 use azure_storage::StorageCredentials;
@@ -10,25 +9,45 @@ use futures::{Future, StreamExt};
 use rand::seq::IteratorRandom;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
-use std::{fmt::Debug, pin::Pin, sync::Arc};
+use std::{collections::BTreeSet, fmt::Debug, pin::Pin, sync::Arc};
 use tracing_batteries::prelude::*;
 
 type TableReference = Arc<TableClient>;
 
+/// The number of times redeeming an invite link is attempted when it is being redeemed by
+/// several people at once.
+const INVITE_LINK_REDEMPTION_ATTEMPTS: usize = 5;
+
 pub struct TableStorage {
     started_at: chrono::DateTime<chrono::Utc>,
 
     ideas: TableReference,
+    idea_tags: TableReference,
     role_assignments: TableReference,
     collections: TableReference,
     users: TableReference,
+    chat_links: TableReference,
+    invitations: TableReference,
+    invite_links: TableReference,
+    share_links: TableReference,
+    idea_schedules: TableReference,
+    calendar_feeds: TableReference,
+    webhooks: TableReference,
+    webhook_deliveries: TableReference,
+    audit_log: TableReference,
+
+    events: EventBus,
+
+    /// A token which is held by every operation while it is in progress, allowing
+    /// [`FlushStore`] to wait until they have all completed.
+    in_flight: Arc<()>,
 }
 
 impl TableStorage {
-    pub fn new() -> Self {
-        let connection_string = std::env::var("TABLE_STORAGE_CONNECTION_STRING").expect("Set the TABLE_STORAGE_CONNECTION_STRING environment variable before starting the server.");
+    pub fn new(events: EventBus, config: &StoreConfig) -> Self {
+        let connection_string = config.table_storage_connection_string.as_deref().expect("Set store.table_storage_connection_string in your configuration before starting the server.");
 
-        let creds = azure_storage::ConnectionString::new(&connection_string)
+        let creds = azure_storage::ConnectionString::new(connection_string)
             .expect("a valid connection string");
 
         let table_service = TableServiceClient::new(
@@ -48,17 +67,41 @@ impl TableStorage {
         );
 
         let ideas_table = table_service.table_client("ideas");
+        let idea_tags_table = table_service.table_client("ideatags");
         let role_assignments_table = table_service.table_client("roleassignments");
         let collections_table = table_service.table_client("collections");
         let users_table = table_service.table_client("users");
+        let chat_links_table = table_service.table_client("chatlinks");
+        let invitations_table = table_service.table_client("invitations");
+        let invite_links_table = table_service.table_client("invitelinks");
+        let share_links_table = table_service.table_client("sharelinks");
+        let idea_schedules_table = table_service.table_client("ideaschedules");
+        let calendar_feeds_table = table_service.table_client("calendarfeeds");
+        let webhooks_table = table_service.table_client("webhooks");
+        let webhook_deliveries_table = table_service.table_client("webhookdeliveries");
+        let audit_log_table = table_service.table_client("auditlog");
 
         Self {
             started_at: chrono::Utc::now(),
 
             ideas: TableReference::new(ideas_table),
+            idea_tags: TableReference::new(idea_tags_table),
             collections: TableReference::new(collections_table),
             role_assignments: TableReference::new(role_assignments_table),
             users: TableReference::new(users_table),
+            chat_links: TableReference::new(chat_links_table),
+            invitations: TableReference::new(invitations_table),
+            invite_links: TableReference::new(invite_links_table),
+            share_links: TableReference::new(share_links_table),
+            idea_schedules: TableReference::new(idea_schedules_table),
+            calendar_feeds: TableReference::new(calendar_feeds_table),
+            webhooks: TableReference::new(webhooks_table),
+            webhook_deliveries: TableReference::new(webhook_deliveries_table),
+            audit_log: TableReference::new(audit_log_table),
+
+            events,
+
+            in_flight: Arc::new(()),
         }
     }
 
@@ -71,7 +114,7 @@ impl TableStorage {
         not_found_err: APIError,
     ) -> Result<T, APIError>
     where
-        ST: DeserializeOwned + Clone + Sync + Send,
+        ST: Versioned + DeserializeOwned + Clone + Sync + Send,
         T: From<ST>,
     {
         let result: ST = table
@@ -86,19 +129,38 @@ impl TableStorage {
             })?
             .entity;
 
-        Ok(result.into())
+        Ok(result.upgrade().into())
     }
 
     #[instrument(err, skip(table, filter), fields(otel.kind = "client", db.system = "TABLESTORAGE", db.operation = "LIST", db.statement = %query))]
     async fn get_all_entities<ST, P>(
         table: TableReference,
-        _type_name: &str,
+        type_name: &str,
         query: String,
         filter: P,
     ) -> Result<Vec<ST>, APIError>
     where
-        ST: Serialize + DeserializeOwned + Clone + Sync + Send,
+        ST: Versioned + Serialize + DeserializeOwned + Clone + Sync + Send,
         P: Fn(&ST) -> bool,
+    {
+        let entries: Vec<ST> = TableStorage::query_entities(table, type_name, query).await?;
+
+        Ok(entries
+            .into_iter()
+            .map(Versioned::upgrade)
+            .filter(|e| filter(e))
+            .collect())
+    }
+
+    /// Retrieves the entities matching a query exactly as they are stored, without
+    /// upgrading those which were written with an older schema.
+    async fn query_entities<ST>(
+        table: TableReference,
+        _type_name: &str,
+        query: String,
+    ) -> Result<Vec<ST>, APIError>
+    where
+        ST: Serialize + DeserializeOwned + Clone + Sync + Send,
     {
         let mut entries: Vec<ST> = vec![];
 
@@ -120,7 +182,7 @@ impl TableStorage {
             entries.append(&mut result.entities);
         }
 
-        Ok(entries.iter().filter(|&e| filter(e)).cloned().collect())
+        Ok(entries)
     }
 
     #[instrument(err, skip(table, filter), fields(otel.kind = "client", db.system = "TABLESTORAGE", db.operation = "LIST", db.statement = %query))]
@@ -131,7 +193,7 @@ impl TableStorage {
         filter: P,
     ) -> Result<Vec<T>, APIError>
     where
-        ST: Serialize + DeserializeOwned + Clone + Sync + Send,
+        ST: Versioned + Serialize + DeserializeOwned + Clone + Sync + Send,
         P: Fn(&ST) -> bool,
         T: From<ST>,
     {
@@ -140,26 +202,23 @@ impl TableStorage {
         Ok(entries.iter().map(|e| e.clone().into()).collect())
     }
 
-    #[instrument(err, skip( table, filter, not_found_err), fields(otel.kind = "client", db.system = "TABLESTORAGE", db.operation = "LIST", db.statement = %query))]
-    async fn get_random<ST, T, P>(
+    /// Retrieves an entity if it exists, without treating its absence as an error.
+    async fn find_single<ST, T>(
         table: TableReference,
         type_name: &str,
-        query: String,
-        filter: P,
-        not_found_err: APIError,
-    ) -> Result<T, APIError>
+        partition_key: u128,
+        row_key: u128,
+    ) -> Result<Option<T>, APIError>
     where
-        ST: Serialize + DeserializeOwned + Clone + Sync + Send,
-        P: Fn(&ST) -> bool,
-        T: From<ST> + ToOwned,
+        ST: Versioned + Serialize + DeserializeOwned + Clone + Sync + Send,
+        T: From<ST>,
     {
+        let query =
+            format!("PartitionKey eq '{partition_key:0>32x}' and RowKey eq '{row_key:0>32x}'");
         let entries: Vec<ST> =
-            TableStorage::get_all_entities(table, type_name, query, filter).await?;
-        entries
-            .iter()
-            .choose(&mut rand::rng())
-            .map(|e| e.clone().into())
-            .ok_or(not_found_err)
+            TableStorage::get_all_entities(table, type_name, query, |_: &ST| true).await?;
+
+        Ok(entries.into_iter().next().map(|e| e.into()))
     }
 
     #[instrument(err, skip(table, item), fields(otel.kind = "client", db.system = "TABLESTORAGE", db.operation = "PUT"))]
@@ -211,122 +270,213 @@ impl TableStorage {
         Ok(())
     }
 
-    fn build_idea_filter_query(
-        partition_key: u128,
-        is_completed: Option<bool>,
-        tag: Option<String>,
-    ) -> String {
-        let mut query = format!("PartitionKey eq '{partition_key:0>32x}'");
-        if let Some(completed) = is_completed {
-            query += format!(" and Completed eq {completed}").as_str()
+    /// Rewrites the entities in a table which were stored with an older schema, returning
+    /// those which were upgraded. Each is read again immediately before it is written, so
+    /// that an entity which has been updated since the table was scanned is left alone.
+    #[instrument(err, skip(table), fields(otel.kind = "client", db.system = "TABLESTORAGE", db.operation = "UPGRADE"))]
+    async fn upgrade_table<ST>(
+        table: TableReference,
+        type_name: &str,
+        dry_run: bool,
+    ) -> Result<(SchemaUpgrade, Vec<ST>), APIError>
+    where
+        ST: Versioned + Serialize + DeserializeOwned + Clone + Debug + Sync + Send,
+    {
+        let entities: Vec<ST> =
+            TableStorage::query_entities(table.clone(), type_name, String::new()).await?;
+
+        let mut report = SchemaUpgrade {
+            table: type_name.to_string(),
+            scanned: entities.len(),
+            upgraded: 0,
+        };
+        let mut upgraded = vec![];
+
+        for entity in entities
+            .into_iter()
+            .filter(|e| e.schema_version() < ST::SCHEMA_VERSION)
+        {
+            if dry_run {
+                report.upgraded += 1;
+                continue;
+            }
+
+            let (partition_key, row_key) = entity.keys();
+            let entity_client = table
+                .partition_key_client(partition_key.to_string())
+                .entity_client(row_key.to_string());
+
+            let latest: ST = entity_client.get().into_future().await?.entity;
+            if latest.schema_version() >= ST::SCHEMA_VERSION {
+                continue;
+            }
+
+            let latest = latest.upgrade();
+            entity_client
+                .insert_or_replace(&latest)?
+                .into_future()
+                .await?;
+            report.upgraded += 1;
+            upgraded.push(latest);
         }
 
-        if let Some(tag) = tag {
-            query += format!(
-                " and contains(Tags, '{}')",
-                tag.replace('\'', "''").replace('%', "%25")
-            )
-            .as_str()
+        Ok((report, upgraded))
+    }
+
+    fn build_idea_filter_query(partition_key: &str, is_completed: Option<bool>) -> String {
+        let mut query = format!("PartitionKey eq '{partition_key}'");
+        if let Some(completed) = is_completed {
+            query += format!(" and Completed eq {completed}").as_str()
         }
 
         query
     }
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct TableStorageIdea {
-    #[serde(rename = "PartitionKey")]
-    pub collection_id: String,
-    #[serde(rename = "RowKey")]
-    pub id: String,
-
-    #[serde(rename = "Name")]
-    pub name: String,
-    #[serde(rename = "Description")]
-    pub description: String,
-    #[serde(rename = "Tags")]
-    pub tags: String,
-    #[serde(rename = "Completed")]
-    pub completed: bool,
-}
 
-impl From<TableStorageIdea> for Idea {
-    fn from(entity: TableStorageIdea) -> Self {
-        Self {
-            id: u128::from_str_radix(&entity.id, 16).unwrap_or_default(),
-            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
-            name: entity.name.clone(),
-            tags: hashset!([entity.tags.split(',').filter(|t| !t.is_empty())]),
-            description: entity.description.clone(),
-            completed: entity.completed,
+    /// Retrieves the ideas in a collection which match the provided filters. Ideas with a
+    /// tag are found using the `ideatags` index, so that only those ideas are read.
+    async fn find_ideas(
+        ideas: TableReference,
+        idea_tags: TableReference,
+        collection: u128,
+        tag: Option<String>,
+        is_completed: Option<bool>,
+    ) -> Result<Vec<TableStorageIdea>, APIError> {
+        let Some(tag) = tag else {
+            let query =
+                TableStorage::build_idea_filter_query(&format!("{collection:0>32x}"), is_completed);
+            return TableStorage::get_all_entities(ideas, "ideas", query, |_| true).await;
+        };
+
+        let marker = TableStorageIdeaTag::indexed_marker(collection);
+        let indexed: Vec<TableStorageIdeaTag> = TableStorage::get_all_entities(
+            idea_tags.clone(),
+            "idea_tags",
+            format!(
+                "PartitionKey eq '{}' and RowKey eq '{}'",
+                marker.partition_key, marker.idea_id
+            ),
+            |_| true,
+        )
+        .await?;
+
+        if indexed.is_empty() {
+            let ideas = TableStorage::backfill_tag_index(ideas, idea_tags, collection).await?;
+            return Ok(ideas
+                .into_iter()
+                .filter(|idea| idea.matches(Some(&tag), is_completed))
+                .collect());
         }
-    }
-}
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct TableStorageCollection {
-    #[serde(rename = "PartitionKey")]
-    pub principal_id: String,
-    #[serde(rename = "RowKey")]
-    pub collection_id: String,
+        let query = TableStorage::build_idea_filter_query(
+            &TableStorageIdeaTag::partition_key(collection, &tag),
+            is_completed,
+        );
+        let index: Vec<TableStorageIdeaTag> =
+            TableStorage::get_all_entities(idea_tags, "idea_tags", query, |_| true).await?;
+
+        let lookups = index.into_iter().map(|entry| {
+            let ideas = ideas.clone();
+            let id = u128::from_str_radix(&entry.idea_id, 16).unwrap_or_default();
+            TableStorage::find_single::<TableStorageIdea, TableStorageIdea>(
+                ideas, "ideas", collection, id,
+            )
+        });
 
-    #[serde(rename = "Name")]
-    pub name: String,
-}
+        // The index is updated after the idea itself, so an entry may briefly refer to an
+        // idea which no longer has the tag, or has since been completed.
+        Ok(futures::future::try_join_all(lookups)
+            .await?
+            .into_iter()
+            .flatten()
+            .filter(|idea| idea.matches(Some(&tag), is_completed))
+            .collect())
+    }
 
-impl From<TableStorageCollection> for Collection {
-    fn from(entity: TableStorageCollection) -> Self {
-        Self {
-            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
-            user_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
-            name: entity.name.clone(),
+    /// Adds every idea in a collection to the `ideatags` index, which is needed for ideas
+    /// stored before the index existed, and returns them so that the query which found
+    /// the collection unindexed can still be answered.
+    async fn backfill_tag_index(
+        ideas: TableReference,
+        idea_tags: TableReference,
+        collection: u128,
+    ) -> Result<Vec<TableStorageIdea>, APIError> {
+        let entities: Vec<TableStorageIdea> = TableStorage::get_all_entities(
+            ideas,
+            "ideas",
+            format!("PartitionKey eq '{collection:0>32x}'"),
+            |_| true,
+        )
+        .await?;
+
+        for entry in entities.iter().flat_map(|idea| idea.tag_index()) {
+            TableStorage::store_idea_tag(idea_tags.clone(), &entry).await?;
         }
-    }
-}
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct TableStorageRoleAssignment {
-    #[serde(rename = "PartitionKey")]
-    pub collection_id: String,
-    #[serde(rename = "RowKey")]
-    pub principal_id: String,
+        TableStorage::store_idea_tag(idea_tags, &TableStorageIdeaTag::indexed_marker(collection))
+            .await?;
 
-    #[serde(rename = "Role")]
-    pub role: String,
-}
+        Ok(entities)
+    }
 
-impl From<TableStorageRoleAssignment> for RoleAssignment {
-    fn from(entity: TableStorageRoleAssignment) -> Self {
-        Self {
-            collection_id: u128::from_str_radix(&entity.collection_id, 16).unwrap_or_default(),
-            user_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
-            role: entity.role.as_str().into(),
-        }
+    async fn store_idea_tag(
+        idea_tags: TableReference,
+        item: &TableStorageIdeaTag,
+    ) -> Result<(), APIError> {
+        idea_tags
+            .partition_key_client(item.partition_key.clone())
+            .entity_client(item.idea_id.clone())
+            .insert_or_replace(item)?
+            .into_future()
+            .await
+            .map_err(|err| {
+                error!("Failed to store idea tag in table storage: {}", err);
+                APIError::new(503, "Service Unavailable", "We were unable to store the item you requested, this failure has been reported.")
+            })?;
+
+        Ok(())
     }
-}
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-struct TableStorageUser {
-    #[serde(rename = "PartitionKey")]
-    pub email_hash: String,
+    /// Updates the `ideatags` index to reflect the tags of an idea which has been stored,
+    /// or removed when `current` is `None`.
+    async fn index_idea_tags(
+        idea_tags: TableReference,
+        previous: Option<&Idea>,
+        current: Option<&Idea>,
+    ) -> Result<(), APIError> {
+        let Some(idea) = current.or(previous) else {
+            return Ok(());
+        };
 
-    #[serde(rename = "RowKey")]
-    pub row_key: String,
+        let row_key = format!("{:0>32x}", idea.id);
 
-    #[serde(rename = "PrincipalId")]
-    pub principal_id: String,
+        for tag in previous.iter().flat_map(|p| p.tags.iter()) {
+            if current.is_some_and(|c| c.tags.contains(tag)) {
+                continue;
+            }
 
-    #[serde(rename = "FirstName")]
-    pub first_name: String,
-}
+            let entity_client = idea_tags
+                .partition_key_client(TableStorageIdeaTag::partition_key(idea.collection_id, tag))
+                .entity_client(row_key.clone());
 
-impl From<TableStorageUser> for models::User {
-    fn from(entity: TableStorageUser) -> Self {
-        Self {
-            email_hash: u128::from_str_radix(&entity.email_hash, 16).unwrap_or_default(),
-            principal_id: u128::from_str_radix(&entity.principal_id, 16).unwrap_or_default(),
-            first_name: entity.first_name.as_str().into(),
+            // An idea stored before the index existed has no entries to remove, and any
+            // stale entries are ignored when the index is read.
+            if let Err(err) = entity_client.delete().into_future().await {
+                warn!("Failed to remove idea tag from table storage: {}", err);
+            }
         }
+
+        for tag in current.iter().flat_map(|c| c.tags.iter()) {
+            let item = TableStorageIdeaTag {
+                partition_key: TableStorageIdeaTag::partition_key(idea.collection_id, tag),
+                idea_id: row_key.clone(),
+                schema_version: TableStorageIdeaTag::SCHEMA_VERSION,
+                completed: idea.completed,
+            };
+
+            TableStorage::store_idea_tag(idea_tags.clone(), &item).await?;
+        }
+
+        Ok(())
     }
 }
 
@@ -353,7 +503,13 @@ macro_rules! actor_handler {
             type Result = ResponseActFuture<Self, Result<$res, APIError>>;
 
             fn handle(&mut self, msg: $msg, _ctx: &mut Self::Context) -> Self::Result {
-                Box::pin(fut::wrap_future(self.handle_internal(msg)))
+                let in_flight = self.in_flight.clone();
+                let work = self.handle_internal(msg);
+
+                Box::pin(fut::wrap_future(async move {
+                    let _in_flight = in_flight;
+                    work.await
+                }))
             }
         }
 
@@ -361,7 +517,19 @@ macro_rules! actor_handler {
             type Result = ResponseActFuture<Self, Result<$res, APIError>>;
 
             fn handle(&mut self, msg: $crate::telemetry::TraceMessage<$msg>, _ctx: &mut Self::Context) -> Self::Result {
-                let work = self.handle_internal(msg.message);
+                let in_flight = self.in_flight.clone();
+                let started = std::time::Instant::now();
+                let work = futures::FutureExt::map(self.handle_internal(msg.message), move |result| {
+                    drop(in_flight);
+
+                    $crate::telemetry::metrics().record_store_message(
+                        stringify!($msg),
+                        started.elapsed(),
+                        &result,
+                    );
+
+                    result
+                });
 
                 let instrumentation = work.instrument(msg.span);
 
@@ -402,25 +570,6 @@ macro_rules! actor_handler {
         });
     };
 
-    ($msg:ty|$src:ident => $res:ty: get_random from $table:ident ( $st:ty ) where query = $query:expr, context = [$($ctx:tt)*], filter = $fid:ident -> $filter:expr; not found = $err:expr) => {
-        actor_handler!($msg => $res: handler = fn handle_internal(&self, $src: $msg) -> Pin<Box<dyn Future<Output = Self::Result>>> {
-            let table = self.$table.clone();
-            let query = $query;
-
-            $($ctx)*
-
-            let work = TableStorage::get_random::<$st, $res, _>(
-                table,
-                "$table",
-                query,
-                move |$fid| $filter,
-                APIError::new(404, "Not Found", $err)
-            );
-
-            Box::pin(work)
-        });
-    };
-
     ($msg:ty|$src:ident: remove_single from $table:ident where pk=$pk:expr, rk=$rk:expr) => {
         actor_handler!($msg => (): handler = fn handle_internal(&self, $src: $msg) -> Pin<Box<dyn Future<Output = Self::Result>>> {
             let table = self.$table.clone();
@@ -455,46 +604,251 @@ impl Actor for TableStorage {
     type Context = actix::prelude::Context<Self>;
 }
 
-trace_handler!(TableStorage, GetHealth, Result<Health, APIError>);
+actor_handler!(GetHealth => Health: handler = fn handle_internal(&self, _msg: GetHealth) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.collections.clone();
+    let started_at = self.started_at;
 
-impl Handler<GetHealth> for TableStorage {
-    type Result = Result<Health, APIError>;
+    // Querying a partition which never holds any entities exercises the credentials and
+    // connectivity to the storage account without transferring any data.
+    let work = async move {
+        let probe = TableStorage::query_entities::<TableStorageCollection>(
+            table,
+            "collections",
+            format!("PartitionKey eq '{:0>32x}'", 0),
+        )
+        .await;
 
-    fn handle(&mut self, _: GetHealth, _: &mut Self::Context) -> Self::Result {
         Ok(Health {
-            ok: true,
-            started_at: self.started_at,
+            ok: probe.is_ok(),
+            started_at,
+        })
+    };
+
+    Box::pin(work)
+});
+
+impl Handler<FlushStore> for TableStorage {
+    type Result = ResponseFuture<Result<(), APIError>>;
+
+    fn handle(&mut self, _: FlushStore, _: &mut Self::Context) -> Self::Result {
+        // Only a weak reference is held here, so that this does not wait for itself.
+        let in_flight = Arc::downgrade(&self.in_flight);
+
+        Box::pin(async move {
+            while in_flight.strong_count() > 1 {
+                actix_rt::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+
+            Ok(())
         })
     }
 }
 
+impl Handler<crate::telemetry::TraceMessage<FlushStore>> for TableStorage {
+    type Result = ResponseFuture<Result<(), APIError>>;
+
+    fn handle(
+        &mut self,
+        msg: crate::telemetry::TraceMessage<FlushStore>,
+        ctx: &mut Self::Context,
+    ) -> Self::Result {
+        Box::pin(self.handle(msg.message, ctx).instrument(msg.span))
+    }
+}
+
+actor_handler!(UpgradeSchema => Vec<SchemaUpgrade>: handler = fn handle_internal(&self, msg: UpgradeSchema) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let ideas = self.ideas.clone();
+    let idea_tags = self.idea_tags.clone();
+    let collections = self.collections.clone();
+    let role_assignments = self.role_assignments.clone();
+    let users = self.users.clone();
+    let chat_links = self.chat_links.clone();
+    let invitations = self.invitations.clone();
+    let invite_links = self.invite_links.clone();
+    let share_links = self.share_links.clone();
+    let idea_schedules = self.idea_schedules.clone();
+    let calendar_feeds = self.calendar_feeds.clone();
+    let webhooks = self.webhooks.clone();
+    let webhook_deliveries = self.webhook_deliveries.clone();
+    let audit_log = self.audit_log.clone();
+    let dry_run = msg.dry_run;
+
+    Box::pin(async move {
+        let (idea_upgrade, upgraded_ideas) = TableStorage::upgrade_table::<TableStorageIdea>(ideas, "ideas", dry_run).await?;
+
+        // Ideas stored before version 2 were not added to the tag index.
+        for entity in upgraded_ideas {
+            let idea: Idea = entity.into();
+            TableStorage::index_idea_tags(idea_tags.clone(), None, Some(&idea)).await?;
+        }
+
+        // Every idea is now in the tag index, so each collection is marked as indexed to
+        // keep its first tag query from scanning and re-indexing the whole collection.
+        if !dry_run {
+            let listings: Vec<TableStorageCollection> = TableStorage::query_entities(
+                collections.clone(),
+                "collections",
+                String::new(),
+            )
+            .await?;
+            let collection_ids: BTreeSet<u128> = listings
+                .iter()
+                .filter_map(|c| u128::from_str_radix(&c.collection_id, 16).ok())
+                .collect();
+
+            for collection_id in collection_ids {
+                TableStorage::store_idea_tag(
+                    idea_tags.clone(),
+                    &TableStorageIdeaTag::indexed_marker(collection_id),
+                )
+                .await?;
+            }
+        }
+
+        Ok(vec![
+            idea_upgrade,
+            TableStorage::upgrade_table::<TableStorageIdeaTag>(idea_tags, "ideatags", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageCollection>(collections, "collections", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageRoleAssignment>(role_assignments, "roleassignments", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageUser>(users, "users", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageChatLink>(chat_links, "chatlinks", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageInvitation>(invitations, "invitations", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageInviteLink>(invite_links, "invitelinks", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageShareLink>(share_links, "sharelinks", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageIdeaSchedule>(idea_schedules, "ideaschedules", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageCalendarFeed>(calendar_feeds, "calendarfeeds", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageWebhook>(webhooks, "webhooks", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageWebhookDelivery>(webhook_deliveries, "webhookdeliveries", dry_run).await?.0,
+            TableStorage::upgrade_table::<TableStorageAuditEntry>(audit_log, "auditlog", dry_run).await?.0,
+        ])
+    })
+});
+
 actor_handler!(GetIdea|msg => Idea: get_single from ideas(TableStorageIdea) where pk=msg.collection, rk=msg.id; not found = "The combination of collection and idea ID you provided could not be found. Please check them and try again.");
 
-actor_handler!(GetIdeas|msg => Idea: get_all from ideas(TableStorageIdea) where
-    query=TableStorage::build_idea_filter_query(msg.collection, msg.is_completed, msg.tag.clone()),
-    context = [
-        let tag_str = msg.tag.unwrap_or_default();
-    ],
-    filter=i -> tag_str.is_empty() || i.tags.split(',').any(|i| i == tag_str.as_str()));
-
-actor_handler!(GetRandomIdea|msg => Idea: get_random from ideas(TableStorageIdea) where
-    query = TableStorage::build_idea_filter_query(msg.collection, msg.is_completed, msg.tag.clone()),
-    context = [
-        let tag_str = msg.tag.unwrap_or_default();
-    ],
-    filter = i -> tag_str.is_empty() || i.tags.split(',').any(|i| i == tag_str.as_str());
-    not found = "We could not find any ideas in the collection you provided which matched your query. Please create some and try again.");
-
-actor_handler!(StoreIdea|msg => Idea: store_single in ideas(TableStorageIdea) where pk=msg.collection, rk=msg.id; return TableStorageIdea {
-    collection_id: format!("{:0>32x}", msg.collection),
-    id: format!("{:0>32x}", msg.id),
-    name: msg.name.clone(),
-    description: msg.description.clone(),
-    tags: msg.tags.iter().fold("".to_string(), |j, i| j + "," + i.as_str()),
-    completed: msg.completed,
+actor_handler!(GetIdeas => Vec<Idea>: handler = fn handle_internal(&self, msg: GetIdeas) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let ideas = self.ideas.clone();
+    let idea_tags = self.idea_tags.clone();
+
+    Box::pin(async move {
+        let entities = TableStorage::find_ideas(ideas, idea_tags, msg.collection, msg.tag, msg.is_completed).await?;
+        Ok(entities.into_iter().map(|e| e.into()).collect())
+    })
+});
+
+actor_handler!(CountIdeas => usize: handler = fn handle_internal(&self, msg: CountIdeas) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.ideas.clone();
+
+    // Table Storage cannot count entities on the server, so this still pages through the
+    // collection's partition, but it skips upgrading and converting each idea.
+    Box::pin(async move {
+        let entities = TableStorage::query_entities::<TableStorageIdea>(
+            table,
+            "ideas",
+            format!("PartitionKey eq '{:0>32x}'", msg.collection),
+        )
+        .await?;
+
+        Ok(entities.len())
+    })
 });
 
-actor_handler!(RemoveIdea|msg: remove_single from ideas where pk=msg.collection, rk=msg.id);
+actor_handler!(GetRandomIdea => Idea: handler = fn handle_internal(&self, msg: GetRandomIdea) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let ideas = self.ideas.clone();
+    let idea_tags = self.idea_tags.clone();
+
+    Box::pin(async move {
+        let entities = TableStorage::find_ideas(ideas, idea_tags, msg.collection, msg.tag, msg.is_completed).await?;
+        entities
+            .into_iter()
+            .choose(&mut rand::rng())
+            .map(|e| e.into())
+            .ok_or_else(|| APIError::new(404, "Not Found", "We could not find any ideas in the collection you provided which matched your query. Please create some and try again."))
+    })
+});
+
+actor_handler!(StoreIdea => Idea: handler = fn handle_internal(&self, msg: StoreIdea) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.ideas.clone();
+    let idea_tags = self.idea_tags.clone();
+    let events = self.events.clone();
+
+    Box::pin(async move {
+        let previous = TableStorage::find_single::<TableStorageIdea, Idea>(table.clone(), "ideas", msg.collection, msg.id).await?;
+        let idea = Idea {
+            id: msg.id,
+            collection_id: msg.collection,
+            name: msg.name,
+            description: msg.description,
+            tags: msg.tags,
+            completed: msg.completed,
+            created_at: None,
+            completed_at: None,
+        }
+        .timestamped(previous.as_ref(), chrono::Utc::now());
+
+        let item = TableStorageIdea {
+            schema_version: TableStorageIdea::SCHEMA_VERSION,
+            collection_id: format!("{:0>32x}", idea.collection_id),
+            id: format!("{:0>32x}", idea.id),
+            name: idea.name.clone(),
+            description: idea.description.clone(),
+            tags: TableStorageIdea::encode_tags(&idea.tags),
+            completed: idea.completed,
+            created_at: idea.created_at,
+            completed_at: idea.completed_at,
+        };
+
+        let idea: Idea = TableStorage::store_single(table, "ideas", msg.collection, msg.id, item).await?;
+        TableStorage::index_idea_tags(idea_tags, previous.as_ref(), Some(&idea)).await?;
+
+        events.publish(msg.collection, CollectionEventKind::stored_idea(previous.as_ref(), idea.clone()));
+
+        Ok(idea)
+    })
+});
+
+actor_handler!(RestoreIdea => Idea: handler = fn handle_internal(&self, msg: RestoreIdea) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.ideas.clone();
+    let idea_tags = self.idea_tags.clone();
+
+    Box::pin(async move {
+        let idea = msg.idea;
+        let previous = TableStorage::find_single::<TableStorageIdea, Idea>(table.clone(), "ideas", idea.collection_id, idea.id).await?;
+
+        let item = TableStorageIdea {
+            schema_version: TableStorageIdea::SCHEMA_VERSION,
+            collection_id: format!("{:0>32x}", idea.collection_id),
+            id: format!("{:0>32x}", idea.id),
+            name: idea.name.clone(),
+            description: idea.description.clone(),
+            tags: TableStorageIdea::encode_tags(&idea.tags),
+            completed: idea.completed,
+            created_at: idea.created_at,
+            completed_at: idea.completed_at,
+        };
+
+        let idea: Idea = TableStorage::store_single(table, "ideas", idea.collection_id, idea.id, item).await?;
+        TableStorage::index_idea_tags(idea_tags, previous.as_ref(), Some(&idea)).await?;
+
+        Ok(idea)
+    })
+});
+
+actor_handler!(RemoveIdea => (): handler = fn handle_internal(&self, msg: RemoveIdea) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.ideas.clone();
+    let idea_tags = self.idea_tags.clone();
+    let events = self.events.clone();
+
+    Box::pin(async move {
+        let previous = TableStorage::find_single::<TableStorageIdea, Idea>(table.clone(), "ideas", msg.collection, msg.id).await?;
+        TableStorage::remove_single(table, "ideas", msg.collection, msg.id).await?;
+        TableStorage::index_idea_tags(idea_tags, previous.as_ref(), None).await?;
+
+        events.publish(msg.collection, CollectionEventKind::IdeaRemoved(msg.id));
+
+        Ok(())
+    })
+});
 
 actor_handler!(GetCollection|msg => Collection: get_single from collections(TableStorageCollection) where pk=msg.principal_id, rk=msg.id; not found = "The collection ID you provided could not be found. Please check them and try again.");
 
@@ -503,7 +857,13 @@ actor_handler!(GetCollections|msg => Collection: get_all from collections(TableS
     context = [],
     filter = _i -> true);
 
+actor_handler!(GetAllCollections|_msg => Collection: get_all from collections(TableStorageCollection) where
+    query = String::new(),
+    context = [],
+    filter = _i -> true);
+
 actor_handler!(StoreCollection|msg => Collection: store_single in collections(TableStorageCollection) where pk=msg.principal_id, rk=msg.collection_id; return TableStorageCollection {
+    schema_version: TableStorageCollection::SCHEMA_VERSION,
     principal_id: format!("{:0>32x}", msg.principal_id),
     collection_id: format!("{:0>32x}", msg.collection_id),
     name: msg.name.clone(),
@@ -518,19 +878,303 @@ actor_handler!(GetRoleAssignments|msg => RoleAssignment: get_all from role_assig
     context = [],
     filter = _i -> true);
 
-actor_handler!(StoreRoleAssignment|msg => RoleAssignment: store_single in role_assignments(TableStorageRoleAssignment) where pk=msg.collection_id, rk=msg.principal_id; return TableStorageRoleAssignment {
-    collection_id: format!("{:0>32x}", msg.collection_id),
-    principal_id: format!("{:0>32x}", msg.principal_id),
-    role: msg.role.into(),
+// Role assignments are partitioned by collection, so this scans every partition for the
+// principal's rows.
+actor_handler!(GetPrincipalRoleAssignments|msg => RoleAssignment: get_all from role_assignments(TableStorageRoleAssignment) where
+    query = format!("RowKey eq '{:0>32x}'", msg.principal_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreRoleAssignment => RoleAssignment: handler = fn handle_internal(&self, msg: StoreRoleAssignment) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.role_assignments.clone();
+    let events = self.events.clone();
+    let item = TableStorageRoleAssignment {
+        schema_version: TableStorageRoleAssignment::SCHEMA_VERSION,
+        collection_id: format!("{:0>32x}", msg.collection_id),
+        principal_id: format!("{:0>32x}", msg.principal_id),
+        role: msg.role.into(),
+    };
+
+    Box::pin(async move {
+        let previous = TableStorage::find_single::<TableStorageRoleAssignment, RoleAssignment>(table.clone(), "role_assignments", msg.collection_id, msg.principal_id).await?;
+        let role_assignment: RoleAssignment = TableStorage::store_single(table, "role_assignments", msg.collection_id, msg.principal_id, item).await?;
+
+        if previous.is_none() {
+            events.publish(msg.collection_id, CollectionEventKind::MemberAdded(role_assignment.clone()));
+        }
+
+        Ok(role_assignment)
+    })
 });
 
-actor_handler!(RemoveRoleAssignment|msg: remove_single from role_assignments where pk=msg.collection_id, rk=msg.principal_id);
+actor_handler!(RemoveRoleAssignment => (): handler = fn handle_internal(&self, msg: RemoveRoleAssignment) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.role_assignments.clone();
+    let events = self.events.clone();
+
+    Box::pin(async move {
+        TableStorage::remove_single(table, "role_assignments", msg.collection_id, msg.principal_id).await?;
+
+        events.publish(msg.collection_id, CollectionEventKind::MemberRemoved(msg.principal_id));
+
+        Ok(())
+    })
+});
 
 actor_handler!(GetUser|msg => models::User: get_single from users(TableStorageUser) where pk=msg.email_hash, rk=msg.email_hash; not found = "The user you are looking for could not be found. Please check that you have entered their email address correctly and try again.");
 
+actor_handler!(GetUsers|_msg => models::User: get_all from users(TableStorageUser) where
+    query = String::new(),
+    context = [],
+    filter = _i -> true);
+
 actor_handler!(StoreUser|msg => models::User: store_single in users(TableStorageUser) where pk=msg.email_hash, rk=msg.email_hash; return TableStorageUser {
+    schema_version: TableStorageUser::SCHEMA_VERSION,
     email_hash: format!("{:0>32x}", msg.email_hash),
     row_key: format!("{:0>32x}", msg.email_hash),
     principal_id: format!("{:0>32x}", msg.principal_id),
     first_name: msg.first_name.clone()
 });
+
+actor_handler!(GetInvitation|msg => Invitation: get_single from invitations(TableStorageInvitation) where pk=msg.collection_id, rk=msg.email_hash; not found = "No pending invitation could be found for the email address you provided. Please check it and try again.");
+
+actor_handler!(GetInvitations|msg => Invitation: get_all from invitations(TableStorageInvitation) where
+    query = format!("PartitionKey eq '{:0>32x}'", msg.collection_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(GetUserInvitations|msg => Invitation: get_all from invitations(TableStorageInvitation) where
+    query = format!("RowKey eq '{:0>32x}'", msg.email_hash),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreInvitation|msg => Invitation: store_single in invitations(TableStorageInvitation) where pk=msg.collection_id, rk=msg.email_hash; return TableStorageInvitation {
+    schema_version: TableStorageInvitation::SCHEMA_VERSION,
+    collection_id: format!("{:0>32x}", msg.collection_id),
+    email_hash: format!("{:0>32x}", msg.email_hash),
+    role: msg.role.into(),
+    invited_by: format!("{:0>32x}", msg.principal_id),
+});
+
+actor_handler!(RemoveInvitation|msg: remove_single from invitations where pk=msg.collection_id, rk=msg.email_hash);
+
+actor_handler!(GetInviteLink|msg => InviteLink: get_single from invite_links(TableStorageInviteLink) where pk=msg.collection_id, rk=msg.id; not found = "The invite link you provided could not be found. Please check it and try again.");
+
+actor_handler!(GetInviteLinks|msg => InviteLink: get_all from invite_links(TableStorageInviteLink) where
+    query = format!("PartitionKey eq '{:0>32x}'", msg.collection_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreInviteLink|msg => InviteLink: store_single in invite_links(TableStorageInviteLink) where pk=msg.collection_id, rk=msg.id; return TableStorageInviteLink {
+    schema_version: TableStorageInviteLink::SCHEMA_VERSION,
+    collection_id: format!("{:0>32x}", msg.collection_id),
+    id: format!("{:0>32x}", msg.id),
+    role: msg.role.into(),
+    max_uses: msg.max_uses,
+    uses: msg.uses,
+    expires_at: msg.expires_at,
+    created_by: format!("{:0>32x}", msg.principal_id),
+});
+
+actor_handler!(RemoveInviteLink|msg: remove_single from invite_links where pk=msg.collection_id, rk=msg.id);
+
+actor_handler!(RedeemInviteLink => InviteLink: handler = fn handle_internal(&self, msg: RedeemInviteLink) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.invite_links.clone();
+
+    Box::pin(async move {
+        let entity_client = table
+            .partition_key_client(format!("{:0>32x}", msg.collection_id))
+            .entity_client(format!("{:0>32x}", msg.id));
+
+        // The link is only updated if nobody else has redeemed it since it was read, and
+        // is read again if they have.
+        for _ in 0..INVITE_LINK_REDEMPTION_ATTEMPTS {
+            let response = entity_client.get().into_future().await.map_err(|err| {
+                error!("Failed to retrieve invite link from table storage: {}", err);
+                APIError::new(404, "Not Found", "The invite link you provided could not be found. Please check it and try again.")
+            })?;
+
+            let entity: TableStorageInviteLink = response.entity;
+            let mut link: InviteLink = entity.upgrade().into();
+            link.redeem()?;
+
+            let item = TableStorageInviteLink {
+                schema_version: TableStorageInviteLink::SCHEMA_VERSION,
+                collection_id: format!("{:0>32x}", link.collection_id),
+                id: format!("{:0>32x}", link.id),
+                role: link.role.into(),
+                max_uses: link.max_uses,
+                uses: link.uses,
+                expires_at: link.expires_at,
+                created_by: format!("{:0>32x}", link.created_by),
+            };
+
+            match entity_client.update(&item, IfMatchCondition::Etag(response.etag))?.into_future().await {
+                Ok(_) => return Ok(link),
+                Err(err) if matches!(err.kind(), azure_storage::ErrorKind::HttpResponse { status, .. } if u16::from(*status) == 412) => {
+                    debug!("Invite link {:0>32x} was redeemed concurrently, retrying", msg.id);
+                }
+                Err(err) => {
+                    error!("Failed to store invite link in table storage: {}", err);
+                    return Err(APIError::new(503, "Service Unavailable", "We were unable to store the item you requested, this failure has been reported."));
+                }
+            }
+        }
+
+        Err(APIError::new(503, "Service Unavailable", "This invite link is being redeemed by too many people at once. Please try again shortly."))
+    })
+});
+
+actor_handler!(GetShareLink|msg => ShareLink: get_single from share_links(TableStorageShareLink) where pk=msg.collection_id, rk=msg.id; not found = "The share link you provided could not be found. Please check it and try again.");
+
+actor_handler!(GetShareLinks|msg => ShareLink: get_all from share_links(TableStorageShareLink) where
+    query = format!("PartitionKey eq '{:0>32x}'", msg.collection_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreShareLink|msg => ShareLink: store_single in share_links(TableStorageShareLink) where pk=msg.collection_id, rk=msg.id; return TableStorageShareLink {
+    schema_version: TableStorageShareLink::SCHEMA_VERSION,
+    collection_id: format!("{:0>32x}", msg.collection_id),
+    id: format!("{:0>32x}", msg.id),
+    created_by: format!("{:0>32x}", msg.principal_id),
+    created_at: msg.created_at,
+});
+
+actor_handler!(RemoveShareLink|msg: remove_single from share_links where pk=msg.collection_id, rk=msg.id);
+
+actor_handler!(GetIdeaSchedule|msg => IdeaSchedule: get_single from idea_schedules(TableStorageIdeaSchedule) where pk=msg.collection_id, rk=msg.idea_id; not found = "The schedule you provided could not be found. Please check it and try again.");
+
+actor_handler!(GetIdeaSchedules|msg => IdeaSchedule: get_all from idea_schedules(TableStorageIdeaSchedule) where
+    query = format!("PartitionKey eq '{:0>32x}'", msg.collection_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreIdeaSchedule|msg => IdeaSchedule: store_single in idea_schedules(TableStorageIdeaSchedule) where pk=msg.collection_id, rk=msg.idea_id; return TableStorageIdeaSchedule {
+    schema_version: TableStorageIdeaSchedule::SCHEMA_VERSION,
+    collection_id: format!("{:0>32x}", msg.collection_id),
+    idea_id: format!("{:0>32x}", msg.idea_id),
+    date: msg.date.to_string(),
+    scheduled_by: format!("{:0>32x}", msg.principal_id),
+    scheduled_at: msg.scheduled_at,
+});
+
+actor_handler!(RemoveIdeaSchedule|msg: remove_single from idea_schedules where pk=msg.collection_id, rk=msg.idea_id);
+
+actor_handler!(GetCalendarFeed|msg => CalendarFeed: get_single from calendar_feeds(TableStorageCalendarFeed) where pk=msg.principal_id, rk=msg.id; not found = "The calendar feed you provided could not be found. Please check it and try again.");
+
+actor_handler!(GetCalendarFeeds|msg => CalendarFeed: get_all from calendar_feeds(TableStorageCalendarFeed) where
+    query = format!("PartitionKey eq '{:0>32x}'", msg.principal_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreCalendarFeed|msg => CalendarFeed: store_single in calendar_feeds(TableStorageCalendarFeed) where pk=msg.principal_id, rk=msg.id; return TableStorageCalendarFeed {
+    schema_version: TableStorageCalendarFeed::SCHEMA_VERSION,
+    principal_id: format!("{:0>32x}", msg.principal_id),
+    id: format!("{:0>32x}", msg.id),
+    created_at: msg.created_at,
+});
+
+actor_handler!(RemoveCalendarFeed|msg: remove_single from calendar_feeds where pk=msg.principal_id, rk=msg.id);
+
+actor_handler!(GetWebhook|msg => Webhook: get_single from webhooks(TableStorageWebhook) where pk=msg.collection_id, rk=msg.id; not found = "The webhook you provided could not be found. Please check it and try again.");
+
+actor_handler!(GetWebhooks|msg => Webhook: get_all from webhooks(TableStorageWebhook) where
+    query = format!("PartitionKey eq '{:0>32x}'", msg.collection_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreWebhook|msg => Webhook: store_single in webhooks(TableStorageWebhook) where pk=msg.collection_id, rk=msg.id; return TableStorageWebhook {
+    schema_version: TableStorageWebhook::SCHEMA_VERSION,
+    collection_id: format!("{:0>32x}", msg.collection_id),
+    id: format!("{:0>32x}", msg.id),
+    url: msg.url.clone(),
+    secret: msg.secret.clone(),
+    events: msg.events.join(","),
+    enabled: msg.enabled,
+    failures: msg.failures,
+    created_by: format!("{:0>32x}", msg.principal_id),
+    created_at: msg.created_at,
+});
+
+actor_handler!(RemoveWebhook|msg: remove_single from webhooks where pk=msg.collection_id, rk=msg.id);
+
+actor_handler!(GetWebhookDeliveries|msg => WebhookDelivery: get_all from webhook_deliveries(TableStorageWebhookDelivery) where
+    query = format!("PartitionKey eq '{:0>32x}'", msg.webhook_id),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreWebhookDelivery => WebhookDelivery: handler = fn handle_internal(&self, msg: StoreWebhookDelivery) -> Pin<Box<dyn Future<Output = Self::Result>>> {
+    let table = self.webhook_deliveries.clone();
+
+    Box::pin(async move {
+        let item = TableStorageWebhookDelivery {
+            schema_version: TableStorageWebhookDelivery::SCHEMA_VERSION,
+            webhook_id: format!("{:0>32x}", msg.webhook_id),
+            id: format!("{:0>32x}", msg.id),
+            event_id: msg.event_id.to_string(),
+            event_type: msg.event_type.clone(),
+            attempt: msg.attempt,
+            status_code: msg.status_code.unwrap_or_default(),
+            error: msg.error.clone().unwrap_or_default(),
+            succeeded: msg.succeeded,
+            timestamp: msg.timestamp,
+        };
+
+        let delivery = TableStorage::store_single(table.clone(), "webhook_deliveries", msg.webhook_id, msg.id, item).await?;
+
+        // Only the most recent deliveries are retained, so that a webhook which receives
+        // events indefinitely does not grow its history without bound.
+        let mut deliveries: Vec<TableStorageWebhookDelivery> = TableStorage::get_all_entities(
+            table.clone(),
+            "webhook_deliveries",
+            format!("PartitionKey eq '{:0>32x}'", msg.webhook_id),
+            |_| true,
+        )
+        .await?;
+
+        if deliveries.len() > WEBHOOK_DELIVERY_HISTORY {
+            deliveries.sort_by_key(|d| d.timestamp);
+
+            for stale in &deliveries[..deliveries.len() - WEBHOOK_DELIVERY_HISTORY] {
+                let entity_client = table
+                    .partition_key_client(stale.webhook_id.clone())
+                    .entity_client(stale.id.clone());
+
+                if let Err(err) = entity_client.delete().into_future().await {
+                    warn!("Failed to remove a webhook delivery from table storage: {}", err);
+                }
+            }
+        }
+
+        Ok(delivery)
+    })
+});
+
+actor_handler!(RemoveUser|msg: remove_single from users where pk=msg.email_hash, rk=msg.email_hash);
+
+actor_handler!(GetChatLink|msg => ChatLink: get_single from chat_links(TableStorageChatLink) where pk=msg.chat_user_hash, rk=msg.chat_user_hash; not found = "Your chat account has not been linked to Rex yet. Please link it and try again.");
+
+actor_handler!(StoreChatLink|msg => ChatLink: store_single in chat_links(TableStorageChatLink) where pk=chat_user_hash(&msg.team_id, &msg.user_id), rk=chat_user_hash(&msg.team_id, &msg.user_id); return TableStorageChatLink {
+    schema_version: TableStorageChatLink::SCHEMA_VERSION,
+    chat_user_hash: format!("{:0>32x}", chat_user_hash(&msg.team_id, &msg.user_id)),
+    row_key: format!("{:0>32x}", chat_user_hash(&msg.team_id, &msg.user_id)),
+    team_id: msg.team_id.clone(),
+    user_id: msg.user_id.clone(),
+    principal_id: format!("{:0>32x}", msg.principal_id),
+    created_at: msg.created_at,
+});
+
+actor_handler!(RemoveChatLink|msg: remove_single from chat_links where pk=msg.chat_user_hash, rk=msg.chat_user_hash);
+
+actor_handler!(GetAuditEntries|_msg => AuditEntry: get_all from audit_log(TableStorageAuditEntry) where
+    query = String::new(),
+    context = [],
+    filter = _i -> true);
+
+actor_handler!(StoreAuditEntry|msg => AuditEntry: store_single in audit_log(TableStorageAuditEntry) where pk=msg.principal_id, rk=msg.id; return TableStorageAuditEntry {
+    schema_version: TableStorageAuditEntry::SCHEMA_VERSION,
+    principal_id: format!("{:0>32x}", msg.principal_id),
+    id: format!("{:0>32x}", msg.id),
+    timestamp: msg.timestamp,
+    action: msg.action.clone(),
+    target: msg.target.clone(),
+});
@@ -51,7 +51,15 @@ macro_rules! trace_handler {
                 ctx: &mut Self::Context,
             ) -> Self::Result {
                 let _entered = msg.span.enter();
-                self.handle(msg.message, ctx)
+                let started = std::time::Instant::now();
+                let result = self.handle(msg.message, ctx);
+                $crate::telemetry::metrics().record_store_message(
+                    stringify!($message),
+                    started.elapsed(),
+                    &result,
+                );
+
+                result
             }
         }
     };
@@ -1,6 +1,7 @@
 use std::{
     pin::Pin,
     task::{Context, Poll},
+    time::Instant,
 };
 
 use actix_service::*;
@@ -77,22 +78,30 @@ where
 
         let _ = span.set_parent(context);
 
+        let started = Instant::now();
+        let method = req.method().to_string();
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| "unmatched".to_string());
+
         let fut = self
             .service
             .call(req)
-            .map(move |outcome| match &outcome {
-                Ok(response) => {
-                    Span::current()
-                        .record("http.status_code", display(response.response().status()));
-                    outcome
-                }
-                Err(error) => {
-                    Span::current().record(
-                        "http.status_code",
-                        display(error.as_response_error().status_code()),
-                    );
-                    outcome
-                }
+            .map(move |outcome| {
+                let status = match &outcome {
+                    Ok(response) => response.response().status(),
+                    Err(error) => error.as_response_error().status_code(),
+                };
+
+                Span::current().record("http.status_code", display(status));
+                super::metrics().record_http_request(
+                    &method,
+                    &route,
+                    status.as_u16(),
+                    started.elapsed(),
+                );
+
+                outcome
             })
             .instrument(span);
 
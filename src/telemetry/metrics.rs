@@ -0,0 +1,309 @@
+use crate::api::APIError;
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex, time::Duration};
+
+/// The upper bounds, in seconds, of the buckets which latencies are counted in.
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+lazy_static::lazy_static! {
+    static ref METRICS: Metrics = Metrics::default();
+}
+
+/// The metrics which are recorded by this process.
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+/// The label names and values which identify a single series within a metric.
+type Labels = Vec<(&'static str, String)>;
+
+pub struct Metrics {
+    http_requests: Histogram,
+    store_messages: Histogram,
+    store_errors: Counter,
+    oidc_verifications: Counter,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            http_requests: Histogram::new(
+                "rex_http_request_duration_seconds",
+                "The time taken to respond to HTTP requests, by route and status code.",
+            ),
+            store_messages: Histogram::new(
+                "rex_store_message_duration_seconds",
+                "The time taken by the store to handle each type of message.",
+            ),
+            store_errors: Counter::new(
+                "rex_store_errors_total",
+                "The number of messages which the store responded to with an error, by message type and status code.",
+            ),
+            oidc_verifications: Counter::new(
+                "rex_oidc_verifications_total",
+                "The number of OpenID Connect tokens which have been verified, by outcome.",
+            ),
+        }
+    }
+}
+
+impl Metrics {
+    pub fn record_http_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        self.http_requests.observe(
+            vec![
+                ("method", method.to_string()),
+                ("route", route.to_string()),
+                ("status", status.to_string()),
+            ],
+            duration,
+        );
+    }
+
+    pub fn record_store_message<T>(
+        &self,
+        message: &str,
+        duration: Duration,
+        result: &Result<T, APIError>,
+    ) {
+        self.store_messages
+            .observe(vec![("message", message.to_string())], duration);
+
+        if let Err(err) = result {
+            self.store_errors.increment(vec![
+                ("message", message.to_string()),
+                ("code", err.code.to_string()),
+            ]);
+        }
+    }
+
+    pub fn record_oidc_verification(&self, outcome: &str) {
+        self.oidc_verifications
+            .increment(vec![("outcome", outcome.to_string())]);
+    }
+
+    /// Writes every metric in the Prometheus text exposition format.
+    pub fn render(&self, out: &mut String) {
+        self.http_requests.render(out);
+        self.store_messages.render(out);
+        self.store_errors.render(out);
+        self.oidc_verifications.render(out);
+    }
+}
+
+/// Writes a gauge whose samples are computed at the time it is rendered.
+pub fn render_gauge<I>(out: &mut String, name: &str, help: &str, samples: I)
+where
+    I: IntoIterator<Item = (Labels, f64)>,
+{
+    write_header(out, name, help, "gauge");
+    for (labels, value) in samples {
+        write_sample(out, name, &labels, value);
+    }
+}
+
+struct Counter {
+    name: &'static str,
+    help: &'static str,
+    values: Mutex<BTreeMap<Labels, u64>>,
+}
+
+impl Counter {
+    fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn increment(&self, labels: Labels) {
+        if let Ok(mut values) = self.values.lock() {
+            *values.entry(labels).or_default() += 1;
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        write_header(out, self.name, self.help, "counter");
+        if let Ok(values) = self.values.lock() {
+            for (labels, value) in values.iter() {
+                write_sample(out, self.name, labels, *value as f64);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct HistogramValue {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    values: Mutex<BTreeMap<Labels, HistogramValue>>,
+}
+
+impl Histogram {
+    fn new(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn observe(&self, labels: Labels, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+
+        if let Ok(mut values) = self.values.lock() {
+            let value = values.entry(labels).or_default();
+            value.count += 1;
+            value.sum += seconds;
+            for (bucket, bound) in value.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+                if seconds <= bound {
+                    *bucket += 1;
+                }
+            }
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        write_header(out, self.name, self.help, "histogram");
+        if let Ok(values) = self.values.lock() {
+            let bucket_name = format!("{}_bucket", self.name);
+            for (labels, value) in values.iter() {
+                for (bucket, bound) in value.buckets.iter().zip(LATENCY_BUCKETS) {
+                    let mut labels = labels.clone();
+                    labels.push(("le", bound.to_string()));
+                    write_sample(out, &bucket_name, &labels, *bucket as f64);
+                }
+
+                let mut labels_inf = labels.clone();
+                labels_inf.push(("le", "+Inf".into()));
+                write_sample(out, &bucket_name, &labels_inf, value.count as f64);
+                write_sample(out, &format!("{}_sum", self.name), labels, value.sum);
+                write_sample(
+                    out,
+                    &format!("{}_count", self.name),
+                    labels,
+                    value.count as f64,
+                );
+            }
+        }
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn write_sample(out: &mut String, name: &str, labels: &[(&'static str, String)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        out.push('{');
+        for (i, (key, value)) in labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            let _ = write!(out, "{key}=\"{value}\"");
+        }
+        out.push('}');
+    }
+    let _ = writeln!(out, " {value}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_counter() {
+        let counter = Counter::new("test_total", "A test counter.");
+        counter.increment(vec![("outcome", "verified".into())]);
+        counter.increment(vec![("outcome", "verified".into())]);
+        counter.increment(vec![("outcome", "say \"hi\"".into())]);
+
+        let mut out = String::new();
+        counter.render(&mut out);
+
+        assert_eq!(
+            out,
+            "# HELP test_total A test counter.\n\
+             # TYPE test_total counter\n\
+             test_total{outcome=\"say \\\"hi\\\"\"} 1\n\
+             test_total{outcome=\"verified\"} 2\n"
+        );
+    }
+
+    #[test]
+    fn render_histogram() {
+        let histogram = Histogram::new("test_seconds", "A test histogram.");
+        histogram.observe(vec![("route", "/".into())], Duration::from_millis(20));
+        histogram.observe(vec![("route", "/".into())], Duration::from_secs(20));
+
+        let mut out = String::new();
+        histogram.render(&mut out);
+
+        assert!(out.contains("# TYPE test_seconds histogram\n"), "{out}");
+        assert!(
+            out.contains("test_seconds_bucket{route=\"/\",le=\"0.01\"} 0\n"),
+            "{out}"
+        );
+        assert!(
+            out.contains("test_seconds_bucket{route=\"/\",le=\"0.025\"} 1\n"),
+            "{out}"
+        );
+        assert!(
+            out.contains("test_seconds_bucket{route=\"/\",le=\"10\"} 1\n"),
+            "{out}"
+        );
+        assert!(
+            out.contains("test_seconds_bucket{route=\"/\",le=\"+Inf\"} 2\n"),
+            "{out}"
+        );
+        assert!(
+            out.contains("test_seconds_sum{route=\"/\"} 20.02\n"),
+            "{out}"
+        );
+        assert!(out.contains("test_seconds_count{route=\"/\"} 2\n"), "{out}");
+    }
+
+    #[test]
+    fn render_store_errors() {
+        let metrics = Metrics::default();
+        metrics.record_store_message::<()>(
+            "GetIdea",
+            Duration::from_millis(1),
+            &Err(APIError::new(
+                404,
+                "Not Found",
+                "The idea could not be found.",
+            )),
+        );
+        metrics.record_store_message("GetIdeas", Duration::from_millis(1), &Ok(()));
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+
+        assert!(
+            out.contains("rex_store_errors_total{message=\"GetIdea\",code=\"404\"} 1\n"),
+            "{out}"
+        );
+        assert!(
+            !out.contains("rex_store_errors_total{message=\"GetIdeas\""),
+            "{out}"
+        );
+        assert!(
+            out.contains("rex_store_message_duration_seconds_count{message=\"GetIdeas\"} 1\n"),
+            "{out}"
+        );
+    }
+}
@@ -1,21 +1,143 @@
 mod actix_message;
 mod actix_web_tracing;
+mod logs;
+mod metrics;
 
 pub use actix_message::*;
 pub use actix_web_tracing::TracingLogger;
+pub use logs::LogFormat;
+pub use metrics::{metrics, render_gauge};
 
+use std::collections::BTreeMap;
 use tracing_batteries::{Medama, OpenTelemetry, Sentry, Session};
 
-pub fn setup() -> Session {
-    Session::new("rex", version!())
-        .with_battery(Sentry::new(
-            "https://b7ca8a41e8e84fef889e4f428071dab2@o219072.ingest.sentry.io/1415519",
-        ))
-        .with_battery(
-            OpenTelemetry::new("https://api.honeycomb.io:443").with_header(
-                "x-honeycomb-team",
-                std::env::var("HONEYCOMB_KEY").unwrap_or_default(),
+/// Where Rex sends its telemetry.
+///
+/// Sentry, the OpenTelemetry endpoint and Medama are enabled by default. Any one of them
+/// can be disabled by setting it to an empty string, such as `sentry_dsn = ""`, in the
+/// configuration file or in its environment variable, while `export = false` disables
+/// all of them at once.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryConfig {
+    /// Whether telemetry is sent to any of the services below. When this is disabled, Rex
+    /// only writes its logs to stdout, which is suitable for air-gapped deployments.
+    pub export: bool,
+    /// The DSN which errors are reported to Sentry with.
+    #[serde(deserialize_with = "deserialize_optional")]
+    pub sentry_dsn: Option<String>,
+    /// The OTLP endpoint which OpenTelemetry traces are exported to, such as a local collector.
+    #[serde(deserialize_with = "deserialize_optional")]
+    pub opentelemetry_endpoint: Option<String>,
+    /// The headers which are sent along with exported traces, such as API keys.
+    pub opentelemetry_headers: BTreeMap<String, String>,
+    /// The Honeycomb API key which is sent along with exported traces.
+    #[serde(deserialize_with = "deserialize_optional")]
+    pub honeycomb_key: Option<String>,
+    /// The Medama instance which page views are reported to.
+    #[serde(deserialize_with = "deserialize_optional")]
+    pub medama_url: Option<String>,
+    /// The format of the logs written to stdout when traces are not exported with OpenTelemetry.
+    pub log_format: LogFormat,
+    /// The address, such as `127.0.0.1:9090`, on which Prometheus metrics are served. They
+    /// are not served at all unless this is set, and never on the public API port.
+    #[serde(deserialize_with = "deserialize_optional")]
+    pub metrics_address: Option<String>,
+}
+
+/// Reads an optional setting, treating an empty string as unset because TOML has no way to
+/// write a null value.
+fn deserialize_optional<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    <String as serde::Deserialize>::deserialize(deserializer)
+        .map(|v| Some(v).filter(|v| !v.is_empty()))
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            export: true,
+            sentry_dsn: Some(
+                "https://b7ca8a41e8e84fef889e4f428071dab2@o219072.ingest.sentry.io/1415519".into(),
             ),
-        )
-        .with_battery(Medama::new("https://analytics.sierrasoftworks.com"))
+            opentelemetry_endpoint: Some("https://api.honeycomb.io:443".into()),
+            opentelemetry_headers: BTreeMap::new(),
+            honeycomb_key: None,
+            medama_url: Some("https://analytics.sierrasoftworks.com".into()),
+            log_format: LogFormat::default(),
+            metrics_address: None,
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// The headers which are sent along with exported traces.
+    fn opentelemetry_headers(&self) -> BTreeMap<String, String> {
+        let mut headers = self.opentelemetry_headers.clone();
+        if let Some(key) = &self.honeycomb_key {
+            headers.insert("x-honeycomb-team".into(), key.clone());
+        }
+
+        headers
+    }
+}
+
+pub fn setup(config: &TelemetryConfig) -> Session {
+    let mut session = Session::new("rex", version!());
+
+    if !config.export {
+        logs::init(config.log_format);
+        return session;
+    }
+
+    if let Some(dsn) = &config.sentry_dsn {
+        session = session.with_battery(Sentry::new(dsn.as_str()));
+    }
+
+    match &config.opentelemetry_endpoint {
+        Some(endpoint) => {
+            let battery = config.opentelemetry_headers().into_iter().fold(
+                OpenTelemetry::new(endpoint.as_str()),
+                |battery, (name, value)| battery.with_header(name, value),
+            );
+
+            session = session.with_battery(battery);
+        }
+        None => logs::init(config.log_format),
+    }
+
+    if let Some(url) = &config.medama_url {
+        session = session.with_battery(Medama::new(url.as_str()));
+    }
+
+    session
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opentelemetry_headers() {
+        let config = TelemetryConfig {
+            opentelemetry_headers: [("authorization".to_string(), "Bearer token".to_string())]
+                .into(),
+            honeycomb_key: Some("honeycomb".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.opentelemetry_headers(),
+            BTreeMap::from([
+                ("authorization".to_string(), "Bearer token".to_string()),
+                ("x-honeycomb-team".to_string(), "honeycomb".to_string()),
+            ])
+        );
+        assert!(
+            TelemetryConfig::default()
+                .opentelemetry_headers()
+                .is_empty()
+        );
+    }
 }
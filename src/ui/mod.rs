@@ -2,12 +2,24 @@ use actix_http::StatusCode;
 use actix_web::{HttpRequest, HttpResponse, get, http::header::ContentType, web};
 use tracing::{Span, field, instrument};
 
-pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(get_ui_path);
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// The address of the server which hosts the UI's static files, which requests for
+    /// anything other than the API are proxied to.
+    pub backend_uri: Option<String>,
+}
+
+pub fn configure(config: &UiConfig) -> impl FnOnce(&mut web::ServiceConfig) + use<> {
+    let config = web::Data::new(config.clone());
+
+    move |cfg| {
+        cfg.app_data(config).service(get_ui_path);
+    }
 }
 
 #[instrument(
-    skip(req),
+    skip(req, config),
     fields(
         otel.kind="client",
         http.method="GET",
@@ -16,9 +28,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         http.user_agent=%req.headers().get("User-Agent").map(|h| h.to_str().unwrap_or("")).unwrap_or(""),
         http.status_code=field::Empty))]
 #[get("/{ui_path:.*}")]
-pub async fn get_ui_path(req: HttpRequest) -> HttpResponse {
-    match std::env::var("INTERFACE_BACKEND_URI").ok() {
-        Some(uri) if !uri.is_empty() => {
+pub async fn get_ui_path(req: HttpRequest, config: web::Data<UiConfig>) -> HttpResponse {
+    match config.backend_uri.as_deref() {
+        Some(uri) => {
             let client = reqwest::Client::new();
             let res = client
                 .get(format!("{}/{}", uri, req.match_info().query("ui_path")))
@@ -0,0 +1,644 @@
+use crate::api::APIError;
+use crate::{models::*, telemetry::TraceMessageExt};
+use actix::prelude::*;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::{net::SocketAddr, time::Duration};
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+/// Controls how persistently the [`WebhookDispatcher`] attempts to deliver events.
+#[derive(Clone, Debug)]
+pub struct DeliveryPolicy {
+    /// The number of times delivery of an event is attempted before giving up on it.
+    pub max_attempts: u32,
+    /// The delay before the first retry, which doubles after each subsequent attempt.
+    pub initial_backoff: Duration,
+    /// The maximum amount of time to wait for a receiver to respond to a delivery.
+    pub timeout: Duration,
+    /// The number of consecutive events which may fail to be delivered before the webhook is disabled.
+    pub disable_after: u32,
+    /// Whether events may be delivered to loopback, link-local and private network addresses,
+    /// which should only be allowed when every webhook's owner is trusted.
+    pub allow_private_addresses: bool,
+}
+
+impl Default for DeliveryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            timeout: Duration::from_secs(10),
+            disable_after: 5,
+            allow_private_addresses: false,
+        }
+    }
+}
+
+/// The `[webhooks]` configuration section, which controls how events are delivered to
+/// webhooks and which addresses webhooks may be registered for.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// The number of times delivery of an event is attempted before giving up on it.
+    pub max_attempts: u32,
+    /// The number of seconds before the first retry, which doubles after each subsequent attempt.
+    pub initial_backoff: u64,
+    /// The number of seconds to wait for a receiver to respond to a delivery.
+    pub timeout: u64,
+    /// The number of consecutive events which may fail to be delivered before the webhook is disabled.
+    pub disable_after: u32,
+    /// Whether webhooks may target loopback, link-local and private network addresses, such
+    /// as a receiver running alongside Rex while testing. This should only be enabled when
+    /// every webhook's owner is trusted.
+    pub allow_private_addresses: bool,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        let policy = DeliveryPolicy::default();
+
+        Self {
+            max_attempts: policy.max_attempts,
+            initial_backoff: policy.initial_backoff.as_secs(),
+            timeout: policy.timeout.as_secs(),
+            disable_after: policy.disable_after,
+            allow_private_addresses: policy.allow_private_addresses,
+        }
+    }
+}
+
+impl From<&WebhookConfig> for DeliveryPolicy {
+    fn from(config: &WebhookConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts,
+            initial_backoff: Duration::from_secs(config.initial_backoff),
+            timeout: Duration::from_secs(config.timeout),
+            disable_after: config.disable_after,
+            allow_private_addresses: config.allow_private_addresses,
+        }
+    }
+}
+
+/// Delivers the events published for each collection to the webhooks which have
+/// subscribed to them.
+///
+/// Each delivery is a `POST` of the event's JSON representation, signed with the
+/// webhook's secret, and is retried with exponential backoff until it is accepted
+/// or the [`DeliveryPolicy`] gives up on it.
+pub struct WebhookDispatcher {
+    state: GlobalState,
+    client: reqwest::Client,
+    policy: DeliveryPolicy,
+    receiver: Option<Receiver<CollectionEvent>>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(state: GlobalState, policy: DeliveryPolicy) -> Self {
+        // Subscribing here, rather than once the actor has started, ensures that
+        // events published immediately after the dispatcher is created are not missed.
+        let receiver = Some(state.events.receiver());
+
+        let mut client = reqwest::Client::builder().timeout(policy.timeout);
+        if !policy.allow_private_addresses {
+            // Redirects are not followed, since they could send a delivery to a URL which
+            // was never validated.
+            client = client
+                .dns_resolver(PublicResolver)
+                .redirect(reqwest::redirect::Policy::none());
+        }
+
+        Self {
+            client: client
+                .build()
+                .expect("The webhook HTTP client should be configured correctly."),
+            state,
+            policy,
+            receiver,
+        }
+    }
+}
+
+impl Actor for WebhookDispatcher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        if let Some(receiver) = self.receiver.take() {
+            ctx.add_stream(futures::stream::unfold(
+                receiver,
+                |mut receiver| async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(event) => return Some((event, receiver)),
+                            Err(RecvError::Lagged(skipped)) => warn!(
+                                "The webhook dispatcher fell behind and skipped {} events.",
+                                skipped
+                            ),
+                            Err(RecvError::Closed) => return None,
+                        }
+                    }
+                },
+            ));
+        }
+    }
+}
+
+impl StreamHandler<CollectionEvent> for WebhookDispatcher {
+    fn handle(&mut self, event: CollectionEvent, ctx: &mut Self::Context) {
+        let state = self.state.clone();
+        let client = self.client.clone();
+        let policy = self.policy.clone();
+
+        ctx.spawn(fut::wrap_future(async move {
+            if let Err(err) = dispatch(state, client, policy, event).await {
+                error!("Failed to dispatch an event to its webhooks: {}", err);
+            }
+        }));
+    }
+}
+
+/// Resolves the hosts of webhooks while discarding any addresses which are not public, so
+/// that a webhook's DNS records cannot be changed after it was validated to reach the host
+/// Rex runs on or its private network.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let host = name.as_str().to_string();
+
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(format!("{host} does not resolve to a public address.").into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+#[instrument("webhooks.dispatch", err, skip(state, client, policy, event), fields(otel.kind = "internal", event.id = event.id, event.type = event.kind.name()))]
+async fn dispatch(
+    state: GlobalState,
+    client: reqwest::Client,
+    policy: DeliveryPolicy,
+    event: CollectionEvent,
+) -> Result<(), APIError> {
+    let webhooks = state
+        .store
+        .send(
+            GetWebhooks {
+                collection_id: event.collection_id,
+            }
+            .trace(),
+        )
+        .await??;
+
+    let event_id = event.id;
+    let event_type = event.kind.name();
+    let webhooks: Vec<Webhook> = webhooks
+        .into_iter()
+        .filter(|w| w.wants(event_type))
+        .collect();
+
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let body = serde_json::to_vec(&CollectionEventV3::from(event)).map_err(|err| {
+        error!("Failed to serialize an event for delivery: {}", err);
+        APIError::new(
+            500,
+            "Internal Server Error",
+            "We were unable to serialize the event for delivery, this failure has been reported.",
+        )
+    })?;
+
+    futures::future::join_all(webhooks.into_iter().map(|webhook| {
+        deliver(
+            &state,
+            &client,
+            &policy,
+            webhook,
+            event_id,
+            event_type,
+            body.clone(),
+        )
+    }))
+    .await;
+
+    Ok(())
+}
+
+/// Attempts to deliver an event to a webhook until it succeeds or the policy gives up,
+/// recording each attempt and the webhook's resulting health.
+async fn deliver(
+    state: &GlobalState,
+    client: &reqwest::Client,
+    policy: &DeliveryPolicy,
+    webhook: Webhook,
+    event_id: u64,
+    event_type: &'static str,
+    body: Vec<u8>,
+) {
+    let delivery_id = format!("{:0>32x}", new_id());
+    let signature = sign(&webhook.secret, &body);
+
+    let mut succeeded = false;
+    for attempt in 1..=policy.max_attempts.max(1) {
+        if attempt > 1 {
+            tokio::time::sleep(policy.initial_backoff * 2u32.pow(attempt - 2)).await;
+        }
+
+        // Addresses in the URL itself are not resolved, so they are checked here instead.
+        let (status_code, error) = match reqwest::Url::parse(&webhook.url)
+            .ok()
+            .and_then(|url| url.host_str().and_then(literal_address))
+        {
+            Some(ip) if !policy.allow_private_addresses && !is_public_address(ip) => {
+                (None, Some(format!("{ip} is not a public address.")))
+            }
+            _ => {
+                send(
+                    client,
+                    &webhook.url,
+                    event_type,
+                    &delivery_id,
+                    &signature,
+                    body.clone(),
+                )
+                .await
+            }
+        };
+
+        succeeded = status_code.is_some_and(|code| (200..300).contains(&code));
+
+        if let Err(err) = state
+            .store
+            .send(
+                StoreWebhookDelivery {
+                    webhook_id: webhook.id,
+                    id: new_id(),
+                    event_id,
+                    event_type: event_type.to_string(),
+                    attempt,
+                    status_code,
+                    error,
+                    succeeded,
+                    timestamp: chrono::Utc::now(),
+                }
+                .trace(),
+            )
+            .await
+            .map_err(APIError::from)
+            .and_then(|result| result)
+        {
+            warn!("Failed to record a webhook delivery attempt: {}", err);
+        }
+
+        if succeeded {
+            break;
+        }
+    }
+
+    if let Err(err) = record_outcome(state, policy, &webhook, succeeded).await {
+        warn!(
+            "Failed to update the webhook after delivering an event: {}",
+            err
+        );
+    }
+}
+
+#[instrument("webhooks.send", skip(client, signature, body), fields(otel.kind = "client", http.method = "POST"))]
+async fn send(
+    client: &reqwest::Client,
+    url: &str,
+    event_type: &str,
+    delivery_id: &str,
+    signature: &str,
+    body: Vec<u8>,
+) -> (Option<u16>, Option<String>) {
+    let response = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "Rex-Webhooks")
+        .header("X-Rex-Event", event_type)
+        .header("X-Rex-Delivery", delivery_id)
+        .header("X-Rex-Signature-256", signature)
+        .body(body)
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => (
+            Some(response.status().as_u16()),
+            Some(format!(
+                "The receiver responded with an unsuccessful status code ({}).",
+                response.status()
+            )),
+        ),
+        Err(err) => (None, Some(err.to_string())),
+    }
+}
+
+/// Updates the webhook's failure count once an event has been delivered (or abandoned),
+/// disabling it once it has failed to accept too many consecutive events.
+async fn record_outcome(
+    state: &GlobalState,
+    policy: &DeliveryPolicy,
+    webhook: &Webhook,
+    succeeded: bool,
+) -> Result<(), APIError> {
+    // The webhook may have been changed while the event was being delivered, so
+    // the latest version is used to avoid reverting those changes.
+    let mut webhook = match state
+        .store
+        .send(
+            GetWebhook {
+                collection_id: webhook.collection_id,
+                id: webhook.id,
+            }
+            .trace(),
+        )
+        .await?
+    {
+        Ok(webhook) => webhook,
+        Err(err) if err.code == 404 => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    if succeeded {
+        if webhook.failures == 0 {
+            return Ok(());
+        }
+
+        webhook.failures = 0;
+    } else {
+        webhook.failures += 1;
+
+        if webhook.enabled && webhook.failures >= policy.disable_after {
+            warn!(
+                "Disabling webhook {:0>32x} after {} consecutive failed deliveries.",
+                webhook.id, webhook.failures
+            );
+            webhook.enabled = false;
+        }
+    }
+
+    state
+        .store
+        .send(StoreWebhook::from(webhook).trace())
+        .await??;
+
+    Ok(())
+}
+
+/// Signs a delivery's body with the webhook's secret, producing the value of the
+/// `X-Rex-Signature-256` header which receivers use to verify its authenticity.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::test::*;
+    use crate::test_state;
+    use actix_web::http::header::HeaderMap;
+    use actix_web::{App, HttpRequest, HttpResponse, HttpServer, web};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone)]
+    struct Receiver {
+        status: u16,
+        requests: Arc<Mutex<Vec<(HeaderMap, web::Bytes)>>>,
+    }
+
+    async fn receive(
+        req: HttpRequest,
+        body: web::Bytes,
+        receiver: web::Data<Receiver>,
+    ) -> HttpResponse {
+        receiver
+            .requests
+            .lock()
+            .unwrap()
+            .push((req.headers().clone(), body));
+        HttpResponse::build(actix_http::StatusCode::from_u16(receiver.status).unwrap()).finish()
+    }
+
+    /// Starts a local HTTP server which records the requests it receives and responds
+    /// to each of them with the provided status code.
+    fn start_receiver(status: u16) -> (String, Receiver) {
+        let receiver = Receiver {
+            status,
+            requests: Arc::new(Mutex::new(Vec::new())),
+        };
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/rex", listener.local_addr().unwrap());
+
+        let data = web::Data::new(receiver.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .route("/hooks/rex", web::post().to(receive))
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        actix_rt::spawn(server);
+
+        (url, receiver)
+    }
+
+    async fn wait_for_deliveries(
+        state: &GlobalState,
+        webhook_id: u128,
+        count: usize,
+    ) -> Vec<WebhookDelivery> {
+        for _ in 0..200 {
+            let deliveries = state
+                .store
+                .send(GetWebhookDeliveries { webhook_id })
+                .await
+                .expect("the actor should have run")
+                .expect("the deliveries should be listed");
+
+            if deliveries.len() >= count {
+                return deliveries;
+            }
+
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+
+        panic!("the webhook did not receive {count} deliveries in time");
+    }
+
+    fn policy() -> DeliveryPolicy {
+        DeliveryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(10),
+            timeout: Duration::from_secs(5),
+            disable_after: 1,
+            allow_private_addresses: true,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn delivers_signed_events() {
+        test_log_init();
+
+        let (url, receiver) = start_receiver(204);
+
+        test_state!(
+            state = [StoreWebhook {
+                collection_id: 1,
+                id: 2,
+                url: url.clone(),
+                secret: "secret".into(),
+                events: vec!["idea.created".into()],
+                enabled: true,
+                failures: 0,
+                principal_id: 0,
+                created_at: chrono::Utc::now(),
+            }]
+        );
+
+        WebhookDispatcher::new(state.clone(), policy()).start();
+
+        test_state!(
+            ::state = [
+                StoreIdea {
+                    collection: 1,
+                    id: 3,
+                    name: "Test Idea".into(),
+                    description: "This is a test idea".into(),
+                    tags: Default::default(),
+                    completed: false,
+                },
+                StoreIdea {
+                    collection: 1,
+                    id: 3,
+                    name: "Updated Idea".into(),
+                    description: "This event is not subscribed to".into(),
+                    tags: Default::default(),
+                    completed: false,
+                }
+            ]
+        );
+
+        let deliveries = wait_for_deliveries(&state, 2, 1).await;
+        assert_eq!(deliveries[0].event_type, "idea.created");
+        assert_eq!(deliveries[0].status_code, Some(204));
+        assert!(deliveries[0].succeeded);
+
+        let requests = receiver.requests.lock().unwrap();
+        assert_eq!(
+            requests.len(),
+            1,
+            "only subscribed events should be delivered"
+        );
+
+        let (headers, body) = &requests[0];
+        assert_eq!(headers.get("X-Rex-Event").unwrap(), "idea.created");
+        assert!(headers.contains_key("X-Rex-Delivery"));
+        assert_eq!(
+            headers
+                .get("X-Rex-Signature-256")
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            sign("secret", body)
+        );
+
+        let event: CollectionEventV3 = serde_json::from_slice(body).unwrap();
+        assert_eq!(event.event_type, "idea.created");
+        assert_eq!(event.collection, "00000000000000000000000000000001");
+        assert_eq!(event.idea.unwrap().name, "Test Idea");
+    }
+
+    #[actix_rt::test]
+    async fn retries_and_disables_failing_webhooks() {
+        test_log_init();
+
+        let (url, receiver) = start_receiver(500);
+
+        test_state!(
+            state = [StoreWebhook {
+                collection_id: 1,
+                id: 2,
+                url,
+                secret: "secret".into(),
+                events: vec![],
+                enabled: true,
+                failures: 0,
+                principal_id: 0,
+                created_at: chrono::Utc::now(),
+            }]
+        );
+
+        WebhookDispatcher::new(state.clone(), policy()).start();
+
+        test_state!(
+            ::state = [StoreIdea {
+                collection: 1,
+                id: 3,
+                name: "Test Idea".into(),
+                description: "This is a test idea".into(),
+                tags: Default::default(),
+                completed: false,
+            }]
+        );
+
+        let deliveries = wait_for_deliveries(&state, 2, 3).await;
+        assert!(
+            deliveries
+                .iter()
+                .all(|d| !d.succeeded && d.status_code == Some(500))
+        );
+        assert_eq!(
+            deliveries.iter().map(|d| d.attempt).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(receiver.requests.lock().unwrap().len(), 3);
+
+        for _ in 0..200 {
+            let webhook = state
+                .store
+                .send(GetWebhook {
+                    collection_id: 1,
+                    id: 2,
+                })
+                .await
+                .expect("the actor should have run")
+                .expect("the webhook should exist");
+
+            if !webhook.enabled {
+                assert_eq!(webhook.failures, 1);
+                return;
+            }
+
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+
+        panic!("the webhook should have been disabled after failing repeatedly");
+    }
+
+    #[test]
+    fn signatures() {
+        assert_eq!(
+            sign("secret", b"{}"),
+            "sha256=77325902caca812dc259733aacd046b73817372c777b8d95b402647474516e13"
+        );
+    }
+}